@@ -0,0 +1,178 @@
+//! Per-phase costs of the star and system queries.
+//!
+//! Each query is split into the phases `MeiGalaxy` runs in order, so a
+//! regression can be pinned on MEI or on the bindings:
+//!
+//! - `generate`: MEI itself
+//! - `convert`: turning MEI output into `StructureData` or `Record`s,
+//!   including the `{:?}` star type names and derived `StarProperties`
+//! - `encode` / `decode`: the on-disk structure cache
+//! - `pack` / `pack_interleaved`: filling flat arrays the size of the packed
+//!   arrays handed to Godot in each `star_layout`
+//!
+//! Godot's own packed arrays and strings need a running engine, so `pack`
+//! measures the same copies into plain vectors; the in-engine cost is in the
+//! `get_structure` log line.
+//!
+//! ```text
+//! cargo bench --bench queries
+//! cargo bench --bench queries -- structure/convert
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mei::api::galaxy_api::GalaxyAPI;
+use mei::util::vec::Vec3;
+use mei_godot::naming::NameStyle;
+use mei_godot::records::{
+    generate_system, nearby_from_stars, structure_from_stars, summary_record, system_facts,
+    system_record,
+};
+use mei_godot::structure_cache::{decode, encode, StructureData, INTERLEAVED_STRIDE};
+
+const SEED: u64 = 42;
+
+/// Structure sizes: the viewer's default and a dense map.
+const STRUCTURE_SIZES: [i64; 2] = [10_000, 100_000];
+
+const NEARBY_RADIUS: f64 = 16.0;
+
+/// Flat copies of every packed array `get_structure` builds in the separate layout.
+///
+/// Only ever written: filling them is what gets measured.
+#[allow(dead_code)]
+struct Packed {
+    positions: Vec<[f32; 3]>,
+    ids: Vec<i64>,
+    luminosities: Vec<f32>,
+    temperatures: Vec<f32>,
+    masses: Vec<f32>,
+    radii: Vec<f32>,
+    absolute_magnitudes: Vec<f32>,
+    star_type_codes: Vec<u8>,
+    spectral_class_codes: Vec<u8>,
+}
+
+fn pack(data: &StructureData) -> Packed {
+    let count = data.stars.len();
+    let mut packed = Packed {
+        positions: vec![[0.0; 3]; count],
+        ids: vec![0; count],
+        luminosities: vec![0.0; count],
+        temperatures: vec![0.0; count],
+        masses: vec![0.0; count],
+        radii: vec![0.0; count],
+        absolute_magnitudes: vec![0.0; count],
+        star_type_codes: vec![0; count],
+        spectral_class_codes: vec![0; count],
+    };
+    for (i, star) in data.stars.iter().enumerate() {
+        packed.positions[i] = star.position;
+        packed.ids[i] = star.id;
+        packed.luminosities[i] = star.luminosity;
+        packed.temperatures[i] = star.temperature;
+        packed.masses[i] = star.mass;
+        packed.radii[i] = star.radius;
+        packed.absolute_magnitudes[i] = star.absolute_magnitude;
+        packed.star_type_codes[i] = star.star_type as u8;
+        packed.spectral_class_codes[i] = star.spectral_class as u8;
+    }
+    packed
+}
+
+/// The single array of the interleaved layout.
+fn pack_interleaved(data: &StructureData) -> Vec<f32> {
+    let mut out = vec![0.0; data.stars.len() * INTERLEAVED_STRIDE];
+    data.fill_interleaved(&mut out);
+    out
+}
+
+/// Center of the first structure star, so nearby queries hit a populated region.
+fn populated_center(api: &GalaxyAPI) -> Vec3 {
+    let star = &api.generator.get_galactic_structure(1)[0];
+    Vec3::new(star.position.x, star.position.y, star.position.z)
+}
+
+fn structure(c: &mut Criterion) {
+    let api = GalaxyAPI::new(SEED);
+    let mut group = c.benchmark_group("structure");
+    group.sample_size(10);
+
+    for max_stars in STRUCTURE_SIZES {
+        let stars = api.generator.get_galactic_structure(max_stars as usize);
+        let data = structure_from_stars(&stars, 0);
+        let bytes = encode(&data, 0);
+
+        group.bench_with_input(
+            BenchmarkId::new("generate", max_stars),
+            &max_stars,
+            |b, &n| b.iter(|| api.generator.get_galactic_structure(n as usize)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("convert", max_stars),
+            &stars,
+            |b, stars| b.iter(|| structure_from_stars(black_box(stars), 0)),
+        );
+        group.bench_with_input(BenchmarkId::new("encode", max_stars), &data, |b, data| {
+            b.iter(|| encode(black_box(data), 0))
+        });
+        group.bench_with_input(BenchmarkId::new("decode", max_stars), &bytes, |b, bytes| {
+            b.iter(|| decode(black_box(bytes), 0).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("pack", max_stars), &data, |b, data| {
+            b.iter(|| pack(black_box(data)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("pack_interleaved", max_stars),
+            &data,
+            |b, data| b.iter(|| pack_interleaved(black_box(data))),
+        );
+    }
+    group.finish();
+}
+
+fn nearby(c: &mut Criterion) {
+    let mut api = GalaxyAPI::new(SEED);
+    let center = populated_center(&api);
+    let stars = api
+        .generator
+        .get_nearby_stars(&center, NEARBY_RADIUS, 10_000);
+    let data = nearby_from_stars(&stars, [0.0; 3]);
+
+    let mut group = c.benchmark_group("nearby");
+    group.bench_function("generate", |b| {
+        b.iter(|| {
+            api.generator
+                .get_nearby_stars(&center, NEARBY_RADIUS, 10_000)
+        })
+    });
+    group.bench_function("convert", |b| {
+        b.iter(|| nearby_from_stars(black_box(&stars), [0.0; 3]))
+    });
+    group.bench_function("pack", |b| b.iter(|| pack(black_box(&data))));
+    group.finish();
+}
+
+fn system(c: &mut Criterion) {
+    let mut api = GalaxyAPI::new(SEED);
+    let center = populated_center(&api);
+    let id = (api.generator.get_nearby_stars(&center, NEARBY_RADIUS, 1)[0].id as i64).to_string();
+    let system = generate_system(&api, &id).unwrap();
+    let style = NameStyle::default();
+    let cell_size = api.generator.config.cell_size;
+
+    let mut group = c.benchmark_group("system");
+    group.bench_function("generate", |b| {
+        b.iter(|| generate_system(&api, black_box(&id)).unwrap())
+    });
+    group.bench_function("convert", |b| {
+        b.iter(|| system_record(SEED, &id, &style, black_box(&system), cell_size))
+    });
+    group.bench_function("summary", |b| {
+        b.iter(|| summary_record(SEED, &id, &style, black_box(&system), cell_size))
+    });
+    group.bench_function("facts", |b| b.iter(|| system_facts(black_box(&system))));
+    group.finish();
+}
+
+criterion_group!(benches, structure, nearby, system);
+criterion_main!(benches);
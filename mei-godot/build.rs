@@ -0,0 +1,37 @@
+//! Records which MEI build this crate is compiled against, so the structure
+//! disk cache is invalidated when MEI changes what it generates.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let lock = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock.display());
+
+    let source = fs::read_to_string(&lock)
+        .ok()
+        .and_then(|text| mei_package(&text))
+        .unwrap_or_default();
+    println!("cargo:rustc-env=MEI_SOURCE={}", source);
+}
+
+/// Version and source (including the git commit) of the MEI package in
+/// `Cargo.lock`. A path dependency only has a version, so local MEI edits
+/// still need `clear_structure_cache()`.
+fn mei_package(lock: &str) -> Option<String> {
+    lock.split("[[package]]")
+        .find(|block| {
+            block
+                .lines()
+                .any(|line| line.trim() == "name = \"matter-energy-information\"")
+        })
+        .map(|block| {
+            block
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with("version") || line.starts_with("source"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+}
@@ -0,0 +1,271 @@
+use std::f64::consts::TAU;
+
+use godot::classes::{IRefCounted, RefCounted};
+use godot::prelude::*;
+
+use crate::orbit::{true_anomaly_from_mean, OrbitalElements};
+use crate::rng::{hash_combine, DetRng};
+
+/// Asteroid type names, indexed by the codes in `sample_asteroids().types`.
+pub const ASTEROID_TYPE_NAMES: [&str; 3] = ["Carbonaceous", "Silicate", "Metallic"];
+
+/// Mean-motion resonances (asteroid:perturber period ratio) that clear Kirkwood gaps.
+const KIRKWOOD_RESONANCES: [(f64, f64); 4] = [(1.0, 3.0), (2.0, 5.0), (3.0, 7.0), (1.0, 2.0)];
+
+/// Samples individual asteroids from an asteroid belt for rendering.
+///
+/// MEI only stores belt totals and a few notable bodies, so the rest of the
+/// belt is drawn from a deterministic distribution seeded by the belt's
+/// `seed`. The same belt looks identical on every visit.
+#[derive(GodotClass)]
+#[class(base=RefCounted)]
+pub struct MeiAsteroidBelt {
+    base: Base<RefCounted>,
+    /// Deterministic belt seed
+    #[var]
+    seed: i64,
+    /// Inner radius in AU
+    #[var]
+    inner_radius: f64,
+    /// Outer radius in AU
+    #[var]
+    outer_radius: f64,
+    /// Largest body diameter in kilometers
+    #[var]
+    max_diameter: f64,
+    /// Orbital radius of the giant planet carving resonance gaps, in AU (0.0 if none)
+    #[var]
+    perturber_radius: f64,
+}
+
+#[godot_api]
+impl IRefCounted for MeiAsteroidBelt {
+    /// Initializes a Sol-like main belt.
+    fn init(base: Base<RefCounted>) -> Self {
+        Self {
+            base,
+            seed: 0,
+            inner_radius: 2.1,
+            outer_radius: 3.3,
+            max_diameter: 950.0,
+            perturber_radius: 5.2,
+        }
+    }
+}
+
+#[godot_api]
+impl MeiAsteroidBelt {
+    /// Creates a `MeiAsteroidBelt` from an asteroid belt dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `belt` - An asteroid belt `Dictionary` from `get_star_system`
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var belt = MeiAsteroidBelt.from_dict(system["asteroid_belts"][0])
+    /// var asteroids = belt.sample_asteroids(5000, 0)
+    /// ```
+    #[func]
+    fn from_dict(belt: Dictionary) -> Gd<Self> {
+        let get = |key: &str| belt.get(key).and_then(|v| v.try_to::<f64>().ok());
+        let max_diameter = belt
+            .get("largest_bodies")
+            .and_then(|v| v.try_to::<Array<Dictionary>>().ok())
+            .map(|bodies| {
+                bodies
+                    .iter_shared()
+                    .filter_map(|b| b.get("diameter").and_then(|d| d.try_to::<f64>().ok()))
+                    .fold(0.0, f64::max)
+            })
+            .filter(|d| *d > 0.0)
+            .unwrap_or(500.0);
+
+        Gd::from_init_fn(|base| Self {
+            base,
+            seed: belt
+                .get("seed")
+                .and_then(|v| v.try_to::<i64>().ok())
+                .unwrap_or(0),
+            inner_radius: get("inner_radius").unwrap_or(2.1),
+            outer_radius: get("outer_radius").unwrap_or(3.3),
+            max_diameter,
+            perturber_radius: get("perturber_radius").unwrap_or(0.0),
+        })
+    }
+
+    /// Samples asteroids from the belt.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Number of asteroids to generate (clamped to 1,000,000)
+    /// * `seed_offset` - Selects an independent subset, e.g. per LOD chunk
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `positions`: `PackedVector3Array` of positions in AU (XZ plane, Y up)
+    /// - `sizes`: `PackedFloat32Array` of diameters in kilometers
+    /// - `types`: `PackedByteArray` of indices into `type_names`
+    /// - `type_names`: `PackedStringArray` of asteroid type names
+    /// - `semi_major_axes`: `PackedFloat32Array` in AU
+    /// - `orbital_phases`: `PackedFloat32Array` of mean anomalies in radians
+    /// - `count`: Number of asteroids returned
+    #[func]
+    fn sample_asteroids(&self, count: i64, seed_offset: i64) -> Dictionary {
+        let params = BeltParams {
+            seed: hash_combine(self.seed as u64, seed_offset as u64),
+            inner_radius: self.inner_radius.min(self.outer_radius),
+            outer_radius: self.outer_radius.max(self.inner_radius),
+            max_diameter: self.max_diameter,
+            perturber_radius: self.perturber_radius,
+        };
+        let asteroids = params.sample(count.clamp(0, 1_000_000) as usize);
+
+        let positions: PackedVector3Array = asteroids
+            .iter()
+            .map(|a| {
+                Vector3::new(
+                    a.position[0] as f32,
+                    a.position[1] as f32,
+                    a.position[2] as f32,
+                )
+            })
+            .collect();
+        let sizes: PackedFloat32Array = asteroids.iter().map(|a| a.diameter as f32).collect();
+        let types: PackedByteArray = asteroids.iter().map(|a| a.asteroid_type).collect();
+        let axes: PackedFloat32Array = asteroids.iter().map(|a| a.semi_major_axis as f32).collect();
+        let phases: PackedFloat32Array = asteroids.iter().map(|a| a.mean_anomaly as f32).collect();
+        let type_names: PackedStringArray = ASTEROID_TYPE_NAMES
+            .iter()
+            .map(|n| GString::from(*n))
+            .collect();
+
+        let mut result = Dictionary::new();
+        result.set("positions", positions);
+        result.set("sizes", sizes);
+        result.set("types", types);
+        result.set("type_names", type_names);
+        result.set("semi_major_axes", axes);
+        result.set("orbital_phases", phases);
+        result.set("count", asteroids.len() as i64);
+        result
+    }
+
+    /// Gets the Kirkwood gap locations for this belt.
+    ///
+    /// # Returns
+    ///
+    /// `PackedFloat32Array` of gap radii in AU that fall inside the belt
+    #[func]
+    fn get_kirkwood_gaps(&self) -> PackedFloat32Array {
+        kirkwood_gaps(self.perturber_radius)
+            .into_iter()
+            .filter(|r| (self.inner_radius..=self.outer_radius).contains(r))
+            .map(|r| r as f32)
+            .collect()
+    }
+}
+
+/// Radii of the main resonance gaps for a perturber at `perturber_radius` AU.
+pub fn kirkwood_gaps(perturber_radius: f64) -> Vec<f64> {
+    if perturber_radius <= 0.0 {
+        return Vec::new();
+    }
+    // Kepler's third law: a ratio = period ratio ^ (2/3)
+    KIRKWOOD_RESONANCES
+        .iter()
+        .map(|(p, q)| perturber_radius * (p / q).powf(2.0 / 3.0))
+        .collect()
+}
+
+/// Everything belt sampling depends on.
+pub struct BeltParams {
+    pub seed: u64,
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub max_diameter: f64,
+    pub perturber_radius: f64,
+}
+
+/// One sampled asteroid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampledAsteroid {
+    pub position: [f64; 3],
+    pub diameter: f64,
+    pub asteroid_type: u8,
+    pub semi_major_axis: f64,
+    pub mean_anomaly: f64,
+}
+
+impl BeltParams {
+    /// Draws `count` asteroids. Asteroid `i` only depends on the seed and `i`,
+    /// so asking for more asteroids keeps the first ones unchanged.
+    pub fn sample(&self, count: usize) -> Vec<SampledAsteroid> {
+        let gaps = kirkwood_gaps(self.perturber_radius);
+        let width = self.outer_radius - self.inner_radius;
+        let min_diameter = 1.0_f64.min(self.max_diameter);
+
+        (0..count)
+            .map(|i| {
+                let mut rng = DetRng::new(hash_combine(self.seed, i as u64));
+
+                // Semi-major axis: peaked towards the middle of the belt, with
+                // resonant orbits rejected to open the gaps.
+                let mut a = self.inner_radius;
+                for _ in 0..16 {
+                    let u = (rng.next_f64() + rng.next_f64()) * 0.5;
+                    a = self.inner_radius + u * width;
+                    let cleared = gaps
+                        .iter()
+                        .map(|gap| (-((a - gap) / (gap * 0.012)).powi(2)).exp())
+                        .fold(0.0, f64::max);
+                    if rng.next_f64() >= cleared * 0.95 {
+                        break;
+                    }
+                }
+
+                // Collisional size distribution: N(>D) ~ D^-2.5
+                let ratio = (min_diameter / self.max_diameter).powf(2.5);
+                let diameter =
+                    min_diameter * (1.0 - rng.next_f64() * (1.0 - ratio)).powf(-1.0 / 2.5);
+
+                // Silicate-rich inner belt, carbonaceous outer belt, few metallic
+                let t = if width > 0.0 {
+                    (a - self.inner_radius) / width
+                } else {
+                    0.5
+                };
+                let roll = rng.next_f64();
+                let asteroid_type = if roll < 0.06 {
+                    2
+                } else if roll < 0.06 + 0.7 * (1.0 - t) + 0.05 {
+                    1
+                } else {
+                    0
+                };
+
+                let eccentricity = (0.07 * (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt()).min(0.35);
+                let inclination = (0.1 * (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt()).min(0.6);
+                let elements = OrbitalElements {
+                    semi_major_axis: a,
+                    eccentricity,
+                    inclination,
+                    longitude_of_ascending_node: rng.range(0.0, TAU),
+                    argument_of_periapsis: rng.range(0.0, TAU),
+                };
+                let mean_anomaly = rng.range(0.0, TAU);
+
+                SampledAsteroid {
+                    position: elements
+                        .position_at(true_anomaly_from_mean(mean_anomaly, eccentricity)),
+                    diameter,
+                    asteroid_type,
+                    semi_major_axis: a,
+                    mean_anomaly,
+                }
+            })
+            .collect()
+    }
+}
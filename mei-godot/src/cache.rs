@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A bounded least-recently-used cache with hit/miss counters.
+///
+/// Recency is tracked with a monotonically increasing tick per access, so
+/// lookups and evictions are `O(log n)`.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    /// Last-access tick -> key, oldest first
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// Creates an empty cache holding at most `capacity` entries (0 disables it).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, evicting the oldest entries if it shrank.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up an entry and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let Some((_, last)) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.tick += 1;
+        self.order.remove(last);
+        *last = self.tick;
+        self.order.insert(self.tick, key.clone());
+        self.hits += 1;
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Looks up an entry without counting a hit or miss or marking it as
+    /// used, for probes that do not fill the cache on a miss.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Inserts or replaces an entry, evicting the least recently used ones.
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.order.remove(&last);
+        }
+        self.order.insert(self.tick, key);
+        self.evict_to(self.capacity);
+    }
+
+    /// Drops every entry. Counters are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.values().map(|(value, _)| value)
+    }
+
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+            self.evictions += 1;
+        }
+    }
+}
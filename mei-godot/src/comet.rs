@@ -0,0 +1,298 @@
+use std::f64::consts::{PI, TAU};
+
+use godot::classes::RefCounted;
+use godot::prelude::*;
+
+use crate::orbit::{true_anomaly_from_hyperbolic_mean, true_anomaly_from_mean, OrbitalElements};
+use crate::rng::DetRng;
+
+/// Gravitational parameter of one solar mass in AU^3 / year^2.
+pub const GM_SUN: f64 = 4.0 * PI * PI;
+
+/// Distance in AU (for a Sun-like star) inside which water ice sublimates
+/// and a comet grows a coma and tail.
+pub const ACTIVITY_RADIUS_AU: f64 = 5.0;
+
+/// Comet motion and tail queries.
+///
+/// Takes comet dictionaries from an Oort cloud's `notable_comets`. Times are
+/// in years relative to the generation epoch (time 0).
+#[derive(GodotClass)]
+#[class(base=RefCounted, init)]
+pub struct MeiComet {
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl MeiComet {
+    /// Gets a comet's position, velocity and tail at a point in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `comet` - A comet `Dictionary` from `get_star_system`
+    /// * `time` - Years since the generation epoch
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `position`: `Vector3` in AU (XZ plane, Y up)
+    /// - `velocity`: `Vector3` in AU per year
+    /// - `sun_distance`: Distance from the star in AU
+    /// - `true_anomaly`: True anomaly in radians
+    /// - `is_active`: Whether the comet is close enough to have a tail
+    /// - `tail_direction`: `Vector3` unit vector of the ion tail (anti-sunward)
+    /// - `dust_tail_direction`: `Vector3` unit vector of the curved dust tail
+    /// - `tail_length`: Estimated tail length in AU (0.0 when inactive)
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var state = MeiComet.get_comet_state(comet, Time.get_ticks_msec() / 1000.0)
+    /// comet_node.position = state["position"] * SYSTEM_SCALE
+    /// ```
+    #[func]
+    fn get_comet_state(comet: Dictionary, time: f64) -> Dictionary {
+        let orbit = CometOrbit::from_dict(&comet);
+        let luminosity = dict_f64(&comet, "host_luminosity").unwrap_or(1.0);
+        let diameter = dict_f64(&comet, "nucleus_diameter").unwrap_or(10.0);
+
+        let state = orbit.state_at(time);
+        let tail = Tail::estimate(&state, diameter, luminosity);
+
+        let mut result = Dictionary::new();
+        result.set("position", to_vector3(state.position));
+        result.set("velocity", to_vector3(state.velocity));
+        result.set("sun_distance", state.distance);
+        result.set("true_anomaly", state.true_anomaly);
+        result.set("is_active", tail.length > 0.0);
+        result.set("tail_direction", to_vector3(tail.ion_direction));
+        result.set("dust_tail_direction", to_vector3(tail.dust_direction));
+        result.set("tail_length", tail.length);
+        result
+    }
+
+    /// Gets the time window a comet spends within a distance of its star.
+    ///
+    /// For periodic comets this is the passage around `time_of_perihelion`;
+    /// add multiples of `period` for other passages.
+    ///
+    /// # Arguments
+    ///
+    /// * `comet` - A comet `Dictionary` from `get_star_system`
+    /// * `radius` - Distance in AU
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` with `start` and `end` in years, or an empty
+    /// `Dictionary` if the comet never comes that close
+    #[func]
+    fn get_passage_window(comet: Dictionary, radius: f64) -> Dictionary {
+        let mut result = Dictionary::new();
+        if let Some((start, end)) = CometOrbit::from_dict(&comet).passage_window(radius) {
+            result.set("start", start);
+            result.set("end", end);
+        }
+        result
+    }
+}
+
+fn dict_f64(dict: &Dictionary, key: &str) -> Option<f64> {
+    dict.get(key).and_then(|v| v.try_to::<f64>().ok())
+}
+
+fn to_vector3(v: [f64; 3]) -> Vector3 {
+    Vector3::new(v[0] as f32, v[1] as f32, v[2] as f32)
+}
+
+/// Broad comet families, which differ in how their orbits are oriented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CometClass {
+    ShortPeriod,
+    LongPeriod,
+    Hyperbolic,
+}
+
+/// A comet orbit anchored in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CometOrbit {
+    pub elements: OrbitalElements,
+    /// Time of (the epoch's nearest) perihelion passage in years
+    pub time_of_perihelion: f64,
+    /// Gravitational parameter of the central mass in AU^3 / year^2
+    pub gm: f64,
+}
+
+/// Comet position and velocity at one instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CometState {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub distance: f64,
+    pub true_anomaly: f64,
+}
+
+impl CometOrbit {
+    /// Derives a full orbit from what MEI stores for a comet.
+    ///
+    /// MEI gives the semi-major axis and eccentricity; orientation and phase
+    /// are drawn from the comet's seed. Short-period comets stay near the
+    /// ecliptic, while Oort cloud comets arrive from any direction.
+    pub fn derive(
+        seed: u64,
+        class: CometClass,
+        semi_major_axis: f64,
+        eccentricity: f64,
+        central_mass: f64,
+    ) -> Self {
+        let mut rng = DetRng::new(seed);
+        let inclination = match class {
+            CometClass::ShortPeriod => (0.2 * (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt()).min(PI),
+            CometClass::LongPeriod | CometClass::Hyperbolic => rng.range(-1.0, 1.0).acos(),
+        };
+        let elements = OrbitalElements {
+            semi_major_axis: semi_major_axis.abs(),
+            eccentricity: eccentricity.max(0.0),
+            inclination,
+            longitude_of_ascending_node: rng.range(0.0, TAU),
+            argument_of_periapsis: rng.range(0.0, TAU),
+        };
+        let gm = GM_SUN * central_mass.max(0.01);
+
+        let mut orbit = Self {
+            elements,
+            time_of_perihelion: 0.0,
+            gm,
+        };
+        orbit.time_of_perihelion = match orbit.period() {
+            Some(period) => -rng.range(0.0, 1.0) * period,
+            // Unbound comets pass perihelion within a few centuries of the epoch
+            None => rng.range(-200.0, 200.0),
+        };
+        orbit
+    }
+
+    /// Reads an orbit back from a comet dictionary.
+    pub fn from_dict(dict: &Dictionary) -> Self {
+        Self {
+            elements: OrbitalElements::from_dict(dict),
+            time_of_perihelion: dict_f64(dict, "time_of_perihelion").unwrap_or(0.0),
+            gm: GM_SUN * dict_f64(dict, "central_mass").unwrap_or(1.0).max(0.01),
+        }
+    }
+
+    /// Whether the comet is on a closed orbit.
+    pub fn is_bound(&self) -> bool {
+        self.elements.eccentricity < 1.0
+    }
+
+    /// Orbital period in years, or `None` for unbound orbits.
+    pub fn period(&self) -> Option<f64> {
+        (self.is_bound() && self.elements.semi_major_axis > 0.0).then(|| TAU / self.mean_motion())
+    }
+
+    /// Mean motion in radians per year.
+    pub fn mean_motion(&self) -> f64 {
+        let a = self.elements.semi_major_axis.max(1e-9);
+        (self.gm / (a * a * a)).sqrt()
+    }
+
+    /// Position and velocity at a time in years.
+    pub fn state_at(&self, time: f64) -> CometState {
+        let mean_anomaly = self.mean_motion() * (time - self.time_of_perihelion);
+        let true_anomaly = if self.is_bound() {
+            true_anomaly_from_mean(mean_anomaly, self.elements.eccentricity)
+        } else {
+            true_anomaly_from_hyperbolic_mean(mean_anomaly, self.elements.eccentricity)
+        };
+        let position = self.elements.position_at(true_anomaly);
+
+        CometState {
+            position,
+            velocity: self.elements.velocity_at(true_anomaly, self.gm),
+            distance: length(position),
+            true_anomaly,
+        }
+    }
+
+    /// Start and end time of the perihelion passage within `radius` AU.
+    pub fn passage_window(&self, radius: f64) -> Option<(f64, f64)> {
+        let e = self.elements.eccentricity;
+        let a = self.elements.semi_major_axis;
+        if radius < self.elements.periapsis() || a <= 0.0 {
+            return None;
+        }
+
+        let half = if self.is_bound() {
+            if radius >= a * (1.0 + e) {
+                // Never leaves the sphere
+                self.period()? / 2.0
+            } else {
+                let ecc = ((1.0 - radius / a) / e.max(1e-9)).clamp(-1.0, 1.0).acos();
+                (ecc - e * ecc.sin()) / self.mean_motion()
+            }
+        } else {
+            let e = e.max(1.000_001);
+            let h = ((radius / a + 1.0) / e).max(1.0).acosh();
+            (e * h.sinh() - h) / self.mean_motion()
+        };
+        Some((
+            self.time_of_perihelion - half,
+            self.time_of_perihelion + half,
+        ))
+    }
+}
+
+/// Rough tail geometry for rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tail {
+    /// Straight ion tail, pointing directly away from the star
+    pub ion_direction: [f64; 3],
+    /// Dust tail, lagging behind along the orbit
+    pub dust_direction: [f64; 3],
+    /// Length in AU
+    pub length: f64,
+}
+
+impl Tail {
+    /// Estimates tail geometry from a comet's state.
+    ///
+    /// Activity starts inside [`ACTIVITY_RADIUS_AU`] (scaled by the square
+    /// root of the host luminosity) and the tail grows with the inverse
+    /// square of the distance, roughly like the sunlight driving it.
+    pub fn estimate(state: &CometState, nucleus_diameter: f64, luminosity: f64) -> Self {
+        let ion_direction = normalize(state.position);
+        let trailing = normalize(state.velocity.map(|v| -v));
+        let dust_direction = normalize([
+            ion_direction[0] * 0.7 + trailing[0] * 0.3,
+            ion_direction[1] * 0.7 + trailing[1] * 0.3,
+            ion_direction[2] * 0.7 + trailing[2] * 0.3,
+        ]);
+
+        let activity_radius = ACTIVITY_RADIUS_AU * luminosity.max(0.0).sqrt();
+        let length = if state.distance > 0.0 && state.distance < activity_radius {
+            let flux = luminosity / (state.distance * state.distance);
+            (0.1 * (nucleus_diameter.max(0.0) / 10.0).sqrt() * flux).min(2.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            ion_direction,
+            dust_direction,
+            length,
+        }
+    }
+}
+
+fn length(v: [f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let len = length(v);
+    if len > 0.0 {
+        v.map(|c| c / len)
+    } else {
+        [0.0; 3]
+    }
+}
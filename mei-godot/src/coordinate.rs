@@ -0,0 +1,259 @@
+//! Precise galactic coordinates for large-world rebasing.
+//!
+//! A `Vector3` holds 32-bit floats, which resolve only about 0.004 ly (250 AU)
+//! at the galaxy's edge. [`Coordinate`] instead splits a position into the
+//! integer designation sector and generator cell it falls in plus a small
+//! offset inside the cell, so offsets between two coordinates stay precise
+//! however far they are from the core.
+
+use godot::prelude::*;
+
+use crate::naming::{cells_per_sector, SectorCoord, SECTOR_SIZE_LY};
+
+/// A galactic position as sector and cell indices plus a local offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    pub sector: SectorCoord,
+    /// Cell within the sector along X, Y and Z, each in `0..cells_per_sector`
+    pub cell: [i64; 3],
+    /// Offset from the cell's minimum corner in light-years
+    pub local: [f64; 3],
+    /// Edge length of a cell in light-years
+    pub cell_size: f64,
+}
+
+impl Coordinate {
+    /// Coordinate of a galactic position in light-years.
+    pub fn from_galactic(position: [f64; 3], cell_size: f64) -> Self {
+        let sector = SectorCoord::containing(position);
+        let origin = sector.origin();
+        Self::from_sector_offset(
+            sector,
+            [
+                position[0] - origin[0],
+                position[1] - origin[1],
+                position[2] - origin[2],
+            ],
+            cell_size,
+        )
+    }
+
+    /// Coordinate at `offset` light-years from a sector's minimum corner. The
+    /// offset may lie outside the sector; it carries into the sector indices.
+    fn from_sector_offset(sector: SectorCoord, offset: [f64; 3], cell_size: f64) -> Self {
+        let cell_size = cell_size.max(1e-3);
+        let cells = cells_per_sector(cell_size) as i64;
+        let mut indices = [sector.x, sector.y, sector.z];
+        let mut cell = [0; 3];
+        let mut local = [0.0; 3];
+        for axis in 0..3 {
+            let carry = (offset[axis] / SECTOR_SIZE_LY).floor();
+            indices[axis] += carry as i64;
+            let within = offset[axis] - carry * SECTOR_SIZE_LY;
+            // Clamped like star numbers, so the last cell absorbs any remainder
+            cell[axis] = ((within / cell_size).floor() as i64).clamp(0, cells - 1);
+            local[axis] = within - cell[axis] as f64 * cell_size;
+        }
+        Self {
+            sector: SectorCoord {
+                x: indices[0],
+                y: indices[1],
+                z: indices[2],
+            },
+            cell,
+            local,
+            cell_size,
+        }
+    }
+
+    /// Minimum corner of the cell in light-years.
+    pub fn cell_origin(&self) -> [f64; 3] {
+        let origin = self.sector.origin();
+        [0, 1, 2].map(|axis| origin[axis] + self.cell[axis] as f64 * self.cell_size)
+    }
+
+    /// Galactic position in light-years.
+    pub fn galactic(&self) -> [f64; 3] {
+        let origin = self.cell_origin();
+        [0, 1, 2].map(|axis| origin[axis] + self.local[axis])
+    }
+
+    /// Offset from `origin` to this coordinate in light-years, computed from
+    /// the integer indices so it is precise at any distance from the core.
+    pub fn offset_from(&self, origin: &Coordinate) -> [f64; 3] {
+        let sectors = [
+            self.sector.x - origin.sector.x,
+            self.sector.y - origin.sector.y,
+            self.sector.z - origin.sector.z,
+        ];
+        [0, 1, 2].map(|axis| {
+            sectors[axis] as f64 * SECTOR_SIZE_LY
+                + (self.cell[axis] as f64 * self.cell_size
+                    - origin.cell[axis] as f64 * origin.cell_size)
+                + (self.local[axis] - origin.local[axis])
+        })
+    }
+
+    /// Coordinate `offset` light-years away.
+    pub fn translated(&self, offset: [f64; 3]) -> Self {
+        Self::from_sector_offset(
+            self.sector,
+            [0, 1, 2].map(|axis| {
+                self.cell[axis] as f64 * self.cell_size + self.local[axis] + offset[axis]
+            }),
+            self.cell_size,
+        )
+    }
+}
+
+/// A galactic position that stays precise far from the core.
+///
+/// Holds integer sector and cell indices plus a local offset in the cell.
+/// Use one as a floating origin: pass it to `MeiGalaxy.get_nearby_stars_relative`
+/// or `search_systems_relative` and place nodes at `relative_to()` offsets
+/// instead of raw galactic positions.
+#[derive(GodotClass)]
+#[class(base=RefCounted)]
+pub struct MeiCoordinate {
+    base: Base<RefCounted>,
+    coordinate: Coordinate,
+}
+
+#[godot_api]
+impl IRefCounted for MeiCoordinate {
+    /// Initializes the coordinate of the galactic center with 1 ly cells.
+    fn init(base: Base<RefCounted>) -> Self {
+        Self {
+            base,
+            coordinate: Coordinate::from_galactic([0.0; 3], 1.0),
+        }
+    }
+}
+
+#[godot_api]
+impl MeiCoordinate {
+    /// Creates the coordinate of a galactic position.
+    ///
+    /// `MeiGalaxy.get_coordinate` does the same with the galaxy's cell size.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate in light-years
+    /// * `y` - Y coordinate in light-years
+    /// * `z` - Z coordinate in light-years
+    /// * `cell_size` - Generator cell size in light-years
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var here = MeiCoordinate.from_galactic(41250.0, 12.5, -8000.0, 0.25)
+    /// ```
+    #[func]
+    pub fn from_galactic(x: f64, y: f64, z: f64, cell_size: f64) -> Gd<Self> {
+        Self::wrap(Coordinate::from_galactic([x, y, z], cell_size))
+    }
+
+    /// Gets the designation sector indices.
+    #[func]
+    fn get_sector(&self) -> Vector3i {
+        let sector = self.coordinate.sector;
+        Vector3i::new(sector.x as i32, sector.y as i32, sector.z as i32)
+    }
+
+    /// Gets the designation sector code, e.g. "NAMQ+3".
+    #[func]
+    fn get_sector_code(&self) -> GString {
+        self.coordinate.sector.code().into()
+    }
+
+    /// Gets the generator cell indices within the sector.
+    #[func]
+    fn get_cell(&self) -> Vector3i {
+        let [x, y, z] = self.coordinate.cell;
+        Vector3i::new(x as i32, y as i32, z as i32)
+    }
+
+    /// Gets the cell size in light-years.
+    #[func]
+    fn get_cell_size(&self) -> f64 {
+        self.coordinate.cell_size
+    }
+
+    /// Gets the offset from the cell's minimum corner in light-years.
+    #[func]
+    fn get_local(&self) -> Vector3 {
+        to_vector3(self.coordinate.local)
+    }
+
+    /// Gets the minimum corner of the cell in light-years.
+    ///
+    /// A multiple of the cell size, which for power-of-two sizes such as the
+    /// default 0.25 ly is exact in a `Vector3`.
+    #[func]
+    fn get_cell_origin(&self) -> Vector3 {
+        to_vector3(self.coordinate.cell_origin())
+    }
+
+    /// Gets the full galactic position as 64-bit floats.
+    ///
+    /// # Returns
+    ///
+    /// A `PackedFloat64Array` of x, y and z in light-years
+    #[func]
+    fn get_galactic(&self) -> PackedFloat64Array {
+        PackedFloat64Array::from(self.coordinate.galactic().as_slice())
+    }
+
+    /// Gets the offset from another coordinate to this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The coordinate to measure from
+    ///
+    /// # Returns
+    ///
+    /// The offset in light-years, precise however far both are from the core
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// star_node.position = star_coordinate.relative_to(camera_origin) * scale
+    /// ```
+    #[func]
+    fn relative_to(&self, origin: Gd<MeiCoordinate>) -> Vector3 {
+        to_vector3(self.coordinate.offset_from(&origin.bind().coordinate))
+    }
+
+    /// Gets the coordinate an offset away.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Offset in light-years
+    ///
+    /// # Returns
+    ///
+    /// A new `MeiCoordinate`; this one is unchanged
+    #[func]
+    fn translated(&self, offset: Vector3) -> Gd<Self> {
+        Self::wrap(
+            self.coordinate
+                .translated([offset.x as f64, offset.y as f64, offset.z as f64]),
+        )
+    }
+}
+
+impl MeiCoordinate {
+    /// The plain [`Coordinate`] this wraps.
+    pub fn coordinate(&self) -> &Coordinate {
+        &self.coordinate
+    }
+
+    /// Wraps a plain [`Coordinate`] for Godot.
+    pub fn wrap(coordinate: Coordinate) -> Gd<Self> {
+        Gd::from_init_fn(|base| Self { base, coordinate })
+    }
+}
+
+fn to_vector3(v: [f64; 3]) -> Vector3 {
+    Vector3::new(v[0] as f32, v[1] as f32, v[2] as f32)
+}
@@ -0,0 +1,372 @@
+//! Stellar ages and a simple single-star evolution model.
+//!
+//! MEI generates every star as it is today. The bindings give each star a
+//! deterministic age, drawn from the age spread of the galactic region it
+//! sits in and kept consistent with its current type, and an initial mass.
+//! [`evolve`] then follows the star through main sequence, giant and
+//! remnant phases with textbook scaling relations. It is meant for
+//! believable "time travel" views, not for accurate stellar tracks.
+
+use crate::morphology::{Morphology, Region};
+use crate::rng::{hash_combine, DetRng};
+use crate::stellar::{stellar_radius, StarProperties, SOLAR_TEMPERATURE};
+
+/// Main sequence lifetime of the Sun in years.
+pub const SOLAR_LIFETIME: f64 = 1.0e10;
+
+/// Lightest star that fuses hydrogen, in solar masses.
+pub const HYDROGEN_BURNING_LIMIT: f64 = 0.08;
+
+/// Lightest star that ends as a neutron star rather than a white dwarf.
+pub const NEUTRON_STAR_MIN_MASS: f64 = 8.0;
+
+/// Lightest star that ends as a black hole.
+pub const BLACK_HOLE_MIN_MASS: f64 = 25.0;
+
+/// Oldest a star can be: roughly the age of the universe, in years.
+pub const MAX_STELLAR_AGE: f64 = 1.35e10;
+
+/// Ends of the post-main-sequence phases, as fractions of the main
+/// sequence lifetime.
+const SUBGIANT_END: f64 = 1.05;
+const GIANT_END: f64 = 1.15;
+
+/// Salt separating age draws from other per-star hashes.
+const AGE_SALT: u64 = 0x5354_4152_4147_4531;
+
+/// Stage of a star's life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The star has not formed yet at the requested time
+    Unformed,
+    MainSequence,
+    Subgiant,
+    Giant,
+    Supergiant,
+    WhiteDwarf,
+    NeutronStar,
+    BlackHole,
+    /// Too light to fuse hydrogen; only cools
+    BrownDwarf,
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Unformed => "Unformed",
+            Phase::MainSequence => "MainSequence",
+            Phase::Subgiant => "Subgiant",
+            Phase::Giant => "Giant",
+            Phase::Supergiant => "Supergiant",
+            Phase::WhiteDwarf => "WhiteDwarf",
+            Phase::NeutronStar => "NeutronStar",
+            Phase::BlackHole => "BlackHole",
+            Phase::BrownDwarf => "BrownDwarf",
+        }
+    }
+}
+
+/// Where a star is in its life today.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StellarAge {
+    /// Years since the star formed
+    pub age: f64,
+    /// Mass at formation in solar masses
+    pub initial_mass: f64,
+    /// Region the age was drawn for
+    pub region: Region,
+}
+
+/// A star's state at some age.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvolvedStar {
+    pub phase: Phase,
+    /// Star type name in MEI's style, e.g. "MainSequenceG" or "RedGiant"
+    pub star_type: String,
+    /// Years since formation, negative if the star has not formed yet
+    pub age: f64,
+    pub mass: f64,
+    pub luminosity: f64,
+    pub temperature: f64,
+    pub radius: f64,
+}
+
+/// Main sequence lifetime in years.
+pub fn main_sequence_lifetime(mass: f64) -> f64 {
+    SOLAR_LIFETIME * mass.max(HYDROGEN_BURNING_LIMIT).powf(-2.5)
+}
+
+/// Age range in years that stars in a region are drawn from. Spiral arms
+/// are where stars form, so arm stars are young (log-uniform); the bulge,
+/// bar, thick disk and halo hold old populations.
+pub fn region_age_range(region: Region) -> (f64, f64) {
+    match region {
+        Region::Arm => (1.0e7, 1.0e9),
+        Region::Disk => (1.0e9, 1.0e10),
+        Region::ThickDisk => (7.0e9, 1.1e10),
+        Region::Bulge => (8.0e9, 1.2e10),
+        Region::Bar => (6.0e9, 1.1e10),
+        Region::Halo => (1.0e10, 1.3e10),
+    }
+}
+
+/// Deterministic age and initial mass of a star today.
+///
+/// Main sequence stars take their region's age, capped so they are still on
+/// the main sequence. Giants sit partway through their giant phase, and
+/// remnants are old enough for their progenitor to have died. Evolved stars
+/// and remnants get a progenitor massive enough to have got there within
+/// [`MAX_STELLAR_AGE`].
+pub fn stellar_age(galaxy_seed: u64, star: &StarProperties, morphology: &Morphology) -> StellarAge {
+    let region = morphology.region(star.position);
+    let mut rng = DetRng::new(hash_combine(
+        hash_combine(galaxy_seed, AGE_SALT),
+        star.id as u64,
+    ));
+    let (min, max) = region_age_range(region);
+    let region_age = if region == Region::Arm {
+        min * (max / min).powf(rng.next_f64())
+    } else {
+        rng.range(min, max)
+    };
+    let kind = star.star_type.as_str();
+
+    let (initial_mass, age) =
+        if kind.starts_with("WhiteDwarf") || kind.starts_with("CataclysmicVariable") {
+            // Initial-final mass relation, inverted
+            let cooling = white_dwarf_cooling_age(star.luminosity).min(0.9 * MAX_STELLAR_AGE);
+            let initial_mass = ((star.mass - 0.5) / 0.1)
+                .max(min_mass_reaching(GIANT_END, MAX_STELLAR_AGE - cooling))
+                .clamp(0.9, NEUTRON_STAR_MIN_MASS - 0.1);
+            (
+                initial_mass,
+                GIANT_END * main_sequence_lifetime(initial_mass) + cooling,
+            )
+        } else if ["NeutronStar", "Pulsar", "Magnetar"]
+            .iter()
+            .any(|t| kind.starts_with(t))
+        {
+            let initial_mass = rng.range(NEUTRON_STAR_MIN_MASS, BLACK_HOLE_MIN_MASS);
+            let death = GIANT_END * main_sequence_lifetime(initial_mass);
+            // Pulsars and magnetars spin down within a few million years
+            let young = if kind.starts_with("NeutronStar") {
+                region_age
+            } else {
+                1.0e6
+            };
+            (initial_mass, death + young.min(region_age).max(1.0e4))
+        } else if kind.starts_with("BlackHole") {
+            let initial_mass = (star.mass / 0.3).max(BLACK_HOLE_MIN_MASS);
+            (
+                initial_mass,
+                GIANT_END * main_sequence_lifetime(initial_mass) + region_age,
+            )
+        } else if let Some(phase) = evolved_phase(kind) {
+            // How far through its main sequence lifetime the star is
+            let progress = match phase {
+                Phase::Subgiant => (1.0 + SUBGIANT_END) / 2.0,
+                // Massive stars cross from blue to red
+                Phase::Supergiant if kind.starts_with("Blue") => {
+                    SUBGIANT_END + 0.25 * (GIANT_END - SUBGIANT_END)
+                }
+                Phase::Supergiant if kind.starts_with("Red") => {
+                    SUBGIANT_END + 0.75 * (GIANT_END - SUBGIANT_END)
+                }
+                _ => (SUBGIANT_END + GIANT_END) / 2.0,
+            };
+            let mut initial_mass = star.mass.max(min_mass_reaching(progress, MAX_STELLAR_AGE));
+            if phase == Phase::Supergiant {
+                initial_mass = initial_mass.max(NEUTRON_STAR_MIN_MASS);
+            }
+            (initial_mass, progress * main_sequence_lifetime(initial_mass))
+        } else if star.mass < HYDROGEN_BURNING_LIMIT || kind.starts_with("BrownDwarf") {
+            (star.mass, region_age)
+        } else {
+            let lifetime = main_sequence_lifetime(star.mass);
+            (star.mass, region_age.min(0.95 * lifetime))
+        };
+
+    StellarAge {
+        age: age.min(MAX_STELLAR_AGE),
+        initial_mass,
+        region,
+    }
+}
+
+/// Post-main-sequence phase of a MEI star type, `None` for main sequence
+/// stars, brown dwarfs and remnants.
+fn evolved_phase(kind: &str) -> Option<Phase> {
+    const EVOLVED_TYPES: [(&str, Phase); 8] = [
+        ("Subgiant", Phase::Subgiant),
+        ("RedGiant", Phase::Giant),
+        ("BlueGiant", Phase::Giant),
+        ("CarbonStar", Phase::Giant),
+        ("RedSupergiant", Phase::Supergiant),
+        ("YellowSupergiant", Phase::Supergiant),
+        ("BlueSupergiant", Phase::Supergiant),
+        ("WolfRayet", Phase::Supergiant),
+    ];
+    EVOLVED_TYPES
+        .iter()
+        .find(|(name, _)| kind.starts_with(name))
+        .map(|&(_, phase)| phase)
+}
+
+/// Lightest star that gets `progress` of the way through its main sequence
+/// lifetime within `years`.
+fn min_mass_reaching(progress: f64, years: f64) -> f64 {
+    (progress * SOLAR_LIFETIME / years.max(1.0)).powf(0.4)
+}
+
+/// Years a white dwarf needs to cool to `luminosity` (Mestel-like cooling).
+fn white_dwarf_cooling_age(luminosity: f64) -> f64 {
+    if luminosity <= 0.0 {
+        return 1.3e10;
+    }
+    (1.0e8 * (luminosity / 1.0e-2).powf(-1.0 / 1.4) - 1.0e7).max(0.0)
+}
+
+/// State of a star of `initial_mass` at `age` years, from the model alone.
+pub fn model_state(initial_mass: f64, age: f64) -> EvolvedStar {
+    let m = initial_mass;
+    let state = |phase, star_type: &str, mass, luminosity: f64, radius: f64| {
+        let temperature = if luminosity > 0.0 && radius > 0.0 {
+            SOLAR_TEMPERATURE * (luminosity / (radius * radius)).powf(0.25)
+        } else {
+            0.0
+        };
+        EvolvedStar {
+            phase,
+            star_type: star_type.to_string(),
+            age,
+            mass,
+            luminosity,
+            temperature,
+            radius,
+        }
+    };
+
+    if age < 0.0 {
+        return state(Phase::Unformed, "", 0.0, 0.0, 0.0);
+    }
+    if m < HYDROGEN_BURNING_LIMIT {
+        // Brown dwarfs fade as they contract
+        let luminosity = 1.0e-4 * (m / 0.05).powf(2.6) * (age.max(1.0e7) / 1.0e9).powf(-1.3);
+        return state(Phase::BrownDwarf, "BrownDwarf", m, luminosity, 0.1);
+    }
+
+    let lifetime = main_sequence_lifetime(m);
+    let zams_luminosity = if m < 0.43 {
+        0.23 * m.powf(2.3)
+    } else {
+        m.powf(3.5)
+    };
+    let zams_radius = m.powf(0.8);
+    let f = age / lifetime;
+    // Stars brighten and swell during the main sequence; the Sun started
+    // at about 70% of today's luminosity
+    let end_luminosity = zams_luminosity * 1.8;
+    let end_radius = zams_radius * 1.3;
+
+    if f < 1.0 {
+        let luminosity = zams_luminosity * (1.0 + 0.8 * f);
+        let radius = zams_radius * (1.0 + 0.3 * f);
+        let mut star = state(Phase::MainSequence, "", m, luminosity, radius);
+        star.star_type = main_sequence_type(star.temperature);
+        return star;
+    }
+    if f < SUBGIANT_END {
+        let x = (f - 1.0) / (SUBGIANT_END - 1.0);
+        return state(
+            Phase::Subgiant,
+            "Subgiant",
+            m,
+            end_luminosity * (1.0 + x),
+            end_radius * (1.0 + 2.0 * x),
+        );
+    }
+    if f < GIANT_END {
+        let y = (f - SUBGIANT_END) / (GIANT_END - SUBGIANT_END);
+        if m >= NEUTRON_STAR_MIN_MASS {
+            // Massive stars cross to the red at roughly constant luminosity
+            let luminosity = end_luminosity * 2.0 * (1.0 + 0.5 * y);
+            let temperature = 20_000.0 * (3_500.0_f64 / 20_000.0).powf(y);
+            let radius = luminosity.sqrt() * (SOLAR_TEMPERATURE / temperature).powi(2);
+            let star_type = if y < 0.5 {
+                "BlueSupergiant"
+            } else {
+                "RedSupergiant"
+            };
+            return state(Phase::Supergiant, star_type, m, luminosity, radius);
+        }
+        let luminosity = end_luminosity * 2.0 * 10f64.powf(2.5 * y);
+        let temperature = 4_800.0 - 1_300.0 * y;
+        let radius = luminosity.sqrt() * (SOLAR_TEMPERATURE / temperature).powi(2);
+        return state(Phase::Giant, "RedGiant", m, luminosity, radius);
+    }
+
+    let cooling = age - GIANT_END * lifetime;
+    if m >= BLACK_HOLE_MIN_MASS {
+        let mass = 0.3 * m;
+        let radius = stellar_radius("BlackHole", 0.0, 0.0, mass);
+        return state(Phase::BlackHole, "BlackHole", mass, 0.0, radius);
+    }
+    if m >= NEUTRON_STAR_MIN_MASS {
+        let radius = stellar_radius("NeutronStar", 0.0, 0.0, 1.4);
+        let temperature = 1.0e6 * ((cooling + 1.0e3) / 1.0e5).powf(-0.2).min(3.0);
+        let luminosity = radius * radius * (temperature / SOLAR_TEMPERATURE).powi(4);
+        let mut star = state(Phase::NeutronStar, "NeutronStar", 1.4, luminosity, radius);
+        star.temperature = temperature;
+        return star;
+    }
+    let mass = (0.5 + 0.1 * m).min(1.35);
+    let radius = 0.0127 * (mass / 0.6).powf(-1.0 / 3.0);
+    let luminosity = 1.0e-2 * ((cooling + 1.0e7) / 1.0e8).powf(-1.4);
+    state(Phase::WhiteDwarf, "WhiteDwarf", mass, luminosity, radius)
+}
+
+/// MEI-style main sequence type name for a temperature.
+fn main_sequence_type(temperature: f64) -> String {
+    let class = match temperature {
+        t if t >= 30_000.0 => "O",
+        t if t >= 10_000.0 => "B",
+        t if t >= 7_500.0 => "A",
+        t if t >= 6_000.0 => "F",
+        t if t >= 5_200.0 => "G",
+        t if t >= 3_700.0 => "K",
+        _ => "M",
+    };
+    format!("MainSequence{}", class)
+}
+
+/// State of a star `years_from_now` years in the future (negative for the
+/// past).
+///
+/// While the star stays in its current phase, the model's relative change is
+/// applied to today's values, so zero years returns the star exactly as MEI
+/// generated it. Across a phase change the model's values are used as they
+/// are.
+pub fn evolve(star: &StarProperties, age: &StellarAge, years_from_now: f64) -> EvolvedStar {
+    let now = model_state(age.initial_mass, age.age);
+    let then = model_state(age.initial_mass, age.age + years_from_now);
+    if then.phase != now.phase {
+        return then;
+    }
+
+    let ratio = |a: f64, b: f64| if b > 0.0 { a / b } else { 1.0 };
+    let luminosity = star.luminosity * ratio(then.luminosity, now.luminosity);
+    let temperature = star.temperature * ratio(then.temperature, now.temperature);
+    EvolvedStar {
+        phase: then.phase,
+        star_type: if then.star_type == now.star_type {
+            star.star_type.clone()
+        } else {
+            then.star_type
+        },
+        age: then.age,
+        mass: star.mass,
+        luminosity,
+        temperature,
+        radius: stellar_radius(&star.star_type, luminosity, temperature, star.mass),
+    }
+}
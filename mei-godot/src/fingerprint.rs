@@ -0,0 +1,104 @@
+use mei::generation::config::GeneratorConfig;
+
+use crate::rng::{hash_combine, hash_f64s, hash_str};
+
+/// Bump when the fingerprint recipe changes, or when a MEI update changes
+/// what the same seed and config generate.
+pub const FINGERPRINT_VERSION: u64 = 1;
+
+/// How a `GeneratorConfig` field influences results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldRole {
+    /// Changes which stars exist and what their systems contain
+    Generation,
+    /// Changes which stars `get_structure` samples, not the galaxy itself
+    Sampling,
+    /// Only limits queries
+    Query,
+}
+
+impl FieldRole {
+    /// Role name as reported to GDScript.
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldRole::Generation => "generation",
+            FieldRole::Sampling => "sampling",
+            FieldRole::Query => "query",
+        }
+    }
+}
+
+/// One `GeneratorConfig` field with its role.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfigField {
+    pub name: &'static str,
+    pub role: FieldRole,
+    pub value: f64,
+}
+
+/// Every `GeneratorConfig` field, in a fixed order.
+///
+/// The config is destructured without `..`, so a field added to MEI fails to
+/// compile here until it is given a role.
+pub fn config_fields(config: &GeneratorConfig) -> Vec<ConfigField> {
+    let GeneratorConfig {
+        cell_size,
+        star_probability_scale,
+        structure_block_size,
+        structure_samples_per_block,
+        scramble_stride,
+        nearby_max_radius,
+    } = config;
+    let field = |name, role, value| ConfigField { name, role, value };
+    vec![
+        field("cell_size", FieldRole::Generation, *cell_size),
+        field(
+            "star_probability_scale",
+            FieldRole::Generation,
+            *star_probability_scale,
+        ),
+        field(
+            "structure_block_size",
+            FieldRole::Sampling,
+            *structure_block_size,
+        ),
+        field(
+            "structure_samples_per_block",
+            FieldRole::Sampling,
+            *structure_samples_per_block as f64,
+        ),
+        field(
+            "scramble_stride",
+            FieldRole::Sampling,
+            *scramble_stride as f64,
+        ),
+        field(
+            "nearby_max_radius",
+            FieldRole::Query,
+            *nearby_max_radius,
+        ),
+    ]
+}
+
+/// Hashes the seed and every field with one of `roles`.
+///
+/// Field names are part of the hash, so reordering or renaming fields
+/// changes the fingerprint rather than silently colliding.
+pub fn fingerprint(seed: u64, fields: &[ConfigField], roles: &[FieldRole]) -> u64 {
+    fields
+        .iter()
+        .filter(|f| roles.contains(&f.role))
+        .fold(hash_combine(FINGERPRINT_VERSION, seed), |h, f| {
+            hash_f64s(hash_combine(h, hash_str(f.name)), &[f.value])
+        })
+}
+
+/// Fingerprint of the galaxy itself: seed plus generation-affecting fields.
+pub fn galaxy_fingerprint(seed: u64, config: &GeneratorConfig) -> u64 {
+    fingerprint(seed, &config_fields(config), &[FieldRole::Generation])
+}
+
+/// Text form of a fingerprint, e.g. `"mei1-0123456789abcdef"`.
+pub fn format_fingerprint(fingerprint: u64) -> String {
+    format!("mei{}-{:016x}", FINGERPRINT_VERSION, fingerprint)
+}
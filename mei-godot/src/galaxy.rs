@@ -1,9 +1,53 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 use godot::prelude::*;
-use godot::classes::Node;
-use mei::api::galaxy_api::{GalaxyAPI, SystemQuery};
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{DirAccess, FileAccess, Node};
+use rayon::prelude::*;
+use mei::api::galaxy_api::GalaxyAPI;
 use mei::generation::config::GeneratorConfig;
 use mei::util::vec::Vec3;
 
+use crate::cache::LruCache;
+use crate::coordinate::{Coordinate, MeiCoordinate};
+use crate::fingerprint::{config_fields, fingerprint, format_fingerprint, galaxy_fingerprint, FieldRole};
+use crate::morphology::Morphology;
+use crate::naming::{
+    catalog_designation, cell_center, cells_per_sector, edit_distance, moon_numeral, parse_designation,
+    planet_letter, star_number, DesignationError, DesignationQuery, MeiNameStyle, NameStyle, SectorCoord,
+};
+use crate::records::{
+    evolution_record, generate_system, lettered_planets, location_record, morphology_record, nearby_data,
+    planet_record, queried_star, record_to_dict, star_seed, structure_from_stars, summary_record, system_facts,
+    system_record, BodyContext, Record,
+};
+use crate::region::visit_nearest_first;
+use crate::rng::{hash_combine, hash_f64s, hash_str};
+use crate::rotation::{position_at_time, RotationCurve};
+use crate::search::{
+    name_code, SystemFacts, SystemFilter, CONFIGURATION_NAMES, FILTER_KEYS, NO_CODE, PLANET_TYPE_NAMES,
+};
+use crate::structure_cache::{
+    decode as decode_structure, encode as encode_structure, StructureData,
+    FORMAT_VERSION as STRUCTURE_FORMAT_VERSION, INTERLEAVED_STRIDE,
+};
+
+/// Systems generated per parallel batch in `search_systems`.
+const SEARCH_CHUNK_SIZE: usize = 256;
+
+/// Default `system_cache_size`.
+const DEFAULT_SYSTEM_CACHE_SIZE: i64 = 64;
+
+/// Most star positions `get_star_positions_at_time` remembers from the
+/// systems it generated; enough to animate the stars around a viewer
+/// without generating them again each frame.
+const STAR_POSITION_CAPACITY: usize = 4096;
+
+/// Rough cost of the Godot dictionaries built per body, for cache memory estimates.
+const DICT_BYTES_PER_BODY: usize = 1024;
+
 /// MEI Galaxy node for Godot - provides direct access to galaxy generation
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -11,7 +55,34 @@ pub struct MeiGalaxy {
     base: Base<Node>,
     #[var]
     seed: i64,
+    /// Syllable tables for star and sector names (built-in style if unset)
+    #[export]
+    name_style: Option<Gd<MeiNameStyle>>,
+    /// Number of generated star systems kept for repeat visits (0 disables caching)
+    #[export]
+    system_cache_size: i64,
+    /// Folder for cached `get_structure` results (empty disables the disk cache)
+    #[export]
+    structure_cache_dir: GString,
+    /// Array layout of star queries: `STAR_LAYOUT_SEPARATE` or `STAR_LAYOUT_INTERLEAVED`
+    #[export]
+    star_layout: i64,
     api: Option<GalaxyAPI>,
+    system_cache: LruCache<i64, Rc<CachedSystem>>,
+    /// Instance and version of the name style the cached dictionaries were
+    /// built with (`None` for the built-in style)
+    system_cache_style: Option<(InstanceId, u64)>,
+    /// Today's position of the stars `get_star_positions_at_time` most
+    /// recently generated
+    star_positions: LruCache<i64, [f64; 3]>,
+}
+
+/// A generated system together with its converted dictionary.
+struct CachedSystem {
+    system: mei::space_objects::system::StarSystem,
+    dict: Dictionary,
+    /// Estimated memory use of both
+    bytes: usize,
 }
 
 #[godot_api]
@@ -29,7 +100,14 @@ impl INode for MeiGalaxy {
         Self {
             base,
             seed: 0,
+            name_style: None,
+            system_cache_size: DEFAULT_SYSTEM_CACHE_SIZE,
+            structure_cache_dir: "user://mei_cache".into(),
+            star_layout: Self::STAR_LAYOUT_SEPARATE,
             api: None,
+            system_cache: LruCache::new(DEFAULT_SYSTEM_CACHE_SIZE as usize),
+            system_cache_style: None,
+            star_positions: LruCache::new(STAR_POSITION_CAPACITY),
         }
     }
 
@@ -38,6 +116,8 @@ impl INode for MeiGalaxy {
     /// Initializes the galaxy API with the current seed value.
     fn ready(&mut self) {
         self.api = Some(GalaxyAPI::new(self.seed as u64));
+        self.system_cache.clear();
+        self.star_positions.clear();
         godot_print!("MeiGalaxy initialized with seed {}", self.seed);
     }
 }
@@ -59,6 +139,8 @@ impl MeiGalaxy {
     fn set_galaxy_seed(&mut self, seed: i64) {
         self.seed = seed;
         self.api = Some(GalaxyAPI::new(self.seed as u64));
+        self.system_cache.clear();
+        self.star_positions.clear();
         godot_print!("MeiGalaxy seed changed to {}", self.seed);
     }
 
@@ -77,6 +159,8 @@ impl MeiGalaxy {
     fn load_config(&mut self, path: GString) {
         let config = GeneratorConfig::load_from_file(&path.to_string());
         self.api = Some(GalaxyAPI::new_with_config(self.seed as u64, config.clone()));
+        self.system_cache.clear();
+        self.star_positions.clear();
         godot_print!("MeiGalaxy config loaded from {}: nearby_max_radius={}, structure_block_size={}", 
             path, config.nearby_max_radius, config.structure_block_size);
     }
@@ -132,64 +216,144 @@ impl MeiGalaxy {
 
     /// Retrieves galactic structure as packed arrays for efficient rendering.
     ///
+    /// Results are cached in `structure_cache_dir`, keyed by the seed, the
+    /// generator config and `max_stars`, so later launches load them from disk.
+    /// Stale or corrupt cache files are ignored and rewritten.
+    ///
     /// # Arguments
     ///
-    /// * `max_stars` - Maximum number of stars to return
+    /// * `max_stars` - Maximum number of stars to return (negative values are an error and return none)
     ///
     /// # Returns
     ///
     /// A `Dictionary` containing:
-    /// - `positions`: `PackedVector3Array` of star positions
     /// - `ids`: `PackedInt64Array` of star IDs
+    /// - `star_type_names`: `PackedStringArray` of the star types present
+    /// - `spectral_class_names`: `PackedStringArray` of the spectral classes
+    ///   present, e.g. "G2V"
+    /// - `colors`: `PackedColorArray` of sRGB star colors from each star's blackbody spectrum
+    /// - `count`: Number of stars returned
+    /// - `estimated_total_stars`: Estimated total stars in galaxy
+    /// - `from_cache`: Whether the stars were loaded from `structure_cache_dir`
+    ///
+    /// With `star_layout` set to `STAR_LAYOUT_SEPARATE` (the default), also:
+    /// - `positions`: `PackedVector3Array` of star positions
     /// - `luminosities`: `PackedFloat32Array` of star luminosities
     /// - `temperatures`: `PackedFloat32Array` of star temperatures
     /// - `masses`: `PackedFloat32Array` of star masses
-    /// - `star_types`: `PackedStringArray` of star type names
-    /// - `count`: Number of stars returned
-    /// - `estimated_total_stars`: Estimated total stars in galaxy
+    /// - `radii`: `PackedFloat32Array` of star radii in solar radii
+    /// - `absolute_magnitudes`: `PackedFloat32Array` of bolometric absolute magnitudes
+    /// - `star_type_codes`: `PackedByteArray` of indices into `star_type_names`
+    /// - `spectral_class_codes`: `PackedInt32Array` of indices into `spectral_class_names`
+    ///
+    /// With `STAR_LAYOUT_INTERLEAVED`, instead:
+    /// - `stars`: `PackedFloat32Array` of `stride` floats per star: x, y, z,
+    ///   luminosity, temperature, mass, star type code, radius
+    /// - `stride`: Floats per star (8)
+    ///
+    /// The interleaved layout carries no spectral class codes, so there
+    /// `spectral_class_names` only lists the classes present; use the
+    /// separate layout to look up each star's class.
     #[func]
-    fn get_structure(&self, max_stars: i64) -> Dictionary {
+    fn get_structure(&mut self, max_stars: i64) -> Dictionary {
         let Some(api) = &self.api else {
             godot_error!("MeiGalaxy not initialized");
             return Dictionary::new();
         };
+        if max_stars < 0 {
+            godot_error!("get_structure: max_stars must not be negative, got {}", max_stars);
+        }
+        let max_stars = max_stars.max(0);
 
-        let stars = api.generator.get_galactic_structure(max_stars as usize);
-        let count = stars.len();
-        
-        let mut positions = PackedVector3Array::new();
-        let mut ids = PackedInt64Array::new();
-        let mut luminosities = PackedFloat32Array::new();
-        let mut temperatures = PackedFloat32Array::new();
-        let mut masses = PackedFloat32Array::new();
-        let mut star_types = PackedStringArray::new();
-        
-        for star in &stars {
-            positions.push(Vector3::new(
-                star.position.x as f32,
-                star.position.y as f32,
-                star.position.z as f32,
-            ));
-            ids.push(star.id as i64);
-            luminosities.push(star.luminosity() as f32);
-            temperatures.push(star.temperature() as f32);
-            masses.push(star.mass as f32);
-            star_types.push(&GString::from(format!("{:?}", star.star_type)));
-        }
-        
-        let estimated_total = api.generator.estimate_total_stars(500.0);
-        
-        let mut result = Dictionary::new();
-        result.set("positions", positions);
-        result.set("ids", ids);
-        result.set("luminosities", luminosities);
-        result.set("temperatures", temperatures);
-        result.set("masses", masses);
-        result.set("star_types", star_types);
-        result.set("count", count as i64);
-        result.set("estimated_total_stars", estimated_total as i64);
+        // Phase timings for the log line; see benches/queries.rs for the breakdown outside Godot
+        let started = Instant::now();
+        let (data, from_cache, phases) = match self.load_structure_cache(max_stars) {
+            Some(data) => {
+                let phases = format!("load {:.1} ms", ms(started.elapsed()));
+                (data, true, phases)
+            }
+            None => {
+                let stars = api.generator.get_galactic_structure(max_stars as usize);
+                let estimated_total = api.generator.estimate_total_stars(500.0) as i64;
+                let generated = started.elapsed();
+                let data = structure_from_stars(&stars, estimated_total);
+                let converted = started.elapsed();
+                self.save_structure_cache(max_stars, &data);
+                let phases = format!(
+                    "generate {:.1} ms, convert {:.1} ms, save {:.1} ms",
+                    ms(generated),
+                    ms(converted - generated),
+                    ms(started.elapsed() - converted)
+                );
+                (data, false, phases)
+            }
+        };
+        let count = data.stars.len();
 
-        godot_print!("Generated {} stars (packed), estimated total: {}", count, estimated_total);
+        let packing = Instant::now();
+        let mut result = packed_stars(&data, self.star_layout);
+        result.set("estimated_total_stars", data.estimated_total);
+        result.set("from_cache", from_cache);
+
+        godot_print!("{} {} stars (packed), estimated total: {} ({}, pack {:.1} ms)",
+            if from_cache { "Loaded" } else { "Generated" }, count, data.estimated_total,
+            phases, ms(packing.elapsed()));
+        result
+    }
+
+    /// Gets a stable fingerprint of the galaxy being generated.
+    ///
+    /// Covers the seed and every generation-affecting config field, so two
+    /// instances (saves, caches, multiplayer peers) with the same fingerprint
+    /// see the same stars and systems. Query-only fields such as
+    /// `nearby_max_radius` and structure sampling fields are excluded; see
+    /// `get_config_fields`.
+    ///
+    /// # Returns
+    ///
+    /// A fingerprint such as "mei1-0123456789abcdef", or an empty string if not initialized
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// if save_data["galaxy"] != galaxy.get_config_fingerprint():
+    ///     push_warning("Save was made in a different galaxy")
+    /// ```
+    #[func]
+    fn get_config_fingerprint(&self) -> GString {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return GString::new();
+        };
+        format_fingerprint(galaxy_fingerprint(self.seed as u64, &api.generator.config)).into()
+    }
+
+    /// Gets every generator config field and how it influences results.
+    ///
+    /// # Returns
+    ///
+    /// An `Array` of `Dictionary` entries containing:
+    /// - `name`: Field name as used in the TOML config
+    /// - `value`: Current value
+    /// - `role`: `"generation"` (changes stars and systems), `"sampling"`
+    ///   (changes which stars `get_structure` returns) or `"query"` (only limits queries)
+    /// - `affects_generation`: Whether the field is part of `get_config_fingerprint`
+    #[func]
+    fn get_config_fields(&self) -> Array<Dictionary> {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Array::new();
+        };
+
+        let mut result = Array::<Dictionary>::new();
+        for field in config_fields(&api.generator.config) {
+            let mut entry = Dictionary::new();
+            entry.set("name", field.name.to_godot());
+            entry.set("value", field.value);
+            entry.set("role", field.role.name().to_godot());
+            entry.set("affects_generation", field.role == FieldRole::Generation);
+            result.push(&entry);
+        }
         result
     }
 
@@ -206,6 +370,86 @@ impl MeiGalaxy {
         api.generator.galaxy.radius
     }
 
+    /// Gets the galaxy's large-scale shape.
+    ///
+    /// This is an approximation. MEI's density functions keep the shape
+    /// implicit and only expose the galaxy radius, so the bindings model it
+    /// with fixed Milky Way values (arm pitch, bar, bulge) scaled to that
+    /// radius. The arms are checked to run through overdense parts of the
+    /// generated structure, but they do not follow MEI's arms exactly. It is
+    /// the same model `describe_location`, `evolve_star` and
+    /// `get_star_positions_at_time` use.
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing (lengths in light-years, angles in degrees):
+    /// - `radius`: Disk radius
+    /// - `arm_count`: Number of spiral arms
+    /// - `arm_names`: `PackedStringArray` of arm names, by arm index
+    /// - `arm_pitch`: Pitch angle shared by the logarithmic arms
+    /// - `arm_start_radius`: Distance from the core where the arms start, at the ends of the bar
+    /// - `arm_half_width`: Half-width of an arm
+    /// - `bar_length`, `bar_width`: Full length and width of the central bar
+    /// - `bar_angle`: Angle of the bar from the +X axis towards +Z
+    /// - `bulge_radius`: Radius of the bulge
+    /// - `thin_disk_height`, `thick_disk_height`: Disk scale heights
+    ///
+    /// An empty `Dictionary` if not initialized.
+    #[func]
+    fn get_morphology(&self) -> Dictionary {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Dictionary::new();
+        };
+        record_to_dict(&morphology_record(&Morphology::milky_way(api.generator.galaxy.radius)))
+    }
+
+    /// Gets points along a spiral arm's center line.
+    ///
+    /// The points run in the disk plane from the end of the bar to the edge
+    /// of the disk, evenly spaced along the arm. Like the rest of
+    /// `get_morphology`, the arm is an approximation of where MEI's density
+    /// puts it, good for labels and overlays rather than exact placement.
+    ///
+    /// # Arguments
+    ///
+    /// * `arm_index` - Arm index, from 0 to `get_morphology()["arm_count"] - 1`
+    /// * `samples` - Number of points, at least 2
+    ///
+    /// # Returns
+    ///
+    /// A `PackedVector3Array` of positions in light-years, empty if not
+    /// initialized or the arguments are out of range
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var arm = galaxy.get_arm_spline(2, 64)
+    /// label.position = arm[arm.size() / 2] * galaxy_scale
+    /// label.text = galaxy.get_morphology()["arm_names"][2]
+    /// ```
+    #[func]
+    fn get_arm_spline(&self, arm_index: i64, samples: i64) -> PackedVector3Array {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return PackedVector3Array::new();
+        };
+        let morphology = Morphology::milky_way(api.generator.galaxy.radius);
+        if arm_index < 0 || arm_index as usize >= morphology.arm_count {
+            godot_error!("get_arm_spline: arm_index must be in 0..{}, got {}", morphology.arm_count, arm_index);
+            return PackedVector3Array::new();
+        }
+        if samples < 2 {
+            godot_error!("get_arm_spline: samples must be at least 2, got {}", samples);
+            return PackedVector3Array::new();
+        }
+        let points: Vec<Vector3> = morphology.arm_spline(arm_index as usize, samples as usize)
+            .into_iter()
+            .map(|p| Vector3::new(p[0] as f32, p[1] as f32, p[2] as f32))
+            .collect();
+        PackedVector3Array::from(points.as_slice())
+    }
+
     /// Gets nearby stars at a specific position as packed arrays.
     ///
     /// Uses a default maximum of 10,000 stars to match the Rust viewer's limit.
@@ -215,7 +459,7 @@ impl MeiGalaxy {
     /// * `x` - X coordinate in light-years
     /// * `y` - Y coordinate in light-years
     /// * `z` - Z coordinate in light-years
-    /// * `radius` - Search radius in light-years (clamped to `nearby_max_radius`)
+    /// * `radius` - Search radius in light-years (clamped to 0..`nearby_max_radius`)
     ///
     /// # Returns
     ///
@@ -232,62 +476,160 @@ impl MeiGalaxy {
     /// * `x` - X coordinate in light-years
     /// * `y` - Y coordinate in light-years
     /// * `z` - Z coordinate in light-years
-    /// * `radius` - Search radius in light-years (clamped to `nearby_max_radius`)
-    /// * `max_stars` - Maximum number of stars to return
+    /// * `radius` - Search radius in light-years (clamped to 0..`nearby_max_radius`)
+    /// * `max_stars` - Maximum number of stars to return (negative values are an error and return none)
     ///
     /// # Returns
     ///
     /// A `Dictionary` containing:
-    /// - `positions`: `PackedVector3Array` of star positions
     /// - `ids`: `PackedInt64Array` of star IDs
+    /// - `star_type_names`: `PackedStringArray` of the star types present
+    /// - `spectral_class_names`: `PackedStringArray` of the spectral classes
+    ///   present, e.g. "G2V"
+    /// - `colors`: `PackedColorArray` of sRGB star colors from each star's blackbody spectrum
+    /// - `count`: Number of stars returned
+    ///
+    /// With `star_layout` set to `STAR_LAYOUT_SEPARATE` (the default), also:
+    /// - `positions`: `PackedVector3Array` of star positions
     /// - `luminosities`: `PackedFloat32Array` of star luminosities
     /// - `temperatures`: `PackedFloat32Array` of star temperatures
     /// - `masses`: `PackedFloat32Array` of star masses
-    /// - `star_types`: `PackedStringArray` of star type names
-    /// - `count`: Number of stars returned
+    /// - `radii`: `PackedFloat32Array` of star radii in solar radii
+    /// - `absolute_magnitudes`: `PackedFloat32Array` of bolometric absolute magnitudes
+    /// - `star_type_codes`: `PackedByteArray` of indices into `star_type_names`
+    /// - `spectral_class_codes`: `PackedInt32Array` of indices into `spectral_class_names`
+    ///
+    /// With `STAR_LAYOUT_INTERLEAVED`, instead:
+    /// - `stars`: `PackedFloat32Array` of `stride` floats per star: x, y, z,
+    ///   luminosity, temperature, mass, star type code, radius
+    /// - `stride`: Floats per star (8)
+    ///
+    /// The interleaved layout carries no spectral class codes, so there
+    /// `spectral_class_names` only lists the classes present; use the
+    /// separate layout to look up each star's class.
     #[func]
     fn get_nearby_stars_limited(&mut self, x: f64, y: f64, z: f64, radius: f64, max_stars: i64) -> Dictionary {
-        let Some(api) = &mut self.api else {
-            godot_error!("MeiGalaxy not initialized");
+        match self.nearby_query([x, y, z], radius, max_stars, [0.0; 3]) {
+            Some(data) => packed_stars(&data, self.star_layout),
+            None => Dictionary::new(),
+        }
+    }
+
+    /// Gets nearby stars with positions relative to a floating origin.
+    ///
+    /// The origin is a `MeiCoordinate`, so it is never narrowed to 32 bits,
+    /// and positions are subtracted from it in double precision before they
+    /// are narrowed to `Vector3`. Stars far from the galactic core keep
+    /// their precision.
+    ///
+    /// `get_structure` samples the whole galaxy and stores 32-bit positions,
+    /// so it has no relative variant.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - Coordinate that results are relative to
+    /// * `offset` - Query center relative to `origin`, in light-years
+    /// * `radius` - Search radius in light-years (clamped to 0..`nearby_max_radius`)
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` with the same structure as `get_nearby_stars`, plus:
+    /// - `origin`: The `MeiCoordinate` positions are relative to
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var here = galaxy.get_coordinate(star_x, star_y, star_z)
+    /// var stars = galaxy.get_nearby_stars_relative(here, Vector3.ZERO, 50.0)
+    /// for position in stars["positions"]:
+    ///     add_marker(position * scale)
+    /// ```
+    #[func]
+    fn get_nearby_stars_relative(&mut self, origin: Gd<MeiCoordinate>, offset: Vector3, radius: f64) -> Dictionary {
+        let base = origin.bind().coordinate().galactic();
+        let center = [base[0] + offset.x as f64, base[1] + offset.y as f64, base[2] + offset.z as f64];
+        let Some(data) = self.nearby_query(center, radius, 10000, base) else {
             return Dictionary::new();
         };
+        let mut result = packed_stars(&data, self.star_layout);
+        result.set("origin", origin);
+        result
+    }
 
-        let position = Vec3::new(x, y, z);
-        let clamped_radius = radius.min(api.generator.config.nearby_max_radius);
-        let stars = api.generator.get_nearby_stars(&position, clamped_radius, max_stars as usize);
-        let count = stars.len();
-        
-        let mut positions = PackedVector3Array::new();
-        let mut ids = PackedInt64Array::new();
-        let mut luminosities = PackedFloat32Array::new();
-        let mut temperatures = PackedFloat32Array::new();
-        let mut masses = PackedFloat32Array::new();
-        let mut star_types = PackedStringArray::new();
-        
-        for star in &stars {
-            positions.push(Vector3::new(
-                star.position.x as f32,
-                star.position.y as f32,
-                star.position.z as f32,
-            ));
-            ids.push(star.id as i64);
-            luminosities.push(star.star_type.luminosity() as f32);
-            temperatures.push(star.star_type.temperature() as f32);
-            masses.push(star.mass as f32);
-            star_types.push(&GString::from(format!("{:?}", star.star_type)));
-        }
-        
-        let mut result = Dictionary::new();
-        result.set("positions", positions);
-        result.set("ids", ids);
-        result.set("luminosities", luminosities);
-        result.set("temperatures", temperatures);
-        result.set("masses", masses);
-        result.set("star_types", star_types);
-        result.set("count", count as i64);
+    /// Gets the precise coordinate of a galactic position.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - X coordinate in light-years
+    /// * `y` - Y coordinate in light-years
+    /// * `z` - Z coordinate in light-years
+    ///
+    /// # Returns
+    ///
+    /// A `MeiCoordinate` using the galaxy's generator cell size, or null if
+    /// not initialized
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var star_pos = system["position"]
+    /// var here = galaxy.get_coordinate(star_pos["x"], star_pos["y"], star_pos["z"])
+    /// print(here.get_sector_code())
+    /// ```
+    #[func]
+    fn get_coordinate(&self, x: f64, y: f64, z: f64) -> Option<Gd<MeiCoordinate>> {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return None;
+        };
+        Some(MeiCoordinate::wrap(Coordinate::from_galactic([x, y, z], api.generator.config.cell_size)))
+    }
 
-        godot_print!("Found {} nearby stars at ({:.1}, {:.1}, {:.1}) radius {} ly (clamped to {} ly)", count, x, y, z, radius, clamped_radius);
-        result
+    /// Describes where a position is in the galaxy, e.g. for a flight HUD.
+    ///
+    /// Regions, arms and metallicity are modeled: they come from
+    /// `get_morphology`, an approximation of the galaxy's shape, not from
+    /// MEI. Only the stellar density is sampled, by counting the stars MEI
+    /// generates within 10 ly (or `nearby_max_radius`, if smaller) of the
+    /// position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Galactic position in light-years
+    /// * `reference` - Observer position longitude and latitude are measured from
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `radius`: Distance from the galactic axis in light-years
+    /// - `height`: Height above (positive) or below the disk plane in light-years
+    /// - `longitude`, `latitude`: Galactic coordinates in degrees as seen from `reference`
+    /// - `distance`: Distance from `reference` in light-years
+    /// - `region` (modeled): "Bulge", "Bar", "Arm", "Disk" (between the arms), "ThickDisk" or "Halo"
+    /// - `arm` (modeled): Index of the nearest spiral arm, -1 inside the bar
+    /// - `arm_name` (modeled): Name of the nearest arm, e.g. "Perseus" (empty inside the bar)
+    /// - `arm_distance` (modeled): Distance to the nearest arm's center line in light-years,
+    ///   -1 inside the bar
+    /// - `sector`: Designation sector code
+    /// - `metallicity` (modeled): Estimated [Fe/H] in dex, 0 for solar
+    /// - `stellar_density` (sampled): Stars per cubic light-year
+    ///
+    /// An empty `Dictionary` if not initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var here = galaxy.describe_location(camera_position, home_position)
+    /// hud.text = "%s, %.0f ly from the %s arm" % [here["region"], here["arm_distance"], here["arm_name"]]
+    /// ```
+    #[func]
+    fn describe_location(&mut self, position: Vector3, reference: Vector3) -> Dictionary {
+        let Some(api) = &mut self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Dictionary::new();
+        };
+        let to_array = |v: Vector3| [v.x as f64, v.y as f64, v.z as f64];
+        record_to_dict(&location_record(api, to_array(position), to_array(reference)))
     }
 
     /// Retrieves a detailed star system by star ID.
@@ -300,10 +642,12 @@ impl MeiGalaxy {
     ///
     /// A `Dictionary` containing complete system information:
     /// - `star_id`: The queried star ID
-    /// - `position`: System position in galactic coordinates
-    /// - `stars`: Array of star data (mass, luminosity, temperature, type)
+    /// - `position`: System position in galactic coordinates (light-years)
+    /// - `stars`: Array of star data (type, spectral class, mass, luminosity, temperature,
+    ///   radius, absolute magnitude, color, galactic `position` in light-years)
     /// - `configuration`: Stellar configuration (Single, Binary, Triple, etc.)
-    /// - `stellar_components`: Individual stellar components with their planets
+    /// - `stellar_components`: Individual stellar components with their planets, around
+    ///   their `barycenter` (AU)
     /// - `inner_planets`: Rocky planets inside frost line
     /// - `outer_planets`: Gas/ice giants beyond frost line
     /// - `asteroid_belts`: Asteroid belt data
@@ -311,398 +655,1236 @@ impl MeiGalaxy {
     /// - `frost_line`: Frost line distance in AU
     /// - `habitable_zone_inner`: Inner edge of habitable zone in AU
     /// - `habitable_zone_outer`: Outer edge of habitable zone in AU
+    ///
+    /// Positions of barycenters, planets, moons, asteroids and comets are in
+    /// the system local frame: MEI's ecliptic turned Y up, the frame
+    /// `MeiOrbit` draws orbits in.
+    ///
+    /// An empty `Dictionary` if the ID is not a star ID or generation failed.
+    #[func]
+    fn get_star_system(&mut self, star_id: GString) -> Dictionary {
+        let cached = match self.cached_system(&star_id.to_string()) {
+            Ok(cached) => cached,
+            Err(error) => {
+                godot_error!("{}", error);
+                return Dictionary::new();
+            }
+        };
+
+        // Hand out a copy so callers can't edit the cached tree
+        cached.dict.duplicate_deep()
+    }
+
+    /// Evolves a star forwards or backwards in time.
+    ///
+    /// Every star gets a deterministic age from where it sits in the galaxy:
+    /// spiral arm stars are young, bulge, thick disk and halo stars old.
+    /// Zero years returns the star as generated; other times follow a simple
+    /// evolution model through giant and remnant phases, so e.g. a white
+    /// dwarf's past shows the star it used to be.
+    ///
+    /// # Arguments
+    ///
+    /// * `star_id` - The unique identifier for the star
+    /// * `years_from_now` - Years into the future, negative for the past
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `id`: The star's ID
+    /// - `years_from_now`: The requested offset
+    /// - `region`: Galactic region the age was drawn for ("Bulge", "Bar", "Arm", "Disk", "ThickDisk", "Halo")
+    /// - `initial_mass`: Mass at formation in solar masses
+    /// - `current_age`: Age today in years
+    /// - `age`: Age at the requested time in years (negative before the star formed)
+    /// - `phase`: "Unformed", "MainSequence", "Subgiant", "Giant", "Supergiant",
+    ///   "WhiteDwarf", "NeutronStar", "BlackHole" or "BrownDwarf"
+    /// - `exists`: Whether the star has formed by then
+    /// - `star_type`, `spectral_class`, `mass`, `luminosity`, `temperature`, `radius`,
+    ///   `absolute_magnitude`, `color`: The star's values at that time
+    ///
+    /// An empty `Dictionary` if the ID is not a star ID or generation failed.
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var future = galaxy.evolve_star(star_id, 5.0e9)
+    /// if future["phase"] == "WhiteDwarf":
+    ///     print("Ends as a white dwarf of %.2f solar masses" % future["mass"])
+    /// ```
     #[func]
-    fn get_star_system(&self, star_id: GString) -> Dictionary {
+    fn evolve_star(&mut self, star_id: GString, years_from_now: f64) -> Dictionary {
+        let cached = match self.cached_system(&star_id.to_string()) {
+            Ok(cached) => cached,
+            Err(error) => {
+                godot_error!("{}", error);
+                return Dictionary::new();
+            }
+        };
         let Some(api) = &self.api else {
-            godot_error!("MeiGalaxy not initialized");
             return Dictionary::new();
         };
 
-        let query = SystemQuery {
-            star_id: star_id.to_string(),
-            position: None,
+        let Some(star) = queried_star(&cached.system, &star_id.to_string()) else {
+            godot_error!("Star system {} has no stars", star_id);
+            return Dictionary::new();
         };
 
-        let system = api.get_star_system(&query);
+        record_to_dict(&evolution_record(self.seed as u64, star, api.generator.galaxy.radius, years_from_now))
+    }
 
-        let mut result = Dictionary::new();
-        result.set("star_id", star_id.clone());
-        result.set("frost_line", system.frost_line);
-        result.set("habitable_zone_inner", system.habitable_zone_inner);
-        result.set("habitable_zone_outer", system.habitable_zone_outer);
-        
-        // Position
-        let mut pos = Dictionary::new();
-        pos.set("x", system.position.x);
-        pos.set("y", system.position.y);
-        pos.set("z", system.position.z);
-        result.set("position", pos);
-        
-        // Stars (can be multiple in binary/trinary systems)
-        let mut stars_arr = Array::<Dictionary>::new();
-        for star in &system.stars {
-            let mut star_dict = Dictionary::new();
-            star_dict.set("id", star.id as i64);
-            star_dict.set("star_type", format!("{:?}", star.star_type).to_godot());
-            star_dict.set("mass", star.mass);
-            star_dict.set("luminosity", star.star_type.luminosity());
-            star_dict.set("temperature", star.star_type.temperature());
-            
-            let mut star_pos = Dictionary::new();
-            star_pos.set("x", star.position.x);
-            star_pos.set("y", star.position.y);
-            star_pos.set("z", star.position.z);
-            star_dict.set("position", star_pos);
-            
-            stars_arr.push(&star_dict);
-        }
-        result.set("stars", stars_arr);
-        
-        // Stellar configuration
-        let config_dict = match &system.configuration {
-            mei::space_objects::system::StellarConfiguration::Single => {
-                let mut d = Dictionary::new();
-                d.set("type", "Single".to_godot());
-                d
-            }
-            mei::space_objects::system::StellarConfiguration::CloseBinary { separation_au, is_contact } => {
-                let mut d = Dictionary::new();
-                d.set("type", "CloseBinary".to_godot());
-                d.set("separation_au", *separation_au);
-                d.set("is_contact", *is_contact);
-                d
-            }
-            mei::space_objects::system::StellarConfiguration::WideBinary { separation_au } => {
-                let mut d = Dictionary::new();
-                d.set("type", "WideBinary".to_godot());
-                d.set("separation_au", *separation_au);
-                d
-            }
-            mei::space_objects::system::StellarConfiguration::HierarchicalTriple { inner_separation_au, outer_separation_au } => {
-                let mut d = Dictionary::new();
-                d.set("type", "HierarchicalTriple".to_godot());
-                d.set("inner_separation_au", *inner_separation_au);
-                d.set("outer_separation_au", *outer_separation_au);
-                d
-            }
-            mei::space_objects::system::StellarConfiguration::UnstableTriple => {
-                let mut d = Dictionary::new();
-                d.set("type", "UnstableTriple".to_godot());
-                d
-            }
+    /// Gets where stars are at another epoch as the galaxy rotates.
+    ///
+    /// The disk follows a rotation curve that is solid-body in the bulge and
+    /// flat (220 km/s) beyond it, so inner stars lap outer ones and the arms
+    /// shear. Each star also has a deterministic peculiar velocity, larger in
+    /// old populations, around its circular orbit. Positions depend only on
+    /// the seed, the star and the time, so every viewer agrees on them.
+    ///
+    /// Today's position of each star comes from its own system, read from
+    /// the system cache or generated, never from earlier star queries. The
+    /// last few thousand positions are remembered, so animating a
+    /// neighbourhood only generates its systems once; animating a whole
+    /// `get_structure` result generates every system in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Star IDs, e.g. `get_structure()["ids"]`
+    /// * `time_myr` - Millions of years from now, negative for the past
+    ///
+    /// # Returns
+    ///
+    /// A `PackedVector3Array` of positions in light-years, one per ID in the
+    /// same order. IDs that are not star IDs map to `Vector3(NAN, NAN, NAN)`.
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var stars = galaxy.get_nearby_stars(x, y, z, 15.0)
+    /// # One full turn of the solar neighbourhood
+    /// var later = galaxy.get_star_positions_at_time(stars["ids"], 230.0)
+    /// ```
+    #[func]
+    fn get_star_positions_at_time(&mut self, ids: PackedInt64Array, time_myr: f64) -> PackedVector3Array {
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return PackedVector3Array::new();
         };
-        result.set("configuration", config_dict);
-        
-        // Stellar components (each can have planets orbiting)
-        let mut components_arr = Array::<Dictionary>::new();
-        for component in &system.stellar_components {
-            let mut comp_dict = Dictionary::new();
-            
-            // Star indices in this component
-            let mut indices = PackedInt64Array::new();
-            for idx in &component.star_indices {
-                indices.push(*idx as i64);
-            }
-            comp_dict.set("star_indices", indices);
-            
-            // Barycenter position (AU)
-            let mut bary = Dictionary::new();
-            bary.set("x", component.barycenter.x);
-            bary.set("y", component.barycenter.y);
-            bary.set("z", component.barycenter.z);
-            comp_dict.set("barycenter", bary);
-            
-            comp_dict.set("combined_mass", component.combined_mass);
-            comp_dict.set("internal_separation", component.internal_separation);
-            comp_dict.set("is_interacting", component.is_interacting);
-            comp_dict.set("planet_inner_limit", component.planet_inner_limit);
-            comp_dict.set("planet_outer_limit", component.planet_outer_limit);
-            comp_dict.set("frost_line", component.frost_line);
-            comp_dict.set("habitable_zone_inner", component.habitable_zone_inner);
-            comp_dict.set("habitable_zone_outer", component.habitable_zone_outer);
-            
-            // Inner planets for this component
-            let mut inner = Array::<Dictionary>::new();
-            for planet in &component.inner_planets {
-                inner.push(&planet_to_dict(planet));
-            }
-            comp_dict.set("inner_planets", inner);
-            
-            // Outer planets for this component
-            let mut outer = Array::<Dictionary>::new();
-            for planet in &component.outer_planets {
-                outer.push(&planet_to_dict(planet));
-            }
-            comp_dict.set("outer_planets", outer);
-            
-            components_arr.push(&comp_dict);
-        }
-        result.set("stellar_components", components_arr);
 
-        // Inner planets
-        let mut inner_planets = Array::<Dictionary>::new();
-        for planet in &system.inner_planets {
-            inner_planets.push(&planet_to_dict(planet));
-        }
-        result.set("inner_planets", inner_planets);
-
-        // Outer planets
-        let mut outer_planets = Array::<Dictionary>::new();
-        for planet in &system.outer_planets {
-            outer_planets.push(&planet_to_dict(planet));
-        }
-        result.set("outer_planets", outer_planets);
-
-        // Asteroid belts
-        let mut asteroid_belts = Array::<Dictionary>::new();
-        for belt in &system.asteroid_belts {
-            asteroid_belts.push(&asteroid_belt_to_dict(belt));
+        // The star's own position (its primary's if MEI renumbered it)
+        let star_position = |system: &mei::space_objects::system::StarSystem, id: i64| {
+            let position = queried_star(system, &id.to_string()).map_or(&system.position, |s| &s.position);
+            [position.x, position.y, position.z]
+        };
+        let known: Vec<Option<[f64; 3]>> = ids.as_slice().iter()
+            .map(|id| {
+                self.star_positions.get(id).copied().or_else(|| {
+                    self.system_cache.peek(id).map(|cached| star_position(&cached.system, *id))
+                })
+            })
+            .collect();
+        let mut missing: Vec<i64> = ids.as_slice().iter()
+            .zip(&known)
+            .filter(|(_, known)| known.is_none())
+            .map(|(id, _)| *id)
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        let generated: HashMap<i64, [f64; 3]> = missing
+            .par_iter()
+            .filter_map(|&id| {
+                let system = generate_system(api, &id.to_string()).ok()?;
+                Some((id, star_position(&system, id)))
+            })
+            .collect();
+        if generated.len() < missing.len() {
+            godot_error!("get_star_positions_at_time: {} of {} IDs are not stars",
+                missing.len() - generated.len(), ids.len());
         }
-        result.set("asteroid_belts", asteroid_belts);
-
-        // Oort cloud (if present)
-        if let Some(oort) = &system.oort_cloud {
-            result.set("oort_cloud", oort_cloud_to_dict(oort));
+        for (&id, &position) in &generated {
+            self.star_positions.insert(id, position);
         }
+        let positions: Vec<Option<[f64; 3]>> = ids.as_slice().iter()
+            .zip(known)
+            .map(|(id, known)| known.or_else(|| generated.get(id).copied()))
+            .collect();
 
-        let total_planets = system.inner_planets.len() + system.outer_planets.len();
-        let total_moons: usize = system.inner_planets.iter().chain(system.outer_planets.iter())
-            .map(|p| p.moons.len()).sum();
-        godot_print!("System has {} stars, {} planets, {} moons, {} asteroid belts", 
-            system.stars.len(), total_planets, total_moons, system.asteroid_belts.len());
-        result
+        let morphology = Morphology::milky_way(api.generator.galaxy.radius);
+        let curve = RotationCurve::milky_way(&morphology);
+        let seed = self.seed as u64;
+        let moved: Vec<Vector3> = ids
+            .as_slice()
+            .par_iter()
+            .zip(positions)
+            .map(|(id, position)| match position {
+                Some(position) => {
+                    let p = position_at_time(seed, *id, position, &morphology, &curve, time_myr);
+                    Vector3::new(p[0] as f32, p[1] as f32, p[2] as f32)
+                }
+                None => Vector3::new(f32::NAN, f32::NAN, f32::NAN),
+            })
+            .collect();
+        PackedVector3Array::from(moved.as_slice())
     }
-}
 
-/// Converts a planet to a Godot Dictionary.
-///
-/// # Arguments
-///
-/// * `planet` - Reference to the planet object
-///
-/// # Returns
-///
-/// A `Dictionary` containing planet data:
-/// - `planet_type`: Type of planet (Terrestrial, GasGiant, IceGiant, Dwarf)
-/// - `mass`: Planet mass in Earth masses
-/// - `orbital_radius`: Distance from star in AU
-/// - `position`: 3D position vector
-/// - `moons`: Array of moon dictionaries
-/// - `moon_count`: Number of moons
-fn planet_to_dict(planet: &mei::space_objects::planet::Planet) -> Dictionary {
-    let mut dict = Dictionary::new();
-    
-    // Map planet type to string
-    let planet_type_str = match planet.planet_type {
-        mei::space_objects::planet::PlanetType::Dwarf => "Dwarf",
-        mei::space_objects::planet::PlanetType::Terrestrial => "Terrestrial",
-        mei::space_objects::planet::PlanetType::SuperEarth => "SuperEarth",
-        mei::space_objects::planet::PlanetType::Desert => "Desert",
-        mei::space_objects::planet::PlanetType::Ocean => "Ocean",
-        mei::space_objects::planet::PlanetType::Lava => "Lava",
-        mei::space_objects::planet::PlanetType::MiniNeptune => "MiniNeptune",
-        mei::space_objects::planet::PlanetType::SubNeptune => "SubNeptune",
-        mei::space_objects::planet::PlanetType::IceGiant => "IceGiant",
-        mei::space_objects::planet::PlanetType::GasGiant => "GasGiant",
-        mei::space_objects::planet::PlanetType::HotJupiter => "HotJupiter",
-        mei::space_objects::planet::PlanetType::Chthonian => "Chthonian",
-        mei::space_objects::planet::PlanetType::Carbon => "Carbon",
-        mei::space_objects::planet::PlanetType::Coreless => "Coreless",
-    };
-    dict.set("planet_type", planet_type_str.to_godot());
-    dict.set("mass", planet.mass);
-    dict.set("orbital_radius", planet.position.x); // x position is orbital radius in AU
-    
-    let mut pos = Dictionary::new();
-    pos.set("x", planet.position.x);
-    pos.set("y", planet.position.y);
-    pos.set("z", planet.position.z);
-    dict.set("position", pos);
-    
-    // Moons with full detail (using same pattern as stars_arr which works)
-    let mut moons_arr = Array::<Dictionary>::new();
-    for moon in &planet.moons {
-        moons_arr.push(&moon_to_dict(moon));
+    /// Gets the procedural proper name of a star.
+    ///
+    /// Names depend only on the galaxy seed, the star ID and `name_style`,
+    /// so this does not generate the system.
+    ///
+    /// # Arguments
+    ///
+    /// * `star_id` - The unique identifier for the star
+    ///
+    /// # Returns
+    ///
+    /// The star's proper name, e.g. "Velonis"
+    #[func]
+    fn get_star_name(&self, star_id: GString) -> GString {
+        self.name_style().proper_name(star_seed(self.seed as u64, &star_id.to_string())).into()
     }
-    dict.set("moons", moons_arr);
-    dict.set("moon_count", planet.moons.len() as i64);
-    dict
-}
 
-/// Converts a moon to a Godot Dictionary.
-///
-/// # Arguments
-///
-/// * `moon` - Reference to the moon object
-///
-/// # Returns
-///
-/// A `Dictionary` containing moon data:
-/// - `moon_type`: Type of moon (Rock, Ice, Water, Gas)
-/// - `mass`: Moon mass in lunar masses
-/// - `orbital_radius`: Distance from planet in kilometers
-/// - `position`: 3D position vector
-fn moon_to_dict(moon: &mei::space_objects::moon::Moon) -> Dictionary {
-    let mut dict = Dictionary::new();
-    
-    let moon_type_str = match moon.moon_type {
-        mei::space_objects::moon::MoonType::Rocky => "Rocky",
-        mei::space_objects::moon::MoonType::Icy => "Icy",
-        mei::space_objects::moon::MoonType::IceRock => "IceRock",
-        mei::space_objects::moon::MoonType::Ocean => "Ocean",
-        mei::space_objects::moon::MoonType::Volcanic => "Volcanic",
-        mei::space_objects::moon::MoonType::Captured => "Captured",
-        mei::space_objects::moon::MoonType::Atmospheric => "Atmospheric",
-    };
-    dict.set("moon_type", moon_type_str.to_godot());
-    dict.set("mass", moon.mass);
-    dict.set("orbital_radius", moon.position.x); // x position is orbital radius in km
-    
-    let mut pos = Dictionary::new();
-    pos.set("x", moon.position.x);
-    pos.set("y", moon.position.y);
-    pos.set("z", moon.position.z);
-    dict.set("position", pos);
-    
-    dict
-}
+    /// Gets the names and catalog designations of a star.
+    ///
+    /// # Arguments
+    ///
+    /// * `star_id` - The unique identifier for the star
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `name`: Procedural proper name
+    /// - `designation`: Catalog designation, e.g. "Sector NAMQ+3 1234567"
+    /// - `sector`: Sector code, e.g. "NAMQ+3"
+    /// - `constellation`: Name of the sector's bright-star group
+    /// - `bayer`: Bayer/Flamsteed-like label for bright stars, e.g. "17 Kappa Velonis" (empty otherwise)
+    #[func]
+    fn get_star_designation(&mut self, star_id: GString) -> Dictionary {
+        let cached = match self.cached_system(&star_id.to_string()) {
+            Ok(cached) => cached,
+            Err(error) => {
+                godot_error!("{}", error);
+                return Dictionary::new();
+            }
+        };
 
-/// Converts an asteroid belt to a Godot Dictionary.
-///
-/// # Arguments
-///
-/// * `belt` - Reference to the asteroid belt object
-///
-/// # Returns
-///
-/// A `Dictionary` containing asteroid belt data:
-/// - `name`: Belt name
-/// - `inner_radius`: Inner radius in AU
-/// - `outer_radius`: Outer radius in AU
-/// - `total_mass`: Total mass of the belt
-/// - `asteroid_count`: Number of asteroids
-/// - `largest_bodies`: Array of notable asteroid dictionaries
-fn asteroid_belt_to_dict(belt: &mei::space_objects::asteroid::AsteroidBelt) -> Dictionary {
-    let mut dict = Dictionary::new();
-    
-    dict.set("name", belt.name.to_godot());
-    dict.set("inner_radius", belt.inner_radius);
-    dict.set("outer_radius", belt.outer_radius);
-    dict.set("total_mass", belt.total_mass);
-    dict.set("asteroid_count", belt.asteroid_count as i64);
-    
-    // Notable/largest bodies
-    let mut asteroids = Array::<Dictionary>::new();
-    for asteroid in &belt.largest_bodies {
-        asteroids.push(&asteroid_to_dict(asteroid));
+        let system = &cached.system;
+        let position = [system.position.x, system.position.y, system.position.z];
+        // The star's own luminosity, as resolve_designation sees it in nearby queries
+        let luminosity = queried_star(system, &star_id.to_string()).map_or(0.0, |s| s.luminosity());
+
+        let style = self.name_style();
+        let star_seed = star_seed(self.seed as u64, &star_id.to_string());
+        let sector = SectorCoord::containing(position);
+        let constellation = style.constellation_name(self.seed as u64, sector);
+
+        let mut result = Dictionary::new();
+        result.set("name", style.proper_name(star_seed).to_godot());
+        result.set("designation", cached.dict.get("designation").unwrap_or_default());
+        result.set("sector", sector.code().to_godot());
+        result.set("bayer", style.bayer_label(star_seed, luminosity, &constellation).unwrap_or_default().to_godot());
+        result.set("constellation", constellation.to_godot());
+        result
+    }
+
+    /// Gets the designation of a planet or moon.
+    ///
+    /// # Arguments
+    ///
+    /// * `star_id` - The unique identifier for the star
+    /// * `planet_index` - Planet index in orbital order (0 is planet "b")
+    /// * `moon_index` - Moon index in orbital order (0 is moon "I"), or -1 for the planet itself
+    ///
+    /// # Returns
+    ///
+    /// The designation, e.g. "Sector NAMQ+3 1234567 c II", or an empty string
+    /// (with an error logged) if the system has no such planet or moon
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// galaxy.get_body_designation("1234", 1, 0)  # second planet, first moon
+    /// ```
+    #[func]
+    fn get_body_designation(&mut self, star_id: GString, planet_index: i64, moon_index: i64) -> GString {
+        let cached = match self.cached_system(&star_id.to_string()) {
+            Ok(cached) => cached,
+            Err(error) => {
+                godot_error!("{}", error);
+                return GString::new();
+            }
+        };
+
+        let designation = cached.dict.get("designation").map(|d| d.to_string()).unwrap_or_default();
+        if planet_index < 0 {
+            return designation.into();
+        }
+
+        // Same checks as resolve_designation's no_such_planet and no_such_moon
+        let planets = lettered_planets(&cached.system);
+        let Some(planet) = planets.get(planet_index as usize) else {
+            godot_error!("get_body_designation: {} has {} planets", designation, planets.len());
+            return GString::new();
+        };
+        let mut designation = format!("{} {}", designation, planet_letter(planet_index as usize));
+        if moon_index >= 0 {
+            if moon_index as usize >= planet.moons.len() {
+                godot_error!("get_body_designation: {} has {} moons", designation, planet.moons.len());
+                return GString::new();
+            }
+            designation = format!("{} {}", designation, moon_numeral(moon_index as usize));
+        }
+        designation.into()
+    }
+
+    /// Resolves a name or designation back to a star and body.
+    ///
+    /// Accepts catalog designations ("Sector NAMQ+3 1234567"), bright-star
+    /// labels ("17 Kappa Velonis") and proper names ("Velonis"), each
+    /// optionally followed by a planet letter and moon numeral ("... c II").
+    /// Catalog designations encode the star's position and resolve anywhere
+    /// in the galaxy; names and labels are only searched for around
+    /// `region_center`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The name or designation to resolve
+    /// * `region_center` - Center of the name search in light-years
+    /// * `region_radius` - Radius of the name search in light-years (clamped to
+    ///   `nearby_max_radius`, with an error logged when it is larger)
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `ok`: Whether the text resolved to an existing star and body
+    /// - `kind`: `"catalog"`, `"bayer"` or `"name"` (when the text parsed)
+    /// - `star_id`: ID of the resolved star
+    /// - `position`: `Vector3` star position in light-years
+    /// - `name`: Proper name of the resolved star
+    /// - `designation`: Canonical catalog designation, including the body suffix
+    /// - `planet_index`: Planet index in orbital order, or -1
+    /// - `moon_index`: Moon index in orbital order, or -1
+    /// - `error`: On failure, a `Dictionary` with `code`, `message` and `offset`
+    ///   (byte offset of the offending token). Codes: `empty`, `unknown_format`,
+    ///   `missing_sector_code`, `bad_sector_code`, `missing_number`, `bad_number`,
+    ///   `number_out_of_range`, `bad_planet_letter`, `bad_moon_numeral`,
+    ///   `trailing_input`, `no_star`, `not_found`, `no_such_planet`, `no_such_moon`,
+    ///   `generation_failed`
+    /// - `suggestions`: On `not_found`, an `Array` of near-miss `Dictionary`
+    ///   entries with `label`, `star_id` and `edit_distance`, closest first
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var found = galaxy.resolve_designation(search_box.text, camera_ly, 15.0)
+    /// if found["ok"]:
+    ///     select_star(found["star_id"])
+    /// elif not found["suggestions"].is_empty():
+    ///     print("Did you mean ", found["suggestions"][0]["label"], "?")
+    /// ```
+    #[func]
+    fn resolve_designation(&mut self, text: GString, region_center: Vector3, region_radius: f64) -> Dictionary {
+        let galaxy_seed = self.seed as u64;
+        let style = self.name_style();
+        let Some(api) = &mut self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Dictionary::new();
+        };
+
+        let cell_size = api.generator.config.cell_size;
+        let mut result = Dictionary::new();
+        result.set("ok", false);
+        result.set("suggestions", Array::<Dictionary>::new());
+
+        let parsed = match parse_designation(&text.to_string(), &style, cells_per_sector(cell_size)) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                result.set("error", designation_error_to_dict(&error));
+                return result;
+            }
+        };
+        let kind = match &parsed.query {
+            DesignationQuery::Catalog { .. } => "catalog",
+            DesignationQuery::Bayer { .. } => "bayer",
+            DesignationQuery::Name { .. } => "name",
+        };
+        result.set("kind", kind.to_godot());
+
+        // (star ID, position) of the matching star
+        let found = match &parsed.query {
+            DesignationQuery::Catalog { sector, number } => {
+                let center = cell_center(*sector, *number, cell_size);
+                api.generator
+                    .get_nearby_stars(&Vec3::new(center[0], center[1], center[2]), cell_size, 16)
+                    .iter()
+                    .map(|s| ((s.id as i64).to_string(), [s.position.x, s.position.y, s.position.z]))
+                    .find(|(_, p)| SectorCoord::containing(*p) == *sector && star_number(*p, *sector, cell_size) == *number)
+                    .ok_or_else(|| DesignationError::new(
+                        "no_star",
+                        format!("No star in cell {} of sector {}", number, sector.code()),
+                        0,
+                    ))
+            }
+            DesignationQuery::Bayer { label: wanted } | DesignationQuery::Name { name: wanted } => {
+                let center = Vec3::new(region_center.x as f64, region_center.y as f64, region_center.z as f64);
+                let max_radius = api.generator.config.nearby_max_radius;
+                if region_radius > max_radius {
+                    godot_error!(
+                        "resolve_designation: name search radius {} ly clamped to nearby_max_radius ({} ly)",
+                        region_radius, max_radius
+                    );
+                }
+                let radius = region_radius.max(0.0).min(max_radius);
+                let distance = |p: [f64; 3]| {
+                    ((p[0] - center.x).powi(2) + (p[1] - center.y).powi(2) + (p[2] - center.z).powi(2)).sqrt()
+                };
+
+                // (label, star ID, position) for every star in the region that has a label of this kind
+                let labelled: Vec<(String, String, [f64; 3])> = api.generator
+                    .get_nearby_stars(&center, radius, 10000)
+                    .iter()
+                    .filter_map(|s| {
+                        let id = (s.id as i64).to_string();
+                        let position = [s.position.x, s.position.y, s.position.z];
+                        let seed = star_seed(galaxy_seed, &id);
+                        let label = match &parsed.query {
+                            DesignationQuery::Bayer { .. } => {
+                                let constellation = style.constellation_name(galaxy_seed, SectorCoord::containing(position));
+                                style.bayer_label(seed, s.luminosity(), &constellation)?
+                            }
+                            _ => style.proper_name(seed),
+                        };
+                        Some((label, id, position))
+                    })
+                    .collect();
+
+                let exact = labelled.iter()
+                    .filter(|(label, _, _)| label.eq_ignore_ascii_case(wanted))
+                    .min_by(|a, b| distance(a.2).total_cmp(&distance(b.2)));
+                match exact {
+                    Some((_, id, position)) => Ok((id.clone(), *position)),
+                    None => {
+                        let tolerance = (wanted.chars().count() / 4).max(2);
+                        let mut near: Vec<(usize, &String, &String)> = labelled.iter()
+                            .map(|(label, id, _)| (edit_distance(label, wanted), label, id))
+                            .filter(|(d, _, _)| *d <= tolerance)
+                            .collect();
+                        near.sort();
+
+                        let mut suggestions = Array::<Dictionary>::new();
+                        for (d, label, id) in near.into_iter().take(5) {
+                            let mut suggestion = Dictionary::new();
+                            suggestion.set("label", label.to_godot());
+                            suggestion.set("star_id", id.to_godot());
+                            suggestion.set("edit_distance", d as i64);
+                            suggestions.push(&suggestion);
+                        }
+                        result.set("suggestions", suggestions);
+                        Err(DesignationError::new(
+                            "not_found",
+                            format!("No star named \"{}\" within {} ly", wanted, radius),
+                            0,
+                        ))
+                    }
+                }
+            }
+        };
+        let (star_id, position) = match found {
+            Ok(found) => found,
+            Err(error) => {
+                result.set("error", designation_error_to_dict(&error));
+                return result;
+            }
+        };
+
+        let mut designation = catalog_designation(position, cell_size);
+        if let Some(planet_index) = parsed.planet {
+            let cached = match self.cached_system(&star_id) {
+                Ok(cached) => cached,
+                Err(message) => {
+                    let error = DesignationError::new("generation_failed", message, 0);
+                    result.set("error", designation_error_to_dict(&error));
+                    return result;
+                }
+            };
+            let planets = lettered_planets(&cached.system);
+
+            let error = match (planets.get(planet_index), parsed.moon) {
+                (None, _) => Some(DesignationError::new(
+                    "no_such_planet",
+                    format!("{} has {} planets", designation, planets.len()),
+                    0,
+                )),
+                (Some(planet), Some(moon_index)) if moon_index >= planet.moons.len() => Some(DesignationError::new(
+                    "no_such_moon",
+                    format!("{} {} has {} moons", designation, planet_letter(planet_index), planet.moons.len()),
+                    0,
+                )),
+                _ => None,
+            };
+            if let Some(error) = error {
+                result.set("error", designation_error_to_dict(&error));
+                return result;
+            }
+
+            designation = format!("{} {}", designation, planet_letter(planet_index));
+            if let Some(moon_index) = parsed.moon {
+                designation = format!("{} {}", designation, moon_numeral(moon_index));
+            }
+        }
+
+        result.set("ok", true);
+        result.set("star_id", star_id.to_godot());
+        result.set("position", Vector3::new(position[0] as f32, position[1] as f32, position[2] as f32));
+        result.set("name", style.proper_name(star_seed(galaxy_seed, &star_id)).to_godot());
+        result.set("designation", designation.to_godot());
+        result.set("planet_index", parsed.planet.map_or(-1, |i| i as i64));
+        result.set("moon_index", parsed.moon.map_or(-1, |i| i as i64));
+        result
+    }
+
+    /// Searches the stars around a point for systems matching a filter.
+    ///
+    /// Systems are generated and checked in parallel in Rust, nearest first,
+    /// until `limit` matches are found. Systems already in the system cache
+    /// are not generated again.
+    ///
+    /// # Arguments
+    ///
+    /// * `region_center` - Search center in light-years
+    /// * `radius` - Search radius in light-years; regions wider than
+    ///   `nearby_max_radius` are queried in tiles, nearest first
+    /// * `filter` - A `Dictionary` with any of these keys (unset keys match anything):
+    ///   - `star_types`: `PackedStringArray` (or `Array` of strings), some star's type must start with one of them
+    ///   - `configurations`: `PackedStringArray` of stellar configurations ("Single", "CloseBinary", ...)
+    ///   - `min_planets` / `max_planets`: Planet count bounds (ints, or whole floats)
+    ///   - `planet_types`: `PackedStringArray`, every listed planet type must be present
+    ///   - `habitable_planets`: Whether a planet must (or must not) orbit in the habitable zone
+    ///   - `asteroid_belts`: Whether the system must (or must not) have an asteroid belt
+    ///   - `oort_cloud`: Whether the system must (or must not) have an Oort cloud
+    /// * `limit` - Maximum number of matches to return
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `matches`: `Array` of `Dictionary` entries, nearest first, with
+    ///   `star_id`, `position` (`Vector3` in light-years), `distance` and
+    ///   `reasons` (`PackedStringArray`, one entry per filter constraint)
+    /// - `count`: Number of matches returned
+    /// - `scanned`: Number of systems generated
+    /// - `failed`: Number of scanned systems that could not be generated, and
+    ///   were skipped (an error is logged with the first few)
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var found = galaxy.search_systems(camera_ly, 50.0, {
+    ///     "planet_types": PackedStringArray(["Ocean"]),
+    ///     "habitable_planets": true,
+    /// }, 20)
+    /// for match in found["matches"]:
+    ///     print(match["star_id"], ": ", ", ".join(match["reasons"]))
+    /// ```
+    #[func]
+    fn search_systems(&mut self, region_center: Vector3, radius: f64, filter: Dictionary, limit: i64) -> Dictionary {
+        let center = [region_center.x as f64, region_center.y as f64, region_center.z as f64];
+        self.search_region(center, [0.0; 3], radius, &filter, limit)
+    }
+
+    /// Searches for systems matching a filter, with positions relative to a
+    /// floating origin.
+    ///
+    /// The same search as `search_systems`, but the center is given and the
+    /// match positions returned relative to a `MeiCoordinate`, in double
+    /// precision, as in `get_nearby_stars_relative`.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - Coordinate that positions are relative to
+    /// * `offset` - Search center relative to `origin`, in light-years
+    /// * `radius` - Search radius in light-years
+    /// * `filter` - The same filter `Dictionary` as `search_systems`
+    /// * `limit` - Maximum number of matches to return
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` with the same structure as `search_systems`, plus:
+    /// - `origin`: The `MeiCoordinate` positions are relative to
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var found = galaxy.search_systems_relative(ship_coordinate, Vector3.ZERO, 50.0, {"oort_cloud": true}, 5)
+    /// for match in found["matches"]:
+    ///     add_waypoint(match["position"] * scale)
+    /// ```
+    #[func]
+    fn search_systems_relative(
+        &mut self,
+        origin: Gd<MeiCoordinate>,
+        offset: Vector3,
+        radius: f64,
+        filter: Dictionary,
+        limit: i64,
+    ) -> Dictionary {
+        let base = origin.bind().coordinate().galactic();
+        let center = [base[0] + offset.x as f64, base[1] + offset.y as f64, base[2] + offset.z as f64];
+        let mut result = self.search_region(center, base, radius, &filter, limit);
+        if !result.is_empty() {
+            result.set("origin", origin);
+        }
+        result
+    }
+
+    /// `star_layout`: one packed array per star property.
+    #[constant]
+    const STAR_LAYOUT_SEPARATE: i64 = 0;
+
+    /// `star_layout`: a single `PackedFloat32Array` of interleaved star data,
+    /// ready to upload to a shader.
+    #[constant]
+    const STAR_LAYOUT_INTERLEAVED: i64 = 1;
+
+    /// `get_star_systems_batch` detail level: summary fields only.
+    #[constant]
+    const DETAIL_SUMMARY: i64 = 0;
+
+    /// `get_star_systems_batch` detail level: summary plus planets, without moons.
+    #[constant]
+    const DETAIL_PLANETS: i64 = 1;
+
+    /// `get_star_systems_batch` detail level: the full `get_star_system` dictionary.
+    #[constant]
+    const DETAIL_FULL: i64 = 2;
+
+    /// Generates many star systems in parallel.
+    ///
+    /// Systems are generated on worker threads; only the conversion to
+    /// dictionaries runs on the calling thread, and `detail` controls how
+    /// much of that is done. Systems already in the system cache are reused,
+    /// but new ones are not added to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Star IDs, e.g. `get_nearby_stars()["ids"]`
+    /// * `detail` - `DETAIL_SUMMARY`, `DETAIL_PLANETS` or `DETAIL_FULL`
+    ///
+    /// # Returns
+    ///
+    /// An `Array` with one `Dictionary` per ID, in the same order. Every entry has
+    /// `star_id` and `ok`; failed entries add `error` with a message. Successful
+    /// entries contain:
+    /// - `DETAIL_SUMMARY`: `name`, `designation`, `position`, `star_types`,
+    ///   `configuration_type`, `planet_types`, `dominant_planet_type`, `planet_count`,
+    ///   `moon_count`, `habitable_planets`, `asteroid_belt_count`, `has_oort_cloud`
+    /// - `DETAIL_PLANETS`: the summary plus `planets`, planet dictionaries in
+    ///   orbital order without their `moons` arrays
+    /// - `DETAIL_FULL`: the same keys as `get_star_system`
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var nearby = galaxy.get_nearby_stars(x, y, z, 50.0)
+    /// for system in galaxy.get_star_systems_batch(nearby["ids"], MeiGalaxy.DETAIL_SUMMARY):
+    ///     if system["ok"]:
+    ///         add_map_marker(system["position"], system["name"])
+    /// ```
+    #[func]
+    fn get_star_systems_batch(&mut self, ids: PackedInt64Array, detail: i64) -> Array<Dictionary> {
+        self.sync_system_cache();
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Array::new();
+        };
+
+        let galaxy_seed = self.seed as u64;
+        let style = self.name_style();
+        let cell_size = api.generator.config.cell_size;
+        // Cached systems are reused; only the rest are generated
+        let cached: Vec<Option<Rc<CachedSystem>>> =
+            ids.as_slice().iter().map(|id| self.system_cache.peek(id).cloned()).collect();
+        let ids: Vec<String> = ids.as_slice().iter().map(|id| id.to_string()).collect();
+        let misses: Vec<&String> = ids.iter().zip(&cached).filter(|(_, c)| c.is_none()).map(|(id, _)| id).collect();
+        let mut generated = misses
+            .par_iter()
+            .map(|id| generate_system(api, id))
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let mut result = Array::<Dictionary>::new();
+        let mut failed = 0;
+        for (id, cached) in ids.iter().zip(&cached) {
+            let generated_system;
+            let system = match cached {
+                Some(cached) => Ok(&cached.system),
+                None => {
+                    generated_system = generated.next().expect("one generated system per cache miss");
+                    generated_system.as_ref()
+                }
+            };
+            let mut entry = match (system, cached) {
+                (Ok(_), Some(cached)) if detail >= Self::DETAIL_FULL => cached.dict.duplicate_deep(),
+                (Ok(system), _) if detail >= Self::DETAIL_FULL => {
+                    record_to_dict(&system_record(galaxy_seed, id, &style, system, cell_size))
+                }
+                (Ok(system), _) => {
+                    let mut entry = summary_record(galaxy_seed, id, &style, system, cell_size);
+                    if detail >= Self::DETAIL_PLANETS {
+                        let ctx = BodyContext::new(galaxy_seed, id, system, cell_size);
+                        let mut planets: Vec<_> = system.inner_planets.iter().chain(system.outer_planets.iter()).collect();
+                        planets.sort_by(|a, b| a.position.x.total_cmp(&b.position.x));
+
+                        let planets: Vec<Record> = planets.into_iter()
+                            .map(|planet| {
+                                let mut record = planet_record(planet, &ctx);
+                                record.remove("moons");
+                                record
+                            })
+                            .collect();
+                        entry.set("planets", planets);
+                    }
+                    record_to_dict(&entry)
+                }
+                (Err(message), _) => {
+                    failed += 1;
+                    let mut entry = Dictionary::new();
+                    entry.set("star_id", id.to_godot());
+                    entry.set("error", message.to_godot());
+                    entry
+                }
+            };
+            entry.set("ok", system.is_ok());
+            result.push(&entry);
+        }
+
+        godot_print!("Generated {} star systems ({} failed) at detail level {}", ids.len(), failed, detail);
+        result
+    }
+
+    /// Gets compact summaries of many star systems as packed arrays.
+    ///
+    /// MEI has no partial generation entry point, so each system is still
+    /// generated in full, but in parallel and without building any nested
+    /// dictionaries. Suited to annotating thousands of map icons. Systems
+    /// already in the system cache are reused, but new ones are not added.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Star IDs, e.g. `get_nearby_stars()["ids"]`
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` of arrays with one entry per ID, in the same order:
+    /// - `ids`: `PackedInt64Array` of the queried star IDs
+    /// - `valid`: `PackedByteArray`, 0 where generation failed (other fields are then 0,
+    ///   and `dominant_planet_types` is 255)
+    /// - `configurations`: `PackedByteArray` of indices into `configuration_names`
+    /// - `planet_counts`: `PackedInt32Array`
+    /// - `moon_counts`: `PackedInt32Array`
+    /// - `belt_counts`: `PackedInt32Array` of asteroid belts
+    /// - `habitable`: `PackedByteArray`, 1 if a planet orbits in the habitable zone
+    /// - `oort_clouds`: `PackedByteArray`, 1 if the system has an Oort cloud
+    /// - `dominant_planet_types`: `PackedByteArray` of indices into `planet_type_names`
+    ///   for the most massive planet (255 if there are no planets)
+    /// - `configuration_names`: `PackedStringArray`
+    /// - `planet_type_names`: `PackedStringArray`
+    /// - `count`: Number of entries
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var summaries = galaxy.get_system_summaries(nearby["ids"])
+    /// for i in summaries["count"]:
+    ///     if summaries["habitable"][i]:
+    ///         icons[i].modulate = Color.GREEN
+    /// ```
+    #[func]
+    fn get_system_summaries(&mut self, ids: PackedInt64Array) -> Dictionary {
+        self.sync_system_cache();
+        let Some(api) = &self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Dictionary::new();
+        };
+
+        // Cached systems are reused; only the rest are generated
+        let cached: Vec<Option<SystemFacts>> = ids
+            .as_slice()
+            .iter()
+            .map(|id| self.system_cache.peek(id).map(|cached| system_facts(&cached.system)))
+            .collect();
+        let facts: Vec<Option<SystemFacts>> = ids
+            .as_slice()
+            .par_iter()
+            .zip(cached)
+            .map(|(id, cached)| {
+                cached.or_else(|| generate_system(api, &id.to_string()).ok().map(|system| system_facts(&system)))
+            })
+            .collect();
+        let field = |f: &dyn Fn(&SystemFacts) -> usize| -> Vec<usize> {
+            facts.iter().map(|facts| facts.as_ref().map_or(0, f)).collect()
+        };
+        let bytes = |values: Vec<usize>| -> PackedByteArray { values.into_iter().map(|v| v as u8).collect() };
+        let ints = |values: Vec<usize>| -> PackedInt32Array { values.into_iter().map(|v| v as i32).collect() };
+        let names = |names: &[&str]| -> PackedStringArray { names.iter().map(|n| GString::from(*n)).collect() };
+
+        let mut result = Dictionary::new();
+        result.set("ids", ids.clone());
+        result.set("valid", facts.iter().map(|f| f.is_some() as u8).collect::<PackedByteArray>());
+        result.set("configurations", bytes(field(&|f| name_code(&CONFIGURATION_NAMES, &f.configuration) as usize)));
+        result.set("planet_counts", ints(field(&|f| f.planet_types.len())));
+        result.set("moon_counts", ints(field(&|f| f.moon_count)));
+        result.set("belt_counts", ints(field(&|f| f.asteroid_belts)));
+        result.set("habitable", bytes(field(&|f| (f.habitable_planets > 0) as usize)));
+        result.set("oort_clouds", bytes(field(&|f| f.has_oort_cloud as usize)));
+        result.set("dominant_planet_types", facts.iter()
+            .map(|f| f.as_ref()
+                .and_then(|f| f.dominant_planet_type.as_deref())
+                .map_or(NO_CODE, |t| name_code(&PLANET_TYPE_NAMES, t)))
+            .collect::<PackedByteArray>());
+        result.set("configuration_names", names(&CONFIGURATION_NAMES));
+        result.set("planet_type_names", names(&PLANET_TYPE_NAMES));
+        result.set("count", facts.len() as i64);
+
+        let failed = facts.iter().filter(|f| f.is_none()).count();
+        godot_print!("Summarized {} star systems ({} failed)", facts.len(), failed);
+        result
+    }
+
+    /// Gets system cache statistics.
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `hits`: Lookups served from the cache
+    /// - `misses`: Lookups that generated a system (searches, batches and
+    ///   summaries only read systems already cached, and count neither)
+    /// - `evictions`: Systems dropped to stay within `system_cache_size`
+    /// - `entries`: Systems currently cached
+    /// - `capacity`: Current `system_cache_size`
+    /// - `memory_bytes`: Estimated memory held by cached systems and dictionaries
+    #[func]
+    fn cache_stats(&self) -> Dictionary {
+        let mut result = Dictionary::new();
+        result.set("hits", self.system_cache.hits as i64);
+        result.set("misses", self.system_cache.misses as i64);
+        result.set("evictions", self.system_cache.evictions as i64);
+        result.set("entries", self.system_cache.len() as i64);
+        result.set("capacity", self.system_cache.capacity() as i64);
+        result.set("memory_bytes", self.system_cache.values().map(|c| c.bytes).sum::<usize>() as i64);
+        result
+    }
+
+    /// Deletes every cached `get_structure` file in `structure_cache_dir`.
+    ///
+    /// # Returns
+    ///
+    /// Number of files removed
+    #[func]
+    fn clear_structure_cache(&self) -> i64 {
+        let dir = self.structure_cache_dir.to_string().trim_end_matches('/').to_string();
+        if dir.is_empty() {
+            return 0;
+        }
+
+        let mut removed = 0;
+        for file in DirAccess::get_files_at(&GString::from(dir.as_str())).as_slice() {
+            let name = file.to_string();
+            if name.starts_with("structure_") && name.ends_with(".bin") {
+                let path = GString::from(format!("{}/{}", dir, name));
+                if DirAccess::remove_absolute(&path) == godot::global::Error::OK {
+                    removed += 1;
+                }
+            }
+        }
+        godot_print!("MeiGalaxy removed {} structure cache files from {}", removed, dir);
+        removed
+    }
+
+    /// Drops every cached star system.
+    ///
+    /// Seed and config changes already do this; call it to free memory.
+    #[func]
+    fn clear_system_cache(&mut self) {
+        if !self.system_cache.is_empty() {
+            godot_print!("MeiGalaxy cleared {} cached systems", self.system_cache.len());
+        }
+        self.system_cache.clear();
     }
-    dict.set("largest_bodies", asteroids);
-    
-    dict
 }
 
-/// Converts an asteroid to a Godot Dictionary.
-///
-/// # Arguments
-///
-/// * `asteroid` - Reference to the asteroid object
-///
-/// # Returns
-///
-/// A `Dictionary` containing asteroid data:
-/// - `asteroid_type`: Type of asteroid (Carbonaceous, Silicate, Metallic)
-/// - `mass`: Asteroid mass
-/// - `diameter`: Diameter in kilometers
-/// - `orbital_radius`: Distance from star in AU
-/// - `position`: 3D position vector
-fn asteroid_to_dict(asteroid: &mei::space_objects::asteroid::Asteroid) -> Dictionary {
-    let mut dict = Dictionary::new();
-    
-    let asteroid_type_str = match asteroid.asteroid_type {
-        mei::space_objects::asteroid::AsteroidType::Carbonaceous => "Carbonaceous",
-        mei::space_objects::asteroid::AsteroidType::Silicate => "Silicate",
-        mei::space_objects::asteroid::AsteroidType::Metallic => "Metallic",
-    };
-    dict.set("asteroid_type", asteroid_type_str.to_godot());
-    dict.set("mass", asteroid.mass);
-    dict.set("diameter", asteroid.diameter);
-    dict.set("orbital_radius", asteroid.orbital_radius);
-    
-    let mut pos = Dictionary::new();
-    pos.set("x", asteroid.position.x);
-    pos.set("y", asteroid.position.y);
-    pos.set("z", asteroid.position.z);
-    dict.set("position", pos);
-    
-    dict
+impl MeiGalaxy {
+    /// Runs a system search, with match positions relative to `origin`.
+    fn search_region(
+        &mut self,
+        center: [f64; 3],
+        origin: [f64; 3],
+        radius: f64,
+        filter: &Dictionary,
+        limit: i64,
+    ) -> Dictionary {
+        self.sync_system_cache();
+        let Some(api) = &mut self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return Dictionary::new();
+        };
+
+        let (filter, unknown, invalid) = SystemFilter::from_dict(filter);
+        if !unknown.is_empty() {
+            godot_error!("search_systems: unknown filter keys {:?} (expected {:?})", unknown, FILTER_KEYS);
+        }
+        if !invalid.is_empty() {
+            godot_error!("search_systems: ignoring filter keys with values of the wrong type: {:?}", invalid);
+        }
+
+        // Nothing is generated far outside the galaxy, so a rare filter cannot scan forever
+        let reach = (center[0].powi(2) + center[1].powi(2) + center[2].powi(2)).sqrt()
+            + 2.0 * api.generator.galaxy.radius;
+        let radius = radius.max(0.0).min(reach);
+        let limit = limit.max(0) as usize;
+
+        // Generate in nearest-first chunks so small limits stop early
+        let mut found = Vec::new();
+        let mut failed = Vec::new();
+        let mut scanned = 0;
+        visit_nearest_first(api, center, radius, |api, stars| {
+            for chunk in stars.chunks(SEARCH_CHUNK_SIZE) {
+                if found.len() >= limit {
+                    break;
+                }
+                scanned += chunk.len();
+                // Systems already visited are read from the cache, the rest generated in parallel
+                let cached: Vec<Option<SystemFacts>> = chunk
+                    .iter()
+                    .map(|star| {
+                        star.id.parse::<i64>().ok()
+                            .and_then(|key| self.system_cache.peek(&key))
+                            .map(|cached| system_facts(&cached.system))
+                    })
+                    .collect();
+                let matched: Vec<Result<_, String>> = chunk
+                    .par_iter()
+                    .zip(cached)
+                    .filter_map(|(star, facts)| {
+                        let facts = match facts {
+                            Some(facts) => facts,
+                            None => match generate_system(api, &star.id) {
+                                Ok(system) => system_facts(&system),
+                                Err(message) => return Some(Err(format!("{}: {}", star.id, message))),
+                            },
+                        };
+                        let reasons = filter.matches(&facts)?;
+                        Some(Ok((star.id.clone(), star.position, star.distance, reasons)))
+                    })
+                    .collect();
+                for result in matched {
+                    match result {
+                        Ok(found_match) => found.push(found_match),
+                        Err(error) => failed.push(error),
+                    }
+                }
+            }
+            found.len() < limit
+        });
+        found.truncate(limit);
+
+        let mut matches = Array::<Dictionary>::new();
+        for (id, position, distance, reasons) in &found {
+            let mut entry = Dictionary::new();
+            entry.set("star_id", id.to_godot());
+            entry.set("position", Vector3::new(
+                (position[0] - origin[0]) as f32,
+                (position[1] - origin[1]) as f32,
+                (position[2] - origin[2]) as f32,
+            ));
+            entry.set("distance", *distance);
+            let reasons: PackedStringArray = reasons.iter().map(|r| GString::from(r.as_str())).collect();
+            entry.set("reasons", reasons);
+            matches.push(&entry);
+        }
+
+        let mut result = Dictionary::new();
+        result.set("matches", matches);
+        result.set("count", found.len() as i64);
+        result.set("scanned", scanned as i64);
+        result.set("failed", failed.len() as i64);
+
+        if !failed.is_empty() {
+            godot_error!("search_systems: skipped {} systems that could not be generated, e.g. {}",
+                failed.len(), failed.iter().take(3).cloned().collect::<Vec<_>>().join("; "));
+        }
+        godot_print!("Search matched {} of {} systems within {} ly", found.len(), scanned, radius);
+        result
+    }
+
+    /// Runs a nearby star query, with positions relative to `origin`.
+    fn nearby_query(&mut self, center: [f64; 3], radius: f64, max_stars: i64, origin: [f64; 3]) -> Option<StructureData> {
+        let Some(api) = &mut self.api else {
+            godot_error!("MeiGalaxy not initialized");
+            return None;
+        };
+
+        if max_stars < 0 {
+            godot_error!("get_nearby_stars: max_stars must not be negative, got {}", max_stars);
+        }
+        let clamped_radius = radius.max(0.0).min(api.generator.config.nearby_max_radius);
+        let data = nearby_data(api, center, clamped_radius, max_stars.max(0), origin);
+
+        let [x, y, z] = center;
+        godot_print!("Found {} nearby stars at ({:.1}, {:.1}, {:.1}) radius {} ly (clamped to {} ly)",
+            data.stars.len(), x, y, z, radius, clamped_radius);
+        Some(data)
+    }
+
+    /// Current naming tables, falling back to the built-in style.
+    fn name_style(&self) -> NameStyle {
+        self.name_style.as_ref().map(|style| style.bind().to_style()).unwrap_or_default()
+    }
+
+    /// Path and key of the structure cache file for the current seed, config
+    /// and MEI build.
+    ///
+    /// Returns `None` if the disk cache is disabled or the API is not initialized.
+    fn structure_cache_path(&self, max_stars: i64) -> Option<(GString, u64)> {
+        let dir = self.structure_cache_dir.to_string().trim_end_matches('/').to_string();
+        let api = self.api.as_ref()?;
+        if dir.is_empty() {
+            return None;
+        }
+
+        let fields = config_fields(&api.generator.config);
+        let key = hash_f64s(
+            hash_combine(
+                hash_combine(
+                    fingerprint(self.seed as u64, &fields, &[FieldRole::Generation, FieldRole::Sampling]),
+                    STRUCTURE_FORMAT_VERSION as u64,
+                ),
+                // Set by build.rs from Cargo.lock, so a new MEI commit regenerates the cache
+                hash_str(env!("MEI_SOURCE")),
+            ),
+            &[max_stars as f64],
+        );
+        Some((format!("{}/structure_{:016x}.bin", dir, key).into(), key))
+    }
+
+    /// Loads cached structure data, or `None` if there is no usable cache file.
+    fn load_structure_cache(&self, max_stars: i64) -> Option<StructureData> {
+        let (path, key) = self.structure_cache_path(max_stars)?;
+        if !FileAccess::file_exists(&path) {
+            return None;
+        }
+
+        match decode_structure(FileAccess::get_file_as_bytes(&path).as_slice(), key) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                godot_print!("Ignoring structure cache {} ({}), regenerating", path, error);
+                None
+            }
+        }
+    }
+
+    /// Writes structure data to the disk cache, if enabled.
+    ///
+    /// Writes to a temporary file first so an interrupted write never leaves
+    /// a half-written cache file behind.
+    fn save_structure_cache(&self, max_stars: i64, data: &StructureData) {
+        let Some((path, key)) = self.structure_cache_path(max_stars) else {
+            return;
+        };
+
+        if DirAccess::make_dir_recursive_absolute(&self.structure_cache_dir) != godot::global::Error::OK {
+            godot_error!("Could not create structure cache folder {}", self.structure_cache_dir);
+            return;
+        }
+        let temp_path = GString::from(format!("{}.tmp", path));
+        let Some(mut file) = FileAccess::open(&temp_path, ModeFlags::WRITE) else {
+            godot_error!("Could not write structure cache {}", temp_path);
+            return;
+        };
+        file.store_buffer(&PackedByteArray::from(encode_structure(data, key).as_slice()));
+        file.close();
+
+        if DirAccess::rename_absolute(&temp_path, &path) != godot::global::Error::OK {
+            godot_error!("Could not move structure cache into place at {}", path);
+            DirAccess::remove_absolute(&temp_path);
+        }
+    }
+
+    /// Keeps the system cache in step with the exported settings it depends on.
+    fn sync_system_cache(&mut self) {
+        let capacity = self.system_cache_size.max(0) as usize;
+        if self.system_cache.capacity() != capacity {
+            self.system_cache.set_capacity(capacity);
+        }
+        let style = self.name_style.as_ref().map(|style| (style.instance_id(), style.bind().version()));
+        if self.system_cache_style != style {
+            self.system_cache.clear();
+            self.system_cache_style = style;
+        }
+    }
+
+    /// Gets a system from the cache, generating and converting it on a miss.
+    ///
+    /// Fails if the API is not initialized, the ID is not a star ID or MEI
+    /// could not generate the system.
+    fn cached_system(&mut self, star_id: &str) -> Result<Rc<CachedSystem>, String> {
+        if self.api.is_none() {
+            return Err("MeiGalaxy not initialized".to_string());
+        }
+        // Star IDs are the decimal integers handed out by get_structure and
+        // get_nearby_stars; "042" and "42" name the same star
+        let Ok(key) = star_id.parse::<i64>() else {
+            return Err(format!("Invalid star ID \"{}\"", star_id));
+        };
+        let star_id = &key.to_string();
+
+        self.sync_system_cache();
+        if let Some(cached) = self.system_cache.get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let style = self.name_style();
+        let Some(api) = self.api.as_ref() else {
+            return Err("MeiGalaxy not initialized".to_string());
+        };
+        let system = generate_system(api, star_id)
+            .map_err(|message| format!("Could not generate star system {}: {}", star_id, message))?;
+        let dict = record_to_dict(&system_record(
+            self.seed as u64,
+            star_id,
+            &style,
+            &system,
+            api.generator.config.cell_size,
+        ));
+
+        let total_planets = system.inner_planets.len() + system.outer_planets.len();
+        let total_moons: usize = system.inner_planets.iter().chain(system.outer_planets.iter())
+            .map(|p| p.moons.len()).sum();
+        godot_print!("System has {} stars, {} planets, {} moons, {} asteroid belts", 
+            system.stars.len(), total_planets, total_moons, system.asteroid_belts.len());
+
+        let cached = Rc::new(CachedSystem {
+            bytes: approx_system_bytes(&system),
+            system,
+            dict,
+        });
+        self.system_cache.insert(key, cached.clone());
+        Ok(cached)
+    }
 }
 
-/// Converts an Oort cloud to a Godot Dictionary.
-///
-/// # Arguments
+/// Converts structure data to the packed arrays returned by `get_structure`
+/// and `get_nearby_stars`.
 ///
-/// * `oort` - Reference to the Oort cloud object
-///
-/// # Returns
-///
-/// A `Dictionary` containing Oort cloud data:
-/// - `inner_radius`: Inner radius in AU
-/// - `outer_radius`: Outer radius in AU
-/// - `estimated_population`: Estimated number of objects
-/// - `total_mass`: Total mass of the cloud
-/// - `notable_comets`: Array of notable comet dictionaries
-fn oort_cloud_to_dict(oort: &mei::space_objects::comet::OortCloud) -> Dictionary {
-    let mut dict = Dictionary::new();
-    
-    dict.set("inner_radius", oort.inner_radius);
-    dict.set("outer_radius", oort.outer_radius);
-    dict.set("estimated_population", oort.estimated_population as i64);
-    dict.set("total_mass", oort.total_mass);
-    
-    // Notable comets
-    let mut comets = Array::<Dictionary>::new();
-    for comet in &oort.notable_comets {
-        comets.push(&comet_to_dict(comet));
+/// Every array is sized once and filled in place; star types and spectral
+/// classes are codes into `star_type_names` and `spectral_class_names` rather
+/// than one string per star. Star types fit a byte; there are hundreds of
+/// spectral classes, so theirs are 32-bit.
+fn packed_stars(data: &StructureData, layout: i64) -> Dictionary {
+    let count = data.stars.len();
+
+    let mut ids = PackedInt64Array::new();
+    ids.resize(count);
+    for (id, star) in ids.as_mut_slice().iter_mut().zip(&data.stars) {
+        *id = star.id;
     }
-    dict.set("notable_comets", comets);
-    
-    dict
+    let star_type_names: PackedStringArray = data.type_names.iter().map(|n| GString::from(n.as_str())).collect();
+    let spectral_class_names: PackedStringArray =
+        data.spectral_class_names.iter().map(|n| GString::from(n.as_str())).collect();
+    let mut colors = PackedColorArray::new();
+    colors.resize(count);
+    for (color, star) in colors.as_mut_slice().iter_mut().zip(&data.stars) {
+        *color = Color::from_rgb(star.color[0], star.color[1], star.color[2]);
+    }
+
+    let mut result = Dictionary::new();
+    if layout == MeiGalaxy::STAR_LAYOUT_INTERLEAVED {
+        let mut stars = PackedFloat32Array::new();
+        stars.resize(count * INTERLEAVED_STRIDE);
+        data.fill_interleaved(stars.as_mut_slice());
+        result.set("stars", stars);
+        result.set("stride", INTERLEAVED_STRIDE as i64);
+    } else {
+        let mut positions = PackedVector3Array::new();
+        let mut luminosities = PackedFloat32Array::new();
+        let mut temperatures = PackedFloat32Array::new();
+        let mut masses = PackedFloat32Array::new();
+        let mut radii = PackedFloat32Array::new();
+        let mut absolute_magnitudes = PackedFloat32Array::new();
+        let mut star_type_codes = PackedByteArray::new();
+        let mut spectral_class_codes = PackedInt32Array::new();
+        positions.resize(count);
+        luminosities.resize(count);
+        temperatures.resize(count);
+        masses.resize(count);
+        radii.resize(count);
+        absolute_magnitudes.resize(count);
+        star_type_codes.resize(count);
+        spectral_class_codes.resize(count);
+
+        let position_slice = positions.as_mut_slice();
+        let luminosity_slice = luminosities.as_mut_slice();
+        let temperature_slice = temperatures.as_mut_slice();
+        let mass_slice = masses.as_mut_slice();
+        let radius_slice = radii.as_mut_slice();
+        let magnitude_slice = absolute_magnitudes.as_mut_slice();
+        let code_slice = star_type_codes.as_mut_slice();
+        let class_slice = spectral_class_codes.as_mut_slice();
+        for (i, star) in data.stars.iter().enumerate() {
+            position_slice[i] = Vector3::new(star.position[0], star.position[1], star.position[2]);
+            luminosity_slice[i] = star.luminosity;
+            temperature_slice[i] = star.temperature;
+            mass_slice[i] = star.mass;
+            radius_slice[i] = star.radius;
+            magnitude_slice[i] = star.absolute_magnitude;
+            code_slice[i] = star.star_type as u8;
+            class_slice[i] = star.spectral_class as i32;
+        }
+
+        result.set("positions", positions);
+        result.set("luminosities", luminosities);
+        result.set("temperatures", temperatures);
+        result.set("masses", masses);
+        result.set("radii", radii);
+        result.set("absolute_magnitudes", absolute_magnitudes);
+        result.set("star_type_codes", star_type_codes);
+        result.set("spectral_class_codes", spectral_class_codes);
+    }
+    result.set("ids", ids);
+    result.set("star_type_names", star_type_names);
+    result.set("spectral_class_names", spectral_class_names);
+    result.set("colors", colors);
+    result.set("count", count as i64);
+    result
 }
 
-/// Converts a comet to a Godot Dictionary.
-///
-/// # Arguments
-///
-/// * `comet` - Reference to the comet object
-///
-/// # Returns
-///
-/// A `Dictionary` containing comet data:
-/// - `comet_type`: Type of comet (ShortPeriod, LongPeriod, Hyperbolic)
-/// - `mass`: Comet mass
-/// - `nucleus_diameter`: Diameter of nucleus in kilometers
-/// - `orbital_radius`: Semi-major axis in AU
-/// - `eccentricity`: Orbital eccentricity
-/// - `position`: 3D position vector
-fn comet_to_dict(comet: &mei::space_objects::comet::Comet) -> Dictionary {
+/// Duration in milliseconds, for log lines.
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Estimated memory held by a cached system and its dictionary.
+fn approx_system_bytes(system: &mei::space_objects::system::StarSystem) -> usize {
+    use std::mem::size_of;
+
+    let planets = system.inner_planets.len() + system.outer_planets.len();
+    let moons: usize = system.inner_planets.iter().chain(system.outer_planets.iter())
+        .map(|p| p.moons.len()).sum();
+    let asteroids: usize = system.asteroid_belts.iter().map(|b| b.largest_bodies.len()).sum();
+    let comets = system.oort_cloud.as_ref().map_or(0, |o| o.notable_comets.len());
+
+    size_of::<mei::space_objects::system::StarSystem>()
+        + planets * size_of::<mei::space_objects::planet::Planet>()
+        + moons * size_of::<mei::space_objects::moon::Moon>()
+        + asteroids * size_of::<mei::space_objects::asteroid::Asteroid>()
+        + comets * size_of::<mei::space_objects::comet::Comet>()
+        + (system.stars.len() + planets + moons + asteroids + comets) * DICT_BYTES_PER_BODY
+}
+
+/// Converts a designation parse or lookup error to a Godot Dictionary.
+fn designation_error_to_dict(error: &DesignationError) -> Dictionary {
     let mut dict = Dictionary::new();
-    
-    let comet_type_str = match comet.comet_type {
-        mei::space_objects::comet::CometType::ShortPeriod => "ShortPeriod",
-        mei::space_objects::comet::CometType::LongPeriod => "LongPeriod",
-        mei::space_objects::comet::CometType::Hyperbolic => "Hyperbolic",
-    };
-    dict.set("comet_type", comet_type_str.to_godot());
-    dict.set("mass", comet.mass);
-    dict.set("nucleus_diameter", comet.nucleus_diameter);
-    dict.set("orbital_radius", comet.orbital_radius);
-    dict.set("eccentricity", comet.eccentricity);
-    
-    let mut pos = Dictionary::new();
-    pos.set("x", comet.position.x);
-    pos.set("y", comet.position.y);
-    pos.set("z", comet.position.z);
-    dict.set("position", pos);
-    
+    dict.set("code", error.code.to_godot());
+    dict.set("message", error.message.to_godot());
+    dict.set("offset", error.offset as i64);
     dict
 }
@@ -6,7 +6,27 @@
 
 use godot::prelude::*;
 
-mod galaxy;
+pub mod asteroid;
+pub mod cache;
+pub mod comet;
+pub mod coordinate;
+pub mod evolution;
+pub mod fingerprint;
+pub mod galaxy;
+pub mod morphology;
+pub mod naming;
+pub mod noise;
+pub mod orbit;
+pub mod planet;
+pub mod records;
+pub mod region;
+pub mod rotation;
+pub mod rng;
+pub mod search;
+pub mod stellar;
+pub mod structure_cache;
+pub mod texture_catalog;
+pub mod units;
 
 /// The main extension entry point for MEI Godot integration.
 struct MeiExtension;
@@ -0,0 +1,242 @@
+//! Large-scale shape of the galaxy: bulge, bar, spiral arms and disks.
+//!
+//! MEI does not expose the shape behind its density functions, so the
+//! bindings model it with the Milky Way values MEI is tuned to (see the
+//! README), scaled to the generated galaxy's radius. The disk lies in the
+//! X/Z plane with Y up, like every position MEI returns.
+
+use std::f64::consts::{PI, TAU};
+
+use crate::units::LY_PER_PC;
+
+/// Disk radius the reference values below are given for, in light-years.
+pub const REFERENCE_RADIUS: f64 = 50_000.0;
+
+/// Number of major spiral arms.
+pub const ARM_COUNT: usize = 4;
+
+/// Names of the spiral arms, after the Milky Way arms they stand in for.
+/// Arms 0 and 2 leave the ends of the bar, like the Milky Way's two major
+/// arms.
+pub const ARM_NAMES: [&str; ARM_COUNT] =
+    ["Scutum-Centaurus", "Sagittarius-Carina", "Perseus", "Norma"];
+
+/// Full length of the central bar at the reference radius.
+pub const BAR_LENGTH: f64 = 15_000.0;
+
+/// Full width of the central bar at the reference radius.
+pub const BAR_WIDTH: f64 = 4_000.0;
+
+/// Angle of the bar from the +X axis, in degrees.
+pub const BAR_ANGLE_DEG: f64 = 25.0;
+
+/// Thin disk scale height at the reference radius.
+pub const THIN_DISK_HEIGHT: f64 = 300.0;
+
+/// Thick disk scale height at the reference radius.
+pub const THICK_DISK_HEIGHT: f64 = 1_000.0;
+
+/// Pitch angle of the logarithmic spiral arms, in degrees.
+pub const ARM_PITCH_DEG: f64 = 12.0;
+
+/// Half-width of a spiral arm at the reference radius.
+pub const ARM_HALF_WIDTH: f64 = 750.0;
+
+/// Bulge radius at the reference radius.
+pub const BULGE_RADIUS: f64 = 5_000.0;
+
+/// The Sun's distance from the core at the reference radius. The disk has
+/// solar metallicity there.
+pub const SOLAR_RADIUS: f64 = 26_000.0;
+
+/// Radial metallicity gradient of the disk at the reference radius, in dex
+/// per light-year (-0.06 dex/kpc).
+pub const RADIAL_METALLICITY_GRADIENT: f64 = -0.06 / (1_000.0 * LY_PER_PC);
+
+/// Vertical metallicity gradient of the thin disk and bulge at the
+/// reference radius, in dex per light-year (-0.3 dex/kpc).
+pub const VERTICAL_METALLICITY_GRADIENT: f64 = -0.3 / (1_000.0 * LY_PER_PC);
+
+/// Mean [Fe/H] of the thick disk.
+pub const THICK_DISK_METALLICITY: f64 = -0.5;
+
+/// Mean [Fe/H] of the halo.
+pub const HALO_METALLICITY: f64 = -1.5;
+
+/// Part of the galaxy a position falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Bulge,
+    /// The central bar outside the bulge
+    Bar,
+    /// Within an arm's half-width of a spiral arm
+    Arm,
+    /// Thin disk between the arms
+    Disk,
+    ThickDisk,
+    /// Above the thick disk or beyond the disk's edge
+    Halo,
+}
+
+impl Region {
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Bulge => "Bulge",
+            Region::Bar => "Bar",
+            Region::Arm => "Arm",
+            Region::Disk => "Disk",
+            Region::ThickDisk => "ThickDisk",
+            Region::Halo => "Halo",
+        }
+    }
+}
+
+/// The galaxy's shape, in light-years.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Morphology {
+    pub radius: f64,
+    pub arm_count: usize,
+    pub bar_length: f64,
+    pub bar_width: f64,
+    /// Radians from the +X axis
+    pub bar_angle: f64,
+    pub thin_disk_height: f64,
+    pub thick_disk_height: f64,
+    /// Radians
+    pub arm_pitch: f64,
+    pub arm_half_width: f64,
+    pub bulge_radius: f64,
+}
+
+impl Morphology {
+    /// The reference Milky Way shape scaled to a galaxy of `radius` light-years.
+    pub fn milky_way(radius: f64) -> Self {
+        let scale = radius / REFERENCE_RADIUS;
+        Self {
+            radius,
+            arm_count: ARM_COUNT,
+            bar_length: BAR_LENGTH * scale,
+            bar_width: BAR_WIDTH * scale,
+            bar_angle: BAR_ANGLE_DEG.to_radians(),
+            thin_disk_height: THIN_DISK_HEIGHT * scale,
+            thick_disk_height: THICK_DISK_HEIGHT * scale,
+            arm_pitch: ARM_PITCH_DEG.to_radians(),
+            arm_half_width: ARM_HALF_WIDTH * scale,
+            bulge_radius: BULGE_RADIUS * scale,
+        }
+    }
+
+    /// Angle at which arm `arm` starts. Arms are evenly spaced, so with
+    /// four arms, arms 0 and 2 leave the ends of the bar.
+    pub fn arm_start_angle(&self, arm: usize) -> f64 {
+        self.bar_angle + TAU * arm as f64 / self.arm_count as f64
+    }
+
+    /// Radius at which the arms start: the ends of the bar.
+    pub fn arm_start_radius(&self) -> f64 {
+        self.bar_length / 2.0
+    }
+
+    /// Angle of arm `arm` at galactocentric distance `r`.
+    pub fn arm_angle(&self, arm: usize, r: f64) -> f64 {
+        self.arm_start_angle(arm) + (r / self.arm_start_radius()).ln() / self.arm_pitch.tan()
+    }
+
+    /// Point on arm `arm`'s center line at galactocentric distance `r`, in
+    /// the disk plane.
+    pub fn arm_point(&self, arm: usize, r: f64) -> [f64; 3] {
+        let angle = self.arm_angle(arm, r);
+        [r * angle.cos(), 0.0, r * angle.sin()]
+    }
+
+    /// `samples` points along arm `arm`'s center line from the end of the
+    /// bar to the disk's edge. The points are evenly spaced in radius, which
+    /// on a logarithmic spiral also spaces them evenly along the arm.
+    pub fn arm_spline(&self, arm: usize, samples: usize) -> Vec<[f64; 3]> {
+        let start = self.arm_start_radius();
+        let step = (self.radius - start) / (samples.max(2) - 1) as f64;
+        (0..samples)
+            .map(|i| self.arm_point(arm, start + step * i as f64))
+            .collect()
+    }
+
+    /// Name of arm `arm`, or `None` past the named arms.
+    pub fn arm_name(&self, arm: usize) -> Option<&'static str> {
+        ARM_NAMES.get(arm).copied()
+    }
+
+    /// Nearest arm to a disk position and the approximate distance to its
+    /// center line, or `None` inside the bar where there are no arms.
+    pub fn nearest_arm(&self, position: [f64; 3]) -> Option<(usize, f64)> {
+        let r = position[0].hypot(position[2]);
+        if r < self.arm_start_radius() {
+            return None;
+        }
+        let theta = position[2].atan2(position[0]);
+        (0..self.arm_count)
+            .map(|arm| {
+                let delta = (theta - self.arm_angle(arm, r) + PI).rem_euclid(TAU) - PI;
+                // Perpendicular distance to a logarithmic spiral
+                (arm, r * delta.abs() * self.arm_pitch.sin())
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Approximate distance from a disk position to the nearest arm's
+    /// center line, or `None` inside the bar where there are no arms.
+    pub fn arm_distance(&self, position: [f64; 3]) -> Option<f64> {
+        self.nearest_arm(position).map(|(_, distance)| distance)
+    }
+
+    /// Whether a position lies within the bar's footprint and no further
+    /// from the plane than the thick disk's scale height.
+    pub fn in_bar(&self, position: [f64; 3]) -> bool {
+        let (sin, cos) = self.bar_angle.sin_cos();
+        let along = position[0] * cos + position[2] * sin;
+        let across = position[2] * cos - position[0] * sin;
+        along.abs() < self.bar_length / 2.0
+            && across.abs() < self.bar_width / 2.0
+            && position[1].abs() < self.thick_disk_height
+    }
+
+    /// Region containing a position.
+    pub fn region(&self, position: [f64; 3]) -> Region {
+        let r = position[0].hypot(position[2]);
+        let height = position[1].abs();
+        if r.hypot(height) < self.bulge_radius {
+            Region::Bulge
+        } else if height > 2.0 * self.thick_disk_height || r > self.radius {
+            Region::Halo
+        } else if self.in_bar(position) {
+            Region::Bar
+        } else if height > 2.0 * self.thin_disk_height {
+            Region::ThickDisk
+        } else if self
+            .arm_distance(position)
+            .is_some_and(|d| d < self.arm_half_width)
+        {
+            Region::Arm
+        } else {
+            Region::Disk
+        }
+    }
+
+    /// Estimated iron abundance [Fe/H] in dex at a position. The thin disk,
+    /// arms, bar and bulge follow the Milky Way's gradients: solar at the
+    /// Sun's radius, falling outwards and away from the plane, and flat
+    /// inside the ends of the bar. The thick disk and halo take their mean
+    /// values.
+    pub fn metallicity(&self, position: [f64; 3]) -> f64 {
+        match self.region(position) {
+            Region::ThickDisk => THICK_DISK_METALLICITY,
+            Region::Halo => HALO_METALLICITY,
+            Region::Bulge | Region::Bar | Region::Arm | Region::Disk => {
+                let scale = self.radius / REFERENCE_RADIUS;
+                let r = position[0].hypot(position[2]).max(self.arm_start_radius());
+                (RADIAL_METALLICITY_GRADIENT * (r - SOLAR_RADIUS * scale)
+                    + VERTICAL_METALLICITY_GRADIENT * position[1].abs())
+                    / scale
+            }
+        }
+    }
+}
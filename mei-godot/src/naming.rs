@@ -0,0 +1,622 @@
+use godot::classes::{IResource, Resource};
+use godot::prelude::*;
+
+use crate::rng::{hash_combine, DetRng};
+
+/// Edge length of a designation sector in light-years.
+pub const SECTOR_SIZE_LY: f64 = 250.0;
+
+/// Offset applied to sector X/Z indices so they fit in two letters (AA..ZZ).
+const SECTOR_LETTER_OFFSET: i64 = 338;
+
+/// Stars at least this luminous (solar luminosities) get a Bayer-like label.
+pub const BRIGHT_STAR_LUMINOSITY: f64 = 10.0;
+
+/// Syllable tables for procedural names.
+///
+/// Assign one to `MeiGalaxy.name_style` to reskin every generated name.
+/// Names are built as `prefix + middle* + suffix`, with the number of middle
+/// syllables between `min_syllables - 2` and `max_syllables - 2`.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct MeiNameStyle {
+    base: Base<Resource>,
+    /// First syllables (capitalized)
+    #[export]
+    #[var(get, set = set_prefixes)]
+    prefixes: PackedStringArray,
+    /// Inner syllables
+    #[export]
+    #[var(get, set = set_middles)]
+    middles: PackedStringArray,
+    /// Final syllables
+    #[export]
+    #[var(get, set = set_suffixes)]
+    suffixes: PackedStringArray,
+    /// Minimum syllables per name (including prefix and suffix)
+    #[export]
+    #[var(get, set = set_min_syllables)]
+    min_syllables: i64,
+    /// Maximum syllables per name (including prefix and suffix)
+    #[export]
+    #[var(get, set = set_max_syllables)]
+    max_syllables: i64,
+    /// Letter names for bright-star labels, brightest first
+    #[export]
+    #[var(get, set = set_bayer_letters)]
+    bayer_letters: PackedStringArray,
+    /// Bumped on every table change, so users can tell cheaply whether
+    /// names built earlier are stale
+    version: u64,
+}
+
+#[godot_api]
+impl IResource for MeiNameStyle {
+    /// Initializes the built-in name style.
+    fn init(base: Base<Resource>) -> Self {
+        let style = NameStyle::default();
+        let packed = |items: &[String]| -> PackedStringArray {
+            items.iter().map(|s| GString::from(s.as_str())).collect()
+        };
+
+        Self {
+            base,
+            prefixes: packed(&style.prefixes),
+            middles: packed(&style.middles),
+            suffixes: packed(&style.suffixes),
+            min_syllables: style.min_syllables as i64,
+            max_syllables: style.max_syllables as i64,
+            bayer_letters: packed(&style.bayer_letters),
+            version: 0,
+        }
+    }
+}
+
+#[godot_api]
+impl MeiNameStyle {
+    #[func]
+    fn set_prefixes(&mut self, prefixes: PackedStringArray) {
+        self.prefixes = prefixes;
+        self.version += 1;
+    }
+
+    #[func]
+    fn set_middles(&mut self, middles: PackedStringArray) {
+        self.middles = middles;
+        self.version += 1;
+    }
+
+    #[func]
+    fn set_suffixes(&mut self, suffixes: PackedStringArray) {
+        self.suffixes = suffixes;
+        self.version += 1;
+    }
+
+    #[func]
+    fn set_min_syllables(&mut self, min_syllables: i64) {
+        self.min_syllables = min_syllables;
+        self.version += 1;
+    }
+
+    #[func]
+    fn set_max_syllables(&mut self, max_syllables: i64) {
+        self.max_syllables = max_syllables;
+        self.version += 1;
+    }
+
+    #[func]
+    fn set_bayer_letters(&mut self, bayer_letters: PackedStringArray) {
+        self.bayer_letters = bayer_letters;
+        self.version += 1;
+    }
+}
+
+impl MeiNameStyle {
+    /// Number of table changes so far.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Snapshot of the tables as a plain Rust [`NameStyle`].
+    ///
+    /// Empty tables fall back to the built-in ones.
+    pub fn to_style(&self) -> NameStyle {
+        let defaults = NameStyle::default();
+        let list = |packed: &PackedStringArray, fallback: Vec<String>| {
+            let items: Vec<String> = packed.as_slice().iter().map(|s| s.to_string()).collect();
+            if items.is_empty() {
+                fallback
+            } else {
+                items
+            }
+        };
+
+        NameStyle {
+            prefixes: list(&self.prefixes, defaults.prefixes),
+            middles: list(&self.middles, defaults.middles),
+            suffixes: list(&self.suffixes, defaults.suffixes),
+            min_syllables: self.min_syllables.max(2) as usize,
+            max_syllables: self.max_syllables.max(self.min_syllables).max(2) as usize,
+            bayer_letters: list(&self.bayer_letters, defaults.bayer_letters),
+        }
+    }
+}
+
+/// Plain syllable tables used by the naming functions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameStyle {
+    pub prefixes: Vec<String>,
+    pub middles: Vec<String>,
+    pub suffixes: Vec<String>,
+    pub min_syllables: usize,
+    pub max_syllables: usize,
+    pub bayer_letters: Vec<String>,
+}
+
+impl Default for NameStyle {
+    fn default() -> Self {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            prefixes: strings(&[
+                "Al", "Bel", "Cor", "Dra", "Eth", "Fal", "Gar", "Hel", "Ka", "Lor", "Mor", "Ny",
+                "Ori", "Pha", "Qu", "Rho", "Sel", "Tau", "Ul", "Ve", "Xan", "Zan",
+            ]),
+            middles: strings(&[
+                "ra", "li", "no", "ve", "ta", "ri", "sa", "ne", "do", "mi", "ce", "lu",
+            ]),
+            suffixes: strings(&[
+                "th", "n", "r", "x", "s", "on", "is", "ar", "a", "us", "ia", "or",
+            ]),
+            min_syllables: 2,
+            max_syllables: 4,
+            bayer_letters: strings(&[
+                "Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Eta", "Theta", "Iota",
+                "Kappa", "Lambda", "Mu", "Nu", "Xi", "Omicron", "Pi", "Rho", "Sigma", "Tau",
+                "Upsilon", "Phi", "Chi", "Psi", "Omega",
+            ]),
+        }
+    }
+}
+
+impl NameStyle {
+    /// Generates a proper name from a seed.
+    pub fn proper_name(&self, seed: u64) -> String {
+        let mut rng = DetRng::new(seed);
+        let span = self.max_syllables.saturating_sub(self.min_syllables) + 1;
+        let syllables = self.min_syllables + rng.index(span);
+
+        let mut name = pick(&self.prefixes, &mut rng);
+        for _ in 0..syllables.saturating_sub(2) {
+            name.push_str(&pick(&self.middles, &mut rng));
+        }
+        name.push_str(&pick(&self.suffixes, &mut rng));
+        name
+    }
+
+    /// Name of the constellation-like group a sector's bright stars belong to.
+    pub fn constellation_name(&self, galaxy_seed: u64, sector: SectorCoord) -> String {
+        let seed = hash_combine(galaxy_seed, sector.key());
+        genitive(&self.proper_name(seed))
+    }
+
+    /// Bayer/Flamsteed-like label (e.g. "17 Kappa Velonis") for bright stars.
+    ///
+    /// Brighter stars get earlier letters; the number disambiguates stars that
+    /// share a letter.
+    pub fn bayer_label(
+        &self,
+        star_seed: u64,
+        luminosity: f64,
+        constellation: &str,
+    ) -> Option<String> {
+        if luminosity < BRIGHT_STAR_LUMINOSITY || self.bayer_letters.is_empty() {
+            return None;
+        }
+
+        let mut rng = DetRng::new(hash_combine(star_seed, 0xba7e));
+        // log10(L) from 1 (dimmest labelled) to 6 (brightest) spreads across the alphabet
+        let brightness = ((6.0 - luminosity.log10()) / 5.0).clamp(0.0, 1.0);
+        let letters = self.bayer_letters.len();
+        let band = (brightness * (letters - 1) as f64) as usize;
+        let letter = (band + rng.index(2)).min(letters - 1);
+        let number = 1 + rng.index(120);
+        Some(format!(
+            "{} {} {}",
+            number, self.bayer_letters[letter], constellation
+        ))
+    }
+}
+
+fn pick(items: &[String], rng: &mut DetRng) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        items[rng.index(items.len())].clone()
+    }
+}
+
+/// Latin-flavoured genitive, as used in constellation labels ("Velon" -> "Velonis").
+fn genitive(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("us") {
+        format!("{}i", stem)
+    } else if name.ends_with('a') {
+        format!("{}e", name)
+    } else if name.ends_with("is") {
+        name.to_string()
+    } else {
+        format!("{}is", name)
+    }
+}
+
+/// Integer coordinates of a designation sector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectorCoord {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl SectorCoord {
+    /// Sector containing a galactic position in light-years.
+    pub fn containing(position: [f64; 3]) -> Self {
+        Self {
+            x: (position[0] / SECTOR_SIZE_LY).floor() as i64,
+            y: (position[1] / SECTOR_SIZE_LY).floor() as i64,
+            z: (position[2] / SECTOR_SIZE_LY).floor() as i64,
+        }
+    }
+
+    /// Minimum corner of the sector in light-years.
+    pub fn origin(&self) -> [f64; 3] {
+        [
+            self.x as f64 * SECTOR_SIZE_LY,
+            self.y as f64 * SECTOR_SIZE_LY,
+            self.z as f64 * SECTOR_SIZE_LY,
+        ]
+    }
+
+    /// Sector code such as `"NAMQ+3"`: two letters each for X and Z, then signed Y.
+    pub fn code(&self) -> String {
+        format!(
+            "{}{}{:+}",
+            letter_pair(self.x + SECTOR_LETTER_OFFSET),
+            letter_pair(self.z + SECTOR_LETTER_OFFSET),
+            self.y
+        )
+    }
+
+    fn key(&self) -> u64 {
+        hash_combine(hash_combine(self.x as u64, self.y as u64), self.z as u64)
+    }
+}
+
+fn letter_pair(value: i64) -> String {
+    let v = value.clamp(0, 26 * 26 - 1);
+    let letter = |n: i64| (b'A' + n as u8) as char;
+    format!("{}{}", letter(v / 26), letter(v % 26))
+}
+
+/// Number of a star within its sector: the index of its generator cell.
+///
+/// MEI places at most one star per `cell_size` cell, so the cell identifies
+/// the star and can be turned back into a position.
+pub fn star_number(position: [f64; 3], sector: SectorCoord, cell_size: f64) -> u64 {
+    let cells = cells_per_sector(cell_size);
+    let origin = sector.origin();
+    let local = |axis: usize| {
+        (((position[axis] - origin[axis]) / cell_size).floor() as i64).clamp(0, cells as i64 - 1)
+            as u64
+    };
+    (local(1) * cells + local(2)) * cells + local(0)
+}
+
+/// Number of generator cells along one sector edge.
+pub fn cells_per_sector(cell_size: f64) -> u64 {
+    (SECTOR_SIZE_LY / cell_size.max(1e-3)).round().max(1.0) as u64
+}
+
+/// Catalog designation such as `"Sector NAMQ+3 1234567"`.
+pub fn catalog_designation(position: [f64; 3], cell_size: f64) -> String {
+    let sector = SectorCoord::containing(position);
+    format!(
+        "Sector {} {}",
+        sector.code(),
+        star_number(position, sector, cell_size)
+    )
+}
+
+/// Planet letter by orbital order: 0 -> "b", 1 -> "c", ... 24 -> "z", 25 -> "ba".
+pub fn planet_letter(index: usize) -> String {
+    let mut n = index + 1;
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+    }
+    letters.iter().rev().collect()
+}
+
+/// Moon numeral by orbital order: 0 -> "I", 1 -> "II", ...
+pub fn moon_numeral(index: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut n = index + 1;
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+/// Inverse of [`planet_letter`]: "b" -> 0, "ba" -> 25.
+pub fn parse_planet_letter(text: &str) -> Option<usize> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_lowercase()) {
+        return None;
+    }
+    let n = text.bytes().try_fold(0usize, |n, b| {
+        n.checked_mul(26)?.checked_add((b - b'a') as usize)
+    })?;
+    let index = n.checked_sub(1)?;
+    // Reject spellings planet_letter never produces, such as "ab"
+    (planet_letter(index) == text).then_some(index)
+}
+
+/// Inverse of [`moon_numeral`]: "I" -> 0, "XIV" -> 13.
+pub fn parse_moon_numeral(text: &str) -> Option<usize> {
+    let value = |c: char| match c {
+        'I' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        'D' => Some(500),
+        'M' => Some(1000),
+        _ => None,
+    };
+    let digits: Vec<usize> = text.chars().map(value).collect::<Option<_>>()?;
+    let mut total = 0;
+    for (i, digit) in digits.iter().enumerate() {
+        if digits.get(i + 1).is_some_and(|next| next > digit) {
+            total -= *digit as i64;
+        } else {
+            total += *digit as i64;
+        }
+    }
+    let index = usize::try_from(total).ok()?.checked_sub(1)?;
+    // Reject non-canonical numerals such as "IIII"
+    (moon_numeral(index) == text).then_some(index)
+}
+
+/// Inverse of [`SectorCoord::code`].
+pub fn parse_sector_code(code: &str) -> Option<SectorCoord> {
+    let bytes = code.as_bytes();
+    if bytes.len() < 6 || !bytes[..4].iter().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    if bytes[4] != b'+' && bytes[4] != b'-' {
+        return None;
+    }
+    let pair = |i: usize| (bytes[i] - b'A') as i64 * 26 + (bytes[i + 1] - b'A') as i64;
+    Some(SectorCoord {
+        x: pair(0) - SECTOR_LETTER_OFFSET,
+        y: code[4..].parse().ok()?,
+        z: pair(2) - SECTOR_LETTER_OFFSET,
+    })
+}
+
+/// Center of the generator cell a star number refers to.
+pub fn cell_center(sector: SectorCoord, number: u64, cell_size: f64) -> [f64; 3] {
+    let cells = cells_per_sector(cell_size);
+    let origin = sector.origin();
+    let (x, z, y) = (
+        number % cells,
+        (number / cells) % cells,
+        number / (cells * cells),
+    );
+    [
+        origin[0] + (x as f64 + 0.5) * cell_size,
+        origin[1] + (y as f64 + 0.5) * cell_size,
+        origin[2] + (z as f64 + 0.5) * cell_size,
+    ]
+}
+
+/// What a designation string refers to, before any star lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DesignationQuery {
+    /// "Sector NAMQ+3 1234567"
+    Catalog { sector: SectorCoord, number: u64 },
+    /// "17 Kappa Velonis"
+    Bayer { label: String },
+    /// "Velonis"
+    Name { name: String },
+}
+
+/// A parsed designation plus the optional planet/moon path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedDesignation {
+    pub query: DesignationQuery,
+    pub planet: Option<usize>,
+    pub moon: Option<usize>,
+}
+
+/// Why a designation could not be parsed or resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignationError {
+    /// Stable machine-readable code, e.g. `"bad_sector_code"`
+    pub code: &'static str,
+    pub message: String,
+    /// Byte offset of the offending token in the input
+    pub offset: usize,
+}
+
+impl DesignationError {
+    pub fn new(code: &'static str, message: impl Into<String>, offset: usize) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            offset,
+        }
+    }
+}
+
+/// Parses a catalog designation, Bayer-like label or proper name, each
+/// optionally followed by a planet letter and moon numeral.
+///
+/// Grammar:
+/// ```text
+/// designation := (catalog | bayer | name) [planet [moon]]
+/// catalog     := "Sector" sector-code number
+/// sector-code := letter letter letter letter ("+" | "-") digits   (e.g. "NAMQ+3")
+/// bayer       := number bayer-letter constellation
+/// ```
+pub fn parse_designation(
+    text: &str,
+    style: &NameStyle,
+    cells_per_sector: u64,
+) -> Result<ParsedDesignation, DesignationError> {
+    let tokens: Vec<(usize, &str)> = text
+        .split_whitespace()
+        .map(|t| (t.as_ptr() as usize - text.as_ptr() as usize, t))
+        .collect();
+    let Some(&(first_at, first)) = tokens.first() else {
+        return Err(DesignationError::new("empty", "Designation is empty", 0));
+    };
+
+    let is_bayer_letter = |t: &str| {
+        style
+            .bayer_letters
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(t))
+    };
+    let (query, consumed) = if first.eq_ignore_ascii_case("sector") {
+        let &(code_at, code) = tokens.get(1).ok_or_else(|| {
+            DesignationError::new(
+                "missing_sector_code",
+                "Expected a sector code after \"Sector\"",
+                text.len(),
+            )
+        })?;
+        let sector = parse_sector_code(&code.to_ascii_uppercase()).ok_or_else(|| {
+            DesignationError::new(
+                "bad_sector_code",
+                format!("\"{}\" is not a sector code like \"NAMQ+3\"", code),
+                code_at,
+            )
+        })?;
+        let &(number_at, number) = tokens.get(2).ok_or_else(|| {
+            DesignationError::new(
+                "missing_number",
+                "Expected a star number after the sector code",
+                text.len(),
+            )
+        })?;
+        let number: u64 = number.parse().map_err(|_| {
+            DesignationError::new(
+                "bad_number",
+                format!("\"{}\" is not a star number", number),
+                number_at,
+            )
+        })?;
+        if number >= cells_per_sector.saturating_pow(3) {
+            return Err(DesignationError::new(
+                "number_out_of_range",
+                format!("Star number {} is outside the sector", number),
+                number_at,
+            ));
+        }
+        (DesignationQuery::Catalog { sector, number }, 3)
+    } else if first.parse::<u32>().is_ok() && tokens.len() >= 3 && is_bayer_letter(tokens[1].1) {
+        let label = tokens[..3]
+            .iter()
+            .map(|(_, t)| *t)
+            .collect::<Vec<_>>()
+            .join(" ");
+        (DesignationQuery::Bayer { label }, 3)
+    } else if first.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DesignationError::new(
+            "unknown_format",
+            "Expected \"Sector <code> <number>\", a bright-star label or a name",
+            first_at,
+        ));
+    } else {
+        (
+            DesignationQuery::Name {
+                name: first.to_string(),
+            },
+            1,
+        )
+    };
+
+    let mut rest = tokens[consumed..].iter();
+    let mut planet = None;
+    let mut moon = None;
+    if let Some(&(at, token)) = rest.next() {
+        planet = Some(parse_planet_letter(token).ok_or_else(|| {
+            DesignationError::new(
+                "bad_planet_letter",
+                format!("\"{}\" is not a planet letter", token),
+                at,
+            )
+        })?);
+    }
+    if let Some(&(at, token)) = rest.next() {
+        moon = Some(parse_moon_numeral(token).ok_or_else(|| {
+            DesignationError::new(
+                "bad_moon_numeral",
+                format!("\"{}\" is not a moon numeral", token),
+                at,
+            )
+        })?);
+    }
+    if let Some(&(at, token)) = rest.next() {
+        return Err(DesignationError::new(
+            "trailing_input",
+            format!("Unexpected \"{}\" after the moon numeral", token),
+            at,
+        ));
+    }
+
+    Ok(ParsedDesignation {
+        query,
+        planet,
+        moon,
+    })
+}
+
+/// Case-insensitive edit distance, for near-miss name suggestions.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = (previous + (ca != cb) as usize)
+                .min(row[j] + 1)
+                .min(current + 1);
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
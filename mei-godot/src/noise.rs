@@ -0,0 +1,117 @@
+//! Seeded 3D gradient noise for procedural textures.
+//!
+//! Textures are sampled on the unit sphere rather than in texture space, so
+//! equirectangular maps built from this noise have no seam at the date line
+//! and no pinching at the poles.
+
+use crate::rng::DetRng;
+
+/// Improved Perlin noise with a permutation table shuffled from a seed.
+pub struct Perlin3 {
+    perm: [u8; 512],
+}
+
+impl Perlin3 {
+    /// Builds a noise field from a seed. The same seed always gives the same field.
+    pub fn new(seed: u64) -> Self {
+        let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut rng = DetRng::new(seed);
+        for i in (1..256).rev() {
+            let j = rng.index(i + 1);
+            table.swap(i, j);
+        }
+
+        let perm = std::array::from_fn(|i| table[i & 255]);
+        Self { perm }
+    }
+
+    /// Samples noise at a point. Output is roughly in `[-1, 1]`.
+    pub fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        let xi = x.floor() as i64 & 255;
+        let yi = y.floor() as i64 & 255;
+        let zi = z.floor() as i64 & 255;
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+        let u = fade(xf);
+        let v = fade(yf);
+        let w = fade(zf);
+
+        let p = &self.perm;
+        let (xi, yi, zi) = (xi as usize, yi as usize, zi as usize);
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p[aa], xf, yf, zf), grad(p[ba], xf - 1.0, yf, zf)),
+                lerp(
+                    u,
+                    grad(p[ab], xf, yf - 1.0, zf),
+                    grad(p[bb], xf - 1.0, yf - 1.0, zf),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p[aa + 1], xf, yf, zf - 1.0),
+                    grad(p[ba + 1], xf - 1.0, yf, zf - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p[ab + 1], xf, yf - 1.0, zf - 1.0),
+                    grad(p[bb + 1], xf - 1.0, yf - 1.0, zf - 1.0),
+                ),
+            ),
+        )
+    }
+
+    /// Fractal Brownian motion: `octaves` layers of noise, each at double the
+    /// frequency and half the amplitude of the previous one. Output is
+    /// normalized to roughly `[-1, 1]`.
+    pub fn fbm(&self, x: f64, y: f64, z: f64, octaves: u32) -> f64 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut norm = 0.0;
+        for _ in 0..octaves {
+            sum += amplitude * self.sample(x * frequency, y * frequency, z * frequency);
+            norm += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        if norm > 0.0 {
+            sum / norm
+        } else {
+            0.0
+        }
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -0,0 +1,501 @@
+use std::f64::consts::TAU;
+
+use godot::classes::mesh::{ArrayType, PrimitiveType};
+use godot::classes::{ArrayMesh, RefCounted};
+use godot::meta::ArrayElement;
+use godot::obj::EngineEnum;
+use godot::prelude::*;
+
+use crate::rng::DetRng;
+use crate::units::{ecliptic_to_local, KM_PER_AU};
+
+/// Orbit line mesh builders.
+///
+/// Orbits are described by `Dictionary` orbital elements. Planet and comet
+/// dictionaries from `get_star_system` can be passed directly; any missing
+/// element defaults to a circular, uninclined orbit:
+/// - `semi_major_axis` (falls back to `orbital_radius`)
+/// - `eccentricity`
+/// - `inclination` (radians)
+/// - `longitude_of_ascending_node` (radians)
+/// - `argument_of_periapsis` (radians)
+///
+/// Meshes use Godot's frame: the reference plane is XZ and Y is up.
+#[derive(GodotClass)]
+#[class(base=RefCounted, init)]
+pub struct MeiOrbit {
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl MeiOrbit {
+    /// Builds a line strip mesh for a single orbit.
+    ///
+    /// # Arguments
+    ///
+    /// * `orbital_elements` - Orbital elements `Dictionary` (or a planet/comet dictionary)
+    /// * `segments` - Number of line segments around the orbit
+    /// * `scale` - Multiplier from orbit units to visual units (e.g. `SYSTEM_SCALE`)
+    ///
+    /// # Returns
+    ///
+    /// An `ArrayMesh` with one `PRIMITIVE_LINE_STRIP` surface (empty for degenerate orbits)
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var mesh = MeiOrbit.build_orbit_mesh(comet, 128, SYSTEM_SCALE)
+    /// ```
+    #[func]
+    fn build_orbit_mesh(orbital_elements: Dictionary, segments: i64, scale: f64) -> Gd<ArrayMesh> {
+        let elements = OrbitalElements::from_dict(&orbital_elements);
+        let points = elements.orbit_points(segments.max(3) as usize);
+
+        let vertices: PackedVector3Array = points.iter().map(|p| to_vector3(p, scale)).collect();
+        let mut mesh = ArrayMesh::new_gd();
+        if vertices.len() >= 2 {
+            mesh.add_surface_from_arrays(
+                PrimitiveType::LINE_STRIP,
+                &surface_arrays(vertices, None),
+            );
+        }
+        mesh
+    }
+
+    /// Builds one line mesh containing many orbits, for a single draw call.
+    ///
+    /// Each entry may also carry:
+    /// - `center`: `Vector3` offset in orbit units (e.g. a moon's parent planet)
+    /// - `color`: `Color` written to the vertex colors
+    ///
+    /// # Arguments
+    ///
+    /// * `orbits` - Array of orbital elements dictionaries
+    /// * `segments` - Number of line segments per orbit
+    /// * `scale` - Multiplier from orbit units to visual units
+    ///
+    /// # Returns
+    ///
+    /// An `ArrayMesh` with one `PRIMITIVE_LINES` surface
+    #[func]
+    fn build_orbit_mesh_batch(orbits: VariantArray, segments: i64, scale: f64) -> Gd<ArrayMesh> {
+        let segments = segments.max(3) as usize;
+        let mut vertices = PackedVector3Array::new();
+        let mut colors = PackedColorArray::new();
+
+        for orbit in orbits.iter_shared() {
+            let Ok(dict) = orbit.try_to::<Dictionary>() else {
+                continue;
+            };
+            let center = dict
+                .get("center")
+                .and_then(|v| v.try_to::<Vector3>().ok())
+                .unwrap_or(Vector3::ZERO);
+            let color = dict
+                .get("color")
+                .and_then(|v| v.try_to::<Color>().ok())
+                .unwrap_or(Color::WHITE);
+
+            let points = OrbitalElements::from_dict(&dict).orbit_points(segments);
+            for pair in points.windows(2) {
+                vertices.push(center * scale as f32 + to_vector3(&pair[0], scale));
+                vertices.push(center * scale as f32 + to_vector3(&pair[1], scale));
+                colors.push(color);
+                colors.push(color);
+            }
+        }
+
+        let mut mesh = ArrayMesh::new_gd();
+        if !vertices.is_empty() {
+            mesh.add_surface_from_arrays(
+                PrimitiveType::LINES,
+                &surface_arrays(vertices, Some(colors)),
+            );
+        }
+        mesh
+    }
+
+    /// Places every planet and moon of a system on the orbit
+    /// `build_system_orbit_mesh` draws for it.
+    ///
+    /// Each body sits at a point of its orbit picked from its `seed`, so a
+    /// system looks the same on every visit. Planets orbit their stellar
+    /// component's barycenter (or the system center) and moons their planet.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - A system `Dictionary` from `get_star_system`
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `planet_positions`: `Dictionary` of planet `seed` to `Vector3` in AU
+    /// - `moon_positions`: `Dictionary` of moon `seed` to `Vector3` in AU
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var layout = MeiOrbit.get_system_layout(system)
+    /// planet_node.position = layout["planet_positions"][planet["seed"]] * SYSTEM_SCALE
+    /// ```
+    #[func]
+    fn get_system_layout(system: Dictionary) -> Dictionary {
+        let mut planet_positions = Dictionary::new();
+        let mut moon_positions = Dictionary::new();
+        for_each_planet(&system, |_, center, planet| {
+            let position = center + placed_on_orbit(&OrbitalElements::from_dict(&planet), &planet);
+            planet_positions.set(seed_of(&planet), position);
+            for moon in array_items::<Dictionary>(&planet, "moons") {
+                let moon_position = position + placed_on_orbit(&moon_elements(&moon), &moon);
+                moon_positions.set(seed_of(&moon), moon_position);
+            }
+        });
+
+        let mut result = Dictionary::new();
+        result.set("planet_positions", planet_positions);
+        result.set("moon_positions", moon_positions);
+        result
+    }
+
+    /// Builds one line mesh with every orbit in a system, for a single draw call.
+    ///
+    /// Includes planets and their moons and the Oort cloud's `notable_comets`.
+    /// When the system has `stellar_components`, planets are taken from each
+    /// component, around its barycenter, rather than from the top-level lists.
+    /// Moon orbits are centered on their planet.
+    ///
+    /// `layout` may contain:
+    /// - `planet_positions`: `Dictionary` of planet `seed` to `Vector3` in AU,
+    ///   where each planet's moon orbits are centered (defaults to
+    ///   `get_system_layout`'s)
+    /// - `planet_color`: `Color` for planet and comet orbits
+    /// - `component_colors`: `Array` of `Color` cycled across stellar components
+    /// - `moon_color`: `Color` for moon orbits
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - A system `Dictionary` from `get_star_system`
+    /// * `layout` - Placement and color overrides (may be empty)
+    /// * `segments` - Number of line segments per orbit
+    /// * `scale` - Multiplier from AU to visual units
+    ///
+    /// # Returns
+    ///
+    /// An `ArrayMesh` with one `PRIMITIVE_LINES` surface
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var layout = {"moon_color": Color(0.5, 0.6, 0.8, 0.3)}
+    /// orbit_lines.mesh = MeiOrbit.build_system_orbit_mesh(system, layout, 64, SYSTEM_SCALE)
+    /// ```
+    #[func]
+    fn build_system_orbit_mesh(
+        system: Dictionary,
+        layout: Dictionary,
+        segments: i64,
+        scale: f64,
+    ) -> Gd<ArrayMesh> {
+        let color = |key: &str| layout.get(key).and_then(|v| v.try_to::<Color>().ok());
+        let planet_color = color("planet_color").unwrap_or(Color::WHITE);
+        let moon_color = color("moon_color").unwrap_or(planet_color);
+        let planet_positions = layout
+            .get("planet_positions")
+            .and_then(|v| v.try_to::<Dictionary>().ok())
+            .or_else(|| {
+                Self::get_system_layout(system.clone())
+                    .get("planet_positions")
+                    .and_then(|v| v.try_to::<Dictionary>().ok())
+            })
+            .unwrap_or_default();
+        let component_colors: Vec<Color> = array_items(&layout, "component_colors");
+
+        let mut orbits = VariantArray::new();
+        for_each_planet(&system, |component, center, planet| {
+            let color = match component {
+                Some(i) if !component_colors.is_empty() => {
+                    component_colors[i % component_colors.len()]
+                }
+                _ => planet_color,
+            };
+            let mut entry = planet.duplicate_shallow();
+            entry.set("center", center);
+            entry.set("color", color);
+            orbits.push(&entry.to_variant());
+
+            let planet_position = planet_positions
+                .get(seed_of(&planet))
+                .and_then(|v| v.try_to::<Vector3>().ok())
+                .unwrap_or(center);
+            for moon in array_items::<Dictionary>(&planet, "moons") {
+                let mut entry = moon.duplicate_shallow();
+                entry.set("semi_major_axis", moon_elements(&moon).semi_major_axis);
+                entry.set("center", planet_position);
+                entry.set("color", moon_color);
+                orbits.push(&entry.to_variant());
+            }
+        });
+
+        let comets: Vec<Dictionary> = system
+            .get("oort_cloud")
+            .and_then(|v| v.try_to::<Dictionary>().ok())
+            .map(|oort| array_items(&oort, "notable_comets"))
+            .unwrap_or_default();
+        for comet in comets {
+            let mut entry = comet.duplicate_shallow();
+            entry.set("color", planet_color);
+            orbits.push(&entry.to_variant());
+        }
+
+        Self::build_orbit_mesh_batch(orbits, segments, scale)
+    }
+}
+
+/// Calls `f` with each inner and outer planet of `system`, the index of the
+/// stellar component it orbits and the center of its orbit in AU. With
+/// `stellar_components`, planets come from each component, around its
+/// barycenter; otherwise from the top-level lists, around the origin.
+fn for_each_planet(system: &Dictionary, mut f: impl FnMut(Option<usize>, Vector3, Dictionary)) {
+    let planets = |dict: &Dictionary| {
+        ["inner_planets", "outer_planets"]
+            .into_iter()
+            .flat_map(|key| array_items::<Dictionary>(dict, key))
+            .collect::<Vec<_>>()
+    };
+
+    let components: Vec<Dictionary> = array_items(system, "stellar_components");
+    if components.is_empty() {
+        for planet in planets(system) {
+            f(None, Vector3::ZERO, planet);
+        }
+    }
+    for (i, component) in components.iter().enumerate() {
+        // Barycenters are in the system local frame, like the orbits
+        let center = component
+            .get("barycenter")
+            .and_then(|v| v.try_to::<Dictionary>().ok())
+            .map(|d| vector_from_dict(&d))
+            .unwrap_or(Vector3::ZERO);
+        for planet in planets(component) {
+            f(Some(i), center, planet);
+        }
+    }
+}
+
+/// A moon's elements, with its orbital radius turned from km to AU.
+fn moon_elements(moon: &Dictionary) -> OrbitalElements {
+    let radius_km = moon
+        .get("orbital_radius")
+        .and_then(|v| v.try_to::<f64>().ok())
+        .unwrap_or(0.0);
+    OrbitalElements {
+        semi_major_axis: radius_km.abs() / KM_PER_AU,
+        ..OrbitalElements::from_dict(moon)
+    }
+}
+
+fn seed_of(body: &Dictionary) -> i64 {
+    body.get("seed")
+        .and_then(|v| v.try_to::<i64>().ok())
+        .unwrap_or(0)
+}
+
+/// Offset from its orbit's center (AU) of a body placed at a mean anomaly
+/// drawn from its seed.
+fn placed_on_orbit(elements: &OrbitalElements, body: &Dictionary) -> Vector3 {
+    let mean_anomaly = DetRng::new(seed_of(body) as u64).range(0.0, TAU);
+    let true_anomaly = true_anomaly_from_mean(mean_anomaly, elements.eccentricity);
+    to_vector3(&elements.position_at(true_anomaly), 1.0)
+}
+
+/// Elements of a typed (e.g. `Array[Dictionary]` from `get_star_system`) or
+/// untyped array entry, skipping any of another type.
+fn array_items<T: ArrayElement + FromGodot>(dict: &Dictionary, key: &str) -> Vec<T> {
+    let Some(value) = dict.get(key) else {
+        return Vec::new();
+    };
+    if let Ok(array) = value.try_to::<Array<T>>() {
+        return array.iter_shared().collect();
+    }
+    value
+        .try_to::<VariantArray>()
+        .map(|array| array.iter_shared().filter_map(|v| v.try_to::<T>().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn vector_from_dict(dict: &Dictionary) -> Vector3 {
+    let get = |key: &str| {
+        dict.get(key)
+            .and_then(|v| v.try_to::<f64>().ok())
+            .unwrap_or(0.0) as f32
+    };
+    Vector3::new(get("x"), get("y"), get("z"))
+}
+
+fn to_vector3(p: &[f64; 3], scale: f64) -> Vector3 {
+    Vector3::new(
+        (p[0] * scale) as f32,
+        (p[1] * scale) as f32,
+        (p[2] * scale) as f32,
+    )
+}
+
+fn surface_arrays(vertices: PackedVector3Array, colors: Option<PackedColorArray>) -> VariantArray {
+    let mut arrays = VariantArray::new();
+    arrays.resize(ArrayType::MAX.ord() as usize, &Variant::nil());
+    arrays.set(ArrayType::VERTEX.ord() as usize, &vertices.to_variant());
+    if let Some(colors) = colors {
+        arrays.set(ArrayType::COLOR.ord() as usize, &colors.to_variant());
+    }
+    arrays
+}
+
+/// Keplerian orbital elements. Angles are in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    /// Semi-major axis (absolute value for hyperbolic orbits)
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+}
+
+impl OrbitalElements {
+    /// Reads elements from a Dictionary, defaulting missing values.
+    pub fn from_dict(dict: &Dictionary) -> Self {
+        let get = |key: &str| dict.get(key).and_then(|v| v.try_to::<f64>().ok());
+        Self {
+            semi_major_axis: get("semi_major_axis")
+                .or_else(|| get("orbital_radius"))
+                .unwrap_or(0.0)
+                .abs(),
+            eccentricity: get("eccentricity").unwrap_or(0.0).max(0.0),
+            inclination: get("inclination").unwrap_or(0.0),
+            longitude_of_ascending_node: get("longitude_of_ascending_node").unwrap_or(0.0),
+            argument_of_periapsis: get("argument_of_periapsis").unwrap_or(0.0),
+        }
+    }
+
+    /// Closest approach distance.
+    pub fn periapsis(&self) -> f64 {
+        if (self.eccentricity - 1.0).abs() < 1e-9 {
+            self.semi_major_axis
+        } else if self.eccentricity < 1.0 {
+            self.semi_major_axis * (1.0 - self.eccentricity)
+        } else {
+            self.semi_major_axis * (self.eccentricity - 1.0)
+        }
+    }
+
+    /// Semi-latus rectum `p`, so that `r = p / (1 + e cos(nu))`.
+    pub fn semi_latus_rectum(&self) -> f64 {
+        if (self.eccentricity - 1.0).abs() < 1e-9 {
+            // Parabolic: treat semi_major_axis as the periapsis distance
+            2.0 * self.semi_major_axis
+        } else {
+            self.semi_major_axis * (1.0 - self.eccentricity * self.eccentricity).abs()
+        }
+    }
+
+    /// Distance from the focus at a true anomaly.
+    pub fn radius_at(&self, true_anomaly: f64) -> f64 {
+        self.semi_latus_rectum() / (1.0 + self.eccentricity * true_anomaly.cos())
+    }
+
+    /// Position at a true anomaly, in Godot's frame (XZ reference plane, Y up).
+    pub fn position_at(&self, true_anomaly: f64) -> [f64; 3] {
+        let r = self.radius_at(true_anomaly);
+        self.perifocal_to_frame(r * true_anomaly.cos(), r * true_anomaly.sin())
+    }
+
+    /// Velocity at a true anomaly, in Godot's frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `true_anomaly` - True anomaly in radians
+    /// * `gm` - Gravitational parameter of the central mass (units^3 / time^2)
+    pub fn velocity_at(&self, true_anomaly: f64, gm: f64) -> [f64; 3] {
+        let p = self.semi_latus_rectum();
+        if p <= 0.0 {
+            return [0.0; 3];
+        }
+        let k = (gm / p).sqrt();
+        self.perifocal_to_frame(
+            -k * true_anomaly.sin(),
+            k * (self.eccentricity + true_anomaly.cos()),
+        )
+    }
+
+    /// Rotates a perifocal-plane vector by argument of periapsis, inclination
+    /// and ascending node (the standard 3-1-3 sequence) into Godot's frame.
+    fn perifocal_to_frame(&self, px: f64, py: f64) -> [f64; 3] {
+        let (sw, cw) = self.argument_of_periapsis.sin_cos();
+        let (si, ci) = self.inclination.sin_cos();
+        let (so, co) = self.longitude_of_ascending_node.sin_cos();
+
+        let x = (co * cw - so * sw * ci) * px + (-co * sw - so * cw * ci) * py;
+        let y = (so * cw + co * sw * ci) * px + (-so * sw + co * cw * ci) * py;
+        let z = (sw * si) * px + (cw * si) * py;
+        ecliptic_to_local([x, y, z])
+    }
+
+    /// Samples points along the orbit.
+    ///
+    /// Closed orbits return `segments + 1` points with the last equal to the
+    /// first. Open (parabolic/hyperbolic) orbits return the branch out to 20
+    /// periapsis distances.
+    pub fn orbit_points(&self, segments: usize) -> Vec<[f64; 3]> {
+        if self.semi_major_axis <= 0.0 || segments == 0 {
+            return Vec::new();
+        }
+
+        if self.eccentricity < 1.0 {
+            return (0..=segments)
+                .map(|i| self.position_at(i as f64 / segments as f64 * TAU))
+                .collect();
+        }
+
+        let max_radius = self.periapsis() * 20.0;
+        let cos_limit =
+            ((self.semi_latus_rectum() / max_radius - 1.0) / self.eccentricity).clamp(-1.0, 1.0);
+        let nu_max = cos_limit.acos();
+        (0..=segments)
+            .map(|i| self.position_at(-nu_max + 2.0 * nu_max * i as f64 / segments as f64))
+            .collect()
+    }
+}
+
+/// Converts a mean anomaly to a true anomaly for an elliptical orbit (`e < 1`).
+///
+/// Solves Kepler's equation `M = E - e sin(E)` with Newton iteration.
+pub fn true_anomaly_from_mean(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity.clamp(0.0, 0.999_999);
+    let m = mean_anomaly.rem_euclid(TAU);
+    let mut ecc = if e < 0.8 { m } else { std::f64::consts::PI };
+    for _ in 0..30 {
+        let delta = (ecc - e * ecc.sin() - m) / (1.0 - e * ecc.cos());
+        ecc -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    2.0 * ((1.0 + e).sqrt() * (ecc / 2.0).sin()).atan2((1.0 - e).sqrt() * (ecc / 2.0).cos())
+}
+
+/// Converts a mean anomaly to a true anomaly for a hyperbolic orbit (`e > 1`).
+///
+/// Solves `M = e sinh(H) - H` with Newton iteration.
+pub fn true_anomaly_from_hyperbolic_mean(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity.max(1.000_001);
+    let m = mean_anomaly;
+    let mut h = (2.0 * m.abs() / e + 1.8).ln().copysign(m);
+    for _ in 0..50 {
+        let delta = (e * h.sinh() - h - m) / (e * h.cosh() - 1.0);
+        h -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (h / 2.0).tanh()).atan()
+}
@@ -0,0 +1,467 @@
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use godot::classes::image::Format;
+use godot::classes::{IRefCounted, Image, RefCounted};
+use godot::prelude::*;
+
+use crate::noise::Perlin3;
+use crate::rng::hash_combine;
+
+/// Procedural surface generator for a single planet.
+///
+/// Built from a planet `Dictionary` returned by `MeiGalaxy.get_star_system`.
+/// Every map is derived from the planet's `seed`, so the same planet always
+/// produces the same textures.
+#[derive(GodotClass)]
+#[class(base=RefCounted)]
+pub struct MeiPlanet {
+    base: Base<RefCounted>,
+    /// Deterministic body seed (from the planet dictionary's `seed` field)
+    #[var]
+    seed: i64,
+    /// Planet type name, e.g. "Terrestrial" or "GasGiant"
+    #[var]
+    planet_type: GString,
+    /// Equilibrium temperature in Kelvin
+    #[var]
+    temperature: f64,
+    /// Planet mass in Earth masses
+    #[var]
+    mass: f64,
+    /// Relative atmosphere thickness, 0.0 (airless) to 1.0 (gas envelope)
+    #[var]
+    atmosphere: f64,
+}
+
+#[godot_api]
+impl IRefCounted for MeiPlanet {
+    /// Initializes an Earth-like `MeiPlanet` with seed 0.
+    fn init(base: Base<RefCounted>) -> Self {
+        Self {
+            base,
+            seed: 0,
+            planet_type: "Terrestrial".into(),
+            temperature: 288.0,
+            mass: 1.0,
+            atmosphere: 0.5,
+        }
+    }
+}
+
+#[godot_api]
+impl MeiPlanet {
+    /// Creates a `MeiPlanet` from a planet dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `planet` - A planet `Dictionary` from `get_star_system`
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var planet = MeiPlanet.from_dict(system["inner_planets"][0])
+    /// var albedo = planet.generate_albedo_map(1024, 512)
+    /// ```
+    #[func]
+    fn from_dict(planet: Dictionary) -> Gd<Self> {
+        let planet_type = planet
+            .get("planet_type")
+            .and_then(|v| v.try_to::<GString>().ok())
+            .unwrap_or_else(|| "Terrestrial".into());
+        let mass = dict_f64(&planet, "mass", 1.0);
+        let temperature = dict_f64(&planet, "temperature", 288.0);
+        let atmosphere = planet
+            .get("atmosphere")
+            .and_then(|v| v.try_to::<f64>().ok())
+            .unwrap_or_else(|| estimate_atmosphere(&planet_type.to_string(), mass, temperature));
+        let seed = planet
+            .get("seed")
+            .and_then(|v| v.try_to::<i64>().ok())
+            .unwrap_or(0);
+
+        Gd::from_init_fn(|base| Self {
+            base,
+            seed,
+            planet_type,
+            temperature,
+            mass,
+            atmosphere,
+        })
+    }
+
+    /// Generates an equirectangular RGB8 albedo map.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Texture width in pixels (longitude)
+    /// * `height` - Texture height in pixels (latitude)
+    ///
+    /// # Returns
+    ///
+    /// An `Image`, or `null` if the size is invalid
+    #[func]
+    fn generate_albedo_map(&self, width: i32, height: i32) -> Option<Gd<Image>> {
+        let (w, h) = checked_size(width, height)?;
+        let surface = self.surface();
+        let heights = surface.height_map(w, h);
+        make_image(
+            width,
+            height,
+            Format::RGB8,
+            surface.albedo_map(w, h, &heights),
+        )
+    }
+
+    /// Generates an equirectangular L8 height map.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Texture width in pixels (longitude)
+    /// * `height` - Texture height in pixels (latitude)
+    ///
+    /// # Returns
+    ///
+    /// An `Image`, or `null` if the size is invalid
+    #[func]
+    fn generate_height_map(&self, width: i32, height: i32) -> Option<Gd<Image>> {
+        let (w, h) = checked_size(width, height)?;
+        let heights = self.surface().height_map(w, h);
+        let bytes = heights
+            .iter()
+            .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect();
+        make_image(width, height, Format::L8, bytes)
+    }
+
+    /// Generates an equirectangular tangent-space RGB8 normal map.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Texture width in pixels (longitude)
+    /// * `height` - Texture height in pixels (latitude)
+    /// * `strength` - Bump strength multiplier (1.0 is a good default)
+    ///
+    /// # Returns
+    ///
+    /// An `Image`, or `null` if the size is invalid
+    #[func]
+    fn generate_normal_map(&self, width: i32, height: i32, strength: f64) -> Option<Gd<Image>> {
+        let (w, h) = checked_size(width, height)?;
+        let surface = self.surface();
+        let heights = surface.height_map(w, h);
+        make_image(
+            width,
+            height,
+            Format::RGB8,
+            surface.normal_map(w, h, &heights, strength),
+        )
+    }
+}
+
+impl MeiPlanet {
+    fn surface(&self) -> Surface {
+        Surface {
+            seed: self.seed as u64,
+            kind: SurfaceKind::from_planet_type(&self.planet_type.to_string()),
+            temperature: self.temperature,
+            atmosphere: self.atmosphere.clamp(0.0, 1.0),
+        }
+    }
+}
+
+fn dict_f64(dict: &Dictionary, key: &str, default: f64) -> f64 {
+    dict.get(key)
+        .and_then(|v| v.try_to::<f64>().ok())
+        .unwrap_or(default)
+}
+
+fn checked_size(width: i32, height: i32) -> Option<(usize, usize)> {
+    if width <= 0 || height <= 0 || width > 16384 || height > 16384 {
+        godot_error!("MeiPlanet: invalid texture size {}x{}", width, height);
+        return None;
+    }
+    Some((width as usize, height as usize))
+}
+
+fn make_image(width: i32, height: i32, format: Format, bytes: Vec<u8>) -> Option<Gd<Image>> {
+    Image::create_from_data(
+        width,
+        height,
+        false,
+        format,
+        &PackedByteArray::from(bytes.as_slice()),
+    )
+}
+
+/// Estimates relative atmosphere thickness from planet type, mass and temperature.
+///
+/// MEI does not model atmospheres, so this is a rough heuristic: gas envelopes
+/// are 1.0, small bodies keep little, and hot low-mass planets lose theirs.
+pub fn estimate_atmosphere(planet_type: &str, mass: f64, temperature: f64) -> f64 {
+    let base = match SurfaceKind::from_planet_type(planet_type) {
+        SurfaceKind::Gaseous => return 1.0,
+        SurfaceKind::Ocean => 0.6,
+        SurfaceKind::Rocky | SurfaceKind::Carbon => 0.4,
+        SurfaceKind::Desert => 0.3,
+        SurfaceKind::Lava => 0.2,
+        SurfaceKind::Barren => 0.0,
+    };
+    // Heavier planets hold on to gas; hot planets lose it.
+    let retention = (mass.max(0.0).sqrt() * 300.0 / temperature.max(30.0)).min(1.5);
+    (base * retention).clamp(0.0, 1.0)
+}
+
+/// Planet equilibrium temperature in Kelvin, assuming an Earth-like albedo.
+///
+/// # Arguments
+///
+/// * `luminosity` - Host luminosity in solar luminosities
+/// * `distance_au` - Orbital distance in AU
+pub fn equilibrium_temperature(luminosity: f64, distance_au: f64) -> f64 {
+    278.6 * luminosity.max(0.0).powf(0.25) / distance_au.abs().max(1e-3).sqrt()
+}
+
+/// Broad surface families that share a texturing strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceKind {
+    Barren,
+    Rocky,
+    Desert,
+    Ocean,
+    Lava,
+    Carbon,
+    Gaseous,
+}
+
+impl SurfaceKind {
+    /// Maps a planet (or moon) type name to its surface family.
+    pub fn from_planet_type(planet_type: &str) -> Self {
+        match planet_type {
+            "Dwarf" | "Chthonian" => SurfaceKind::Barren,
+            "Terrestrial" | "SuperEarth" => SurfaceKind::Rocky,
+            "Desert" => SurfaceKind::Desert,
+            "Ocean" => SurfaceKind::Ocean,
+            "Lava" => SurfaceKind::Lava,
+            "Carbon" => SurfaceKind::Carbon,
+            "MiniNeptune" | "SubNeptune" | "IceGiant" | "GasGiant" | "HotJupiter" | "Coreless" => {
+                SurfaceKind::Gaseous
+            }
+            _ => SurfaceKind::Rocky,
+        }
+    }
+}
+
+type Rgb = [f64; 3];
+
+/// Pure surface texturing parameters; everything the maps depend on.
+pub struct Surface {
+    pub seed: u64,
+    pub kind: SurfaceKind,
+    pub temperature: f64,
+    pub atmosphere: f64,
+}
+
+impl Surface {
+    /// Samples a normalized `[0, 1]` height for every pixel, row-major.
+    pub fn height_map(&self, width: usize, height: usize) -> Vec<f32> {
+        let terrain = Perlin3::new(self.seed);
+        let warp = Perlin3::new(hash_combine(self.seed, 1));
+        let mut out = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy, dz) = sphere_dir(x, y, width, height);
+                let value = if self.kind == SurfaceKind::Gaseous {
+                    // Latitude bands, bent by a low-frequency turbulence field
+                    let turbulence = warp.fbm(dx * 3.0, dy * 3.0, dz * 3.0, 4);
+                    let band = (dy * 9.0 + turbulence * 1.5).sin();
+                    0.5 + 0.5 * (0.8 * band + 0.2 * terrain.fbm(dx * 8.0, dy * 24.0, dz * 8.0, 3))
+                } else {
+                    let (wx, wy, wz) = (
+                        dx + 0.3 * warp.sample(dx * 2.0, dy * 2.0, dz * 2.0),
+                        dy + 0.3 * warp.sample(dx * 2.0 + 5.2, dy * 2.0, dz * 2.0),
+                        dz + 0.3 * warp.sample(dx * 2.0, dy * 2.0 + 1.7, dz * 2.0),
+                    );
+                    0.5 + 0.5 * terrain.fbm(wx * 2.0, wy * 2.0, wz * 2.0, 6) * 1.4
+                };
+                out.push(value.clamp(0.0, 1.0) as f32);
+            }
+        }
+        out
+    }
+
+    /// Shades a height map into RGB8 albedo, row-major.
+    pub fn albedo_map(&self, width: usize, height: usize, heights: &[f32]) -> Vec<u8> {
+        let detail = Perlin3::new(hash_combine(self.seed, 2));
+        let mut out = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy, dz) = sphere_dir(x, y, width, height);
+                let h = heights[y * width + x] as f64;
+                let fine = detail.fbm(dx * 16.0, dy * 16.0, dz * 16.0, 3);
+                let mut color = self.base_color(h, fine, dx, dy, dz, &detail);
+                color = self.apply_ice(color, h, dy, fine);
+                color = mix(color, self.haze_color(), self.atmosphere * 0.25);
+                for c in color {
+                    out.push((c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+            }
+        }
+        out
+    }
+
+    /// Derives a tangent-space normal map from a height map, row-major RGB8.
+    ///
+    /// Longitude wraps around so the left and right edges match.
+    pub fn normal_map(
+        &self,
+        width: usize,
+        height: usize,
+        heights: &[f32],
+        strength: f64,
+    ) -> Vec<u8> {
+        let strength = if self.kind == SurfaceKind::Gaseous {
+            strength * 0.1
+        } else {
+            strength
+        };
+        let scale = strength * width as f64 / 64.0;
+        let at = |x: usize, y: usize| heights[y * width + x] as f64;
+        let mut out = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            let up = y.saturating_sub(1);
+            let down = (y + 1).min(height - 1);
+            for x in 0..width {
+                let left = (x + width - 1) % width;
+                let right = (x + 1) % width;
+                let nx = (at(left, y) - at(right, y)) * scale;
+                let ny = (at(x, down) - at(x, up)) * scale;
+                let len = (nx * nx + ny * ny + 1.0).sqrt();
+                for c in [nx / len, ny / len, 1.0 / len] {
+                    out.push(((c * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+            }
+        }
+        out
+    }
+
+    fn base_color(&self, h: f64, fine: f64, dx: f64, dy: f64, dz: f64, detail: &Perlin3) -> Rgb {
+        let t = self.temperature;
+        match self.kind {
+            SurfaceKind::Barren => gradient(
+                &[[0.22, 0.21, 0.2], [0.45, 0.43, 0.4], [0.62, 0.6, 0.57]],
+                h + fine * 0.1,
+            ),
+            SurfaceKind::Rocky => {
+                let sea_level = if (250.0..=330.0).contains(&t) && self.atmosphere > 0.2 {
+                    0.5
+                } else {
+                    0.0
+                };
+                if h < sea_level {
+                    gradient(&[[0.02, 0.08, 0.25], [0.05, 0.2, 0.45]], h / sea_level)
+                } else if sea_level > 0.0 {
+                    let land = (h - sea_level) / (1.0 - sea_level);
+                    gradient(
+                        &[
+                            [0.2, 0.38, 0.15],
+                            [0.35, 0.4, 0.2],
+                            [0.45, 0.38, 0.3],
+                            [0.6, 0.58, 0.55],
+                        ],
+                        land + fine * 0.1,
+                    )
+                } else {
+                    gradient(
+                        &[[0.3, 0.25, 0.22], [0.5, 0.42, 0.35], [0.65, 0.6, 0.55]],
+                        h + fine * 0.1,
+                    )
+                }
+            }
+            SurfaceKind::Desert => gradient(
+                &[[0.55, 0.35, 0.18], [0.78, 0.6, 0.38], [0.9, 0.8, 0.6]],
+                h + fine * 0.15,
+            ),
+            SurfaceKind::Ocean => {
+                if h < 0.72 {
+                    gradient(&[[0.01, 0.05, 0.2], [0.04, 0.18, 0.42]], h / 0.72)
+                } else {
+                    gradient(&[[0.75, 0.7, 0.55], [0.25, 0.45, 0.2]], (h - 0.72) / 0.28)
+                }
+            }
+            SurfaceKind::Lava => {
+                // Glowing cracks along the zero crossings of a ridged field
+                let ridge = 1.0 - detail.sample(dx * 6.0, dy * 6.0, dz * 6.0).abs() * 6.0;
+                let rock = gradient(&[[0.05, 0.04, 0.04], [0.18, 0.15, 0.13]], h);
+                mix(rock, [1.0, 0.35, 0.05], ridge.clamp(0.0, 1.0))
+            }
+            SurfaceKind::Carbon => gradient(
+                &[[0.05, 0.05, 0.06], [0.16, 0.15, 0.15], [0.3, 0.28, 0.25]],
+                h + fine * 0.1,
+            ),
+            SurfaceKind::Gaseous => {
+                let palette: &[Rgb] = if t < 150.0 {
+                    &[[0.25, 0.45, 0.7], [0.45, 0.65, 0.85], [0.7, 0.85, 0.92]]
+                } else if t < 1000.0 {
+                    &[[0.55, 0.35, 0.2], [0.85, 0.7, 0.5], [0.95, 0.9, 0.8]]
+                } else {
+                    &[[0.2, 0.06, 0.04], [0.45, 0.18, 0.1], [0.65, 0.35, 0.2]]
+                };
+                gradient(palette, h + fine * 0.05)
+            }
+        }
+    }
+
+    fn apply_ice(&self, color: Rgb, h: f64, dy: f64, fine: f64) -> Rgb {
+        if self.kind == SurfaceKind::Gaseous
+            || self.kind == SurfaceKind::Lava
+            || self.temperature > 290.0
+        {
+            return color;
+        }
+        // Colder planets have caps reaching further towards the equator
+        let cap_latitude = ((self.temperature - 150.0) / 140.0).clamp(0.0, 1.0) * 0.95;
+        let latitude = dy.abs() + fine * 0.08 + (h - 0.5) * 0.1;
+        let ice = ((latitude - cap_latitude) * 12.0).clamp(0.0, 1.0);
+        mix(color, [0.92, 0.95, 0.98], ice)
+    }
+
+    fn haze_color(&self) -> Rgb {
+        match self.kind {
+            SurfaceKind::Gaseous => [0.9, 0.85, 0.75],
+            SurfaceKind::Lava => [0.6, 0.3, 0.15],
+            _ if self.temperature > 400.0 => [0.85, 0.75, 0.5],
+            _ => [0.7, 0.8, 1.0],
+        }
+    }
+}
+
+/// Unit-sphere direction for the center of an equirectangular pixel.
+fn sphere_dir(x: usize, y: usize, width: usize, height: usize) -> (f64, f64, f64) {
+    let longitude = (x as f64 + 0.5) / width as f64 * TAU;
+    let latitude = FRAC_PI_2 - (y as f64 + 0.5) / height as f64 * PI;
+    let cos_lat = latitude.cos();
+    (
+        cos_lat * longitude.cos(),
+        latitude.sin(),
+        cos_lat * longitude.sin(),
+    )
+}
+
+fn mix(a: Rgb, b: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
+}
+
+/// Samples a piecewise-linear palette at `t` in `[0, 1]`.
+fn gradient(stops: &[Rgb], t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let i = (t.floor() as usize).min(stops.len() - 2);
+    mix(stops[i], stops[i + 1], t - i as f64)
+}
@@ -0,0 +1,1081 @@
+//! Engine-independent conversion of MEI output.
+//!
+//! Everything `MeiGalaxy` returns is first built here as a [`Record`], a plain
+//! Rust mirror of a Godot `Dictionary`, and only then turned into Godot
+//! types by [`record_to_dict`]. Keeping the conversion pure lets the snapshot
+//! tests in `tests/` check exactly what players see without launching Godot.
+
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use godot::prelude::*;
+use mei::api::galaxy_api::{GalaxyAPI, SystemQuery};
+use mei::space_objects::asteroid::{Asteroid, AsteroidBelt, AsteroidType};
+use mei::space_objects::comet::{Comet, CometType, OortCloud};
+use mei::space_objects::moon::{Moon, MoonType};
+use mei::space_objects::planet::{Planet, PlanetType};
+use mei::space_objects::star::Star;
+use mei::space_objects::system::{StarSystem, StellarConfiguration};
+use mei::util::vec::Vec3;
+
+use crate::comet::{CometClass, CometOrbit, ACTIVITY_RADIUS_AU};
+use crate::evolution::{evolve, stellar_age, Phase};
+use crate::morphology::Morphology;
+use crate::naming::{catalog_designation, moon_numeral, planet_letter, NameStyle, SectorCoord};
+use crate::planet::{equilibrium_temperature, estimate_atmosphere};
+use crate::rng::{hash_combine, hash_f64s, hash_str};
+use crate::search::SystemFacts;
+use crate::stellar::{absolute_magnitude, blackbody_color, SpectralClass, StarProperties};
+use crate::structure_cache::{StructureData, StructureStar};
+use crate::units::{ecliptic_to_local, galactic_to_spherical};
+
+/// Radius in light-years of the sphere [`location_record`] counts stars in
+/// to measure the local stellar density.
+pub const DENSITY_SAMPLE_RADIUS: f64 = 10.0;
+
+/// A value in a [`Record`], limited to what the bindings hand to GDScript.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    /// Becomes a `PackedInt64Array`
+    Ints(Vec<i64>),
+    /// Becomes a `PackedStringArray`
+    Strs(Vec<String>),
+    /// Becomes an opaque `Color`
+    Color([f32; 3]),
+    /// Becomes a `Dictionary`
+    Record(Record),
+    /// Becomes an `Array[Dictionary]`
+    Records(Vec<Record>),
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Value::Int(v)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Float(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Str(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl From<Vec<i64>> for Value {
+    fn from(v: Vec<i64>) -> Self {
+        Value::Ints(v)
+    }
+}
+
+impl From<Vec<String>> for Value {
+    fn from(v: Vec<String>) -> Self {
+        Value::Strs(v)
+    }
+}
+
+impl From<Record> for Value {
+    fn from(v: Record) -> Self {
+        Value::Record(v)
+    }
+}
+
+impl From<Vec<Record>> for Value {
+    fn from(v: Vec<Record>) -> Self {
+        Value::Records(v)
+    }
+}
+
+/// Ordered key/value pairs, the pure counterpart of a `Dictionary`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record(pub Vec<(&'static str, Value)>);
+
+impl Record {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a key, replacing any previous value in place.
+    pub fn set(&mut self, key: &'static str, value: impl Into<Value>) {
+        let value = value.into();
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.0.iter().position(|(k, _)| *k == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Line-per-leaf text form used by the snapshot tests, e.g.
+    /// `inner_planets[0].moons[1].mass = 1.230000000e-2`.
+    pub fn to_snapshot(&self) -> String {
+        let mut out = String::new();
+        write_record(&mut out, "", self);
+        out
+    }
+}
+
+fn write_record(out: &mut String, path: &str, record: &Record) {
+    if record.0.is_empty() {
+        let _ = writeln!(out, "{} = {{}}", path);
+    }
+    for (key, value) in &record.0 {
+        let path = if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        write_value(out, &path, value);
+    }
+}
+
+fn write_value(out: &mut String, path: &str, value: &Value) {
+    let _ = match value {
+        Value::Bool(v) => writeln!(out, "{} = {}", path, v),
+        Value::Int(v) => writeln!(out, "{} = {}", path, v),
+        Value::Float(v) => writeln!(out, "{} = {}", path, snapshot_float(*v)),
+        Value::Str(v) => writeln!(out, "{} = {:?}", path, v),
+        Value::Ints(v) => writeln!(out, "{} = {:?}", path, v),
+        Value::Strs(v) => writeln!(out, "{} = {:?}", path, v),
+        Value::Color(v) => writeln!(
+            out,
+            "{} = [{}]",
+            path,
+            v.map(|c| snapshot_float(c as f64)).join(", ")
+        ),
+        Value::Record(record) => {
+            write_record(out, path, record);
+            Ok(())
+        }
+        Value::Records(records) if records.is_empty() => writeln!(out, "{} = []", path),
+        Value::Records(records) => {
+            for (i, record) in records.iter().enumerate() {
+                write_record(out, &format!("{}[{}]", path, i), record);
+            }
+            Ok(())
+        }
+    };
+}
+
+/// Floats at 10 significant digits, so libm differences in the last bits
+/// across platforms don't break snapshots.
+pub fn snapshot_float(v: f64) -> String {
+    if v == 0.0 || !v.is_finite() {
+        format!("{}", v)
+    } else {
+        format!("{:.9e}", v)
+    }
+}
+
+/// Converts a record to a Godot `Dictionary`.
+pub fn record_to_dict(record: &Record) -> Dictionary {
+    let mut dict = Dictionary::new();
+    for (key, value) in &record.0 {
+        dict.set(*key, value_to_variant(value));
+    }
+    dict
+}
+
+fn value_to_variant(value: &Value) -> Variant {
+    match value {
+        Value::Bool(v) => v.to_variant(),
+        Value::Int(v) => v.to_variant(),
+        Value::Float(v) => v.to_variant(),
+        Value::Str(v) => GString::from(v.as_str()).to_variant(),
+        Value::Ints(v) => PackedInt64Array::from(v.as_slice()).to_variant(),
+        Value::Strs(v) => v
+            .iter()
+            .map(|s| GString::from(s.as_str()))
+            .collect::<PackedStringArray>()
+            .to_variant(),
+        Value::Color([r, g, b]) => Color::from_rgb(*r, *g, *b).to_variant(),
+        Value::Record(record) => record_to_dict(record).to_variant(),
+        Value::Records(records) => {
+            let mut array = Array::<Dictionary>::new();
+            for record in records {
+                array.push(&record_to_dict(record));
+            }
+            array.to_variant()
+        }
+    }
+}
+
+fn vec3_record(v: &Vec3) -> Record {
+    let mut record = Record::new();
+    record.set("x", v.x);
+    record.set("y", v.y);
+    record.set("z", v.z);
+    record
+}
+
+/// Record of a position inside a system, turned from MEI's ecliptic (Z up)
+/// into the system local frame (Y up) that orbits are drawn in.
+fn local_vec3_record(v: &Vec3) -> Record {
+    let [x, y, z] = ecliptic_to_local([v.x, v.y, v.z]);
+    vec3_record(&Vec3::new(x, y, z))
+}
+
+/// The star a star ID names within its system, or the system's primary if
+/// MEI renumbered it.
+pub fn queried_star<'a>(system: &'a StarSystem, star_id: &str) -> Option<&'a Star> {
+    let id = star_id.parse::<i64>().unwrap_or_default();
+    system
+        .stars
+        .iter()
+        .find(|s| s.id as i64 == id)
+        .or_else(|| system.stars.first())
+}
+
+/// Seed shared by everything derived for one star in a galaxy.
+pub fn star_seed(galaxy_seed: u64, star_id: &str) -> u64 {
+    hash_combine(galaxy_seed, hash_str(star_id))
+}
+
+/// Generates one system.
+///
+/// Panics inside MEI are turned into an error message so one bad ID does not
+/// take a whole batch down.
+pub fn generate_system(api: &GalaxyAPI, star_id: &str) -> Result<StarSystem, String> {
+    let query = SystemQuery {
+        star_id: star_id.to_string(),
+        position: None,
+    };
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| api.get_star_system(&query))).map_err(
+        |panic| {
+            panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "system generation failed".to_string())
+        },
+    )
+}
+
+/// Generates the galactic structure in the compact form the disk cache stores.
+pub fn structure_data(api: &GalaxyAPI, max_stars: i64) -> StructureData {
+    let stars = api.generator.get_galactic_structure(max_stars as usize);
+    structure_from_stars(&stars, api.generator.estimate_total_stars(500.0) as i64)
+}
+
+/// Converts `get_galactic_structure` output, without generating anything.
+pub fn structure_from_stars(stars: &[Star], estimated_total: i64) -> StructureData {
+    let mut data = StructureData {
+        stars: Vec::with_capacity(stars.len()),
+        estimated_total,
+        ..Default::default()
+    };
+    for star in stars {
+        push_structure_star(&mut data, star, [0.0; 3]);
+    }
+    data
+}
+
+/// Stars around a point, in the same compact form as [`structure_data`].
+///
+/// Positions are relative to `origin`, subtracted in double precision
+/// before they are narrowed to `f32`.
+pub fn nearby_data(
+    api: &mut GalaxyAPI,
+    position: [f64; 3],
+    radius: f64,
+    max_stars: i64,
+    origin: [f64; 3],
+) -> StructureData {
+    let center = Vec3::new(position[0], position[1], position[2]);
+    nearby_from_stars(
+        &api.generator
+            .get_nearby_stars(&center, radius, max_stars as usize),
+        origin,
+    )
+}
+
+/// Converts `get_nearby_stars` output, without generating anything.
+pub fn nearby_from_stars(stars: &[Star], origin: [f64; 3]) -> StructureData {
+    let mut data = StructureData {
+        stars: Vec::with_capacity(stars.len()),
+        ..Default::default()
+    };
+    for star in stars {
+        push_structure_star(&mut data, star, origin);
+    }
+    data
+}
+
+/// Appends one star in its compact form, positioned relative to `origin`.
+/// Both structure and nearby queries go through here, so a star has the
+/// same values whichever returned it.
+fn push_structure_star(data: &mut StructureData, star: &Star, origin: [f64; 3]) {
+    let properties = StarProperties::of(star);
+    let star_type = data.type_index(&properties.star_type);
+    let spectral_class = data.spectral_class_index(&properties.spectral_class_name());
+    let [x, y, z] = properties.position;
+    data.stars.push(StructureStar {
+        position: [x - origin[0], y - origin[1], z - origin[2]].map(|c| c as f32),
+        id: properties.id,
+        luminosity: properties.luminosity as f32,
+        temperature: properties.temperature as f32,
+        mass: properties.mass as f32,
+        radius: properties.radius as f32,
+        absolute_magnitude: properties.absolute_magnitude as f32,
+        color: properties.color,
+        star_type,
+        spectral_class,
+    });
+}
+
+/// Builds the record of one star in a `get_star_system` result.
+pub fn star_record(star: &Star) -> Record {
+    let properties = StarProperties::of(star);
+    let mut record = Record::new();
+    record.set("id", properties.id);
+    record.set("star_type", properties.star_type);
+    record.set("spectral_class", properties.spectral_class_name());
+    match &properties.spectral_class {
+        Some(class) => {
+            record.set("spectral_type", class.class);
+            record.set("spectral_subclass", class.subclass);
+            record.set("luminosity_class", class.luminosity_class);
+        }
+        None => {
+            record.set("spectral_type", "");
+            record.set("spectral_subclass", -1.0);
+            record.set("luminosity_class", "");
+        }
+    }
+    record.set("mass", properties.mass);
+    record.set("luminosity", properties.luminosity);
+    record.set("temperature", properties.temperature);
+    record.set("radius", properties.radius);
+    record.set("absolute_magnitude", properties.absolute_magnitude);
+    record.set("color", Value::Color(properties.color));
+    // Galactic, like the system's own position: MEI places each star of a
+    // system at its own catalog position
+    record.set("position", vec3_record(&star.position));
+    record
+}
+
+/// Builds the `evolve_star` record: the star `years_from_now` years from
+/// today (negative for the past).
+pub fn evolution_record(
+    galaxy_seed: u64,
+    star: &Star,
+    galaxy_radius: f64,
+    years_from_now: f64,
+) -> Record {
+    let properties = StarProperties::of(star);
+    let age = stellar_age(
+        galaxy_seed,
+        &properties,
+        &Morphology::milky_way(galaxy_radius),
+    );
+    let evolved = evolve(&properties, &age, years_from_now);
+
+    let mut record = Record::new();
+    record.set("id", properties.id);
+    record.set("years_from_now", years_from_now);
+    record.set("region", age.region.name());
+    record.set("initial_mass", age.initial_mass);
+    record.set("current_age", age.age);
+    record.set("age", evolved.age);
+    record.set("phase", evolved.phase.name());
+    record.set("exists", evolved.phase != Phase::Unformed);
+    record.set(
+        "spectral_class",
+        SpectralClass::classify(
+            &evolved.star_type,
+            evolved.temperature,
+            evolved.luminosity,
+            evolved.mass,
+            evolved.radius,
+        )
+        .map_or_else(String::new, |class| class.to_string()),
+    );
+    record.set("star_type", evolved.star_type);
+    record.set("mass", evolved.mass);
+    record.set("luminosity", evolved.luminosity);
+    record.set("temperature", evolved.temperature);
+    record.set("radius", evolved.radius);
+    record.set("absolute_magnitude", absolute_magnitude(evolved.luminosity));
+    record.set("color", Value::Color(blackbody_color(evolved.temperature)));
+    record
+}
+
+/// Builds the `describe_location` record for a galactic position, with
+/// longitude and latitude seen from `reference`.
+///
+/// `region`, the arm fields and `metallicity` are modeled by [`Morphology`],
+/// an approximation of the shape behind MEI's density, not read from MEI.
+/// Only `stellar_density` is sampled: it counts the stars MEI generates
+/// around the position, so this is as costly as a small `get_nearby_stars`
+/// query.
+pub fn location_record(api: &mut GalaxyAPI, position: [f64; 3], reference: [f64; 3]) -> Record {
+    let morphology = Morphology::milky_way(api.generator.galaxy.radius);
+    let sky = galactic_to_spherical(position, reference);
+    let nearest_arm = morphology.nearest_arm(position);
+
+    let sample_radius = DENSITY_SAMPLE_RADIUS.min(api.generator.config.nearby_max_radius);
+    let center = Vec3::new(position[0], position[1], position[2]);
+    let count = api
+        .generator
+        .get_nearby_stars(&center, sample_radius, 10_000)
+        .len();
+    let volume = 4.0 / 3.0 * PI * sample_radius.powi(3);
+
+    let mut record = Record::new();
+    record.set("radius", position[0].hypot(position[2]));
+    record.set("height", position[1]);
+    record.set("longitude", sky.longitude);
+    record.set("latitude", sky.latitude);
+    record.set("distance", sky.distance);
+    record.set("region", morphology.region(position).name());
+    record.set("arm", nearest_arm.map_or(-1, |(arm, _)| arm as i64));
+    record.set(
+        "arm_name",
+        nearest_arm
+            .and_then(|(arm, _)| morphology.arm_name(arm))
+            .unwrap_or_default(),
+    );
+    record.set(
+        "arm_distance",
+        nearest_arm.map_or(-1.0, |(_, distance)| distance),
+    );
+    record.set("sector", SectorCoord::containing(position).code());
+    record.set("metallicity", morphology.metallicity(position));
+    record.set(
+        "stellar_density",
+        if volume > 0.0 {
+            count as f64 / volume
+        } else {
+            0.0
+        },
+    );
+    record
+}
+
+/// Builds the `get_morphology` record: the galaxy's shape in light-years
+/// and degrees.
+pub fn morphology_record(morphology: &Morphology) -> Record {
+    let mut record = Record::new();
+    record.set("radius", morphology.radius);
+    record.set("arm_count", morphology.arm_count as i64);
+    record.set(
+        "arm_names",
+        (0..morphology.arm_count)
+            .map(|arm| morphology.arm_name(arm).unwrap_or_default().to_string())
+            .collect::<Vec<_>>(),
+    );
+    record.set("arm_pitch", morphology.arm_pitch.to_degrees());
+    record.set("arm_start_radius", morphology.arm_start_radius());
+    record.set("arm_half_width", morphology.arm_half_width);
+    record.set("bar_length", morphology.bar_length);
+    record.set("bar_width", morphology.bar_width);
+    record.set("bar_angle", morphology.bar_angle.to_degrees());
+    record.set("bulge_radius", morphology.bulge_radius);
+    record.set("thin_disk_height", morphology.thin_disk_height);
+    record.set("thick_disk_height", morphology.thick_disk_height);
+    record
+}
+
+/// Name of a stellar configuration, as used in `configuration.type`.
+pub fn configuration_name(configuration: &StellarConfiguration) -> &'static str {
+    match configuration {
+        StellarConfiguration::Single => "Single",
+        StellarConfiguration::CloseBinary { .. } => "CloseBinary",
+        StellarConfiguration::WideBinary { .. } => "WideBinary",
+        StellarConfiguration::HierarchicalTriple { .. } => "HierarchicalTriple",
+        StellarConfiguration::UnstableTriple => "UnstableTriple",
+    }
+}
+
+/// Name of a planet type, as used in `planet_type`.
+pub fn planet_type_name(planet_type: &PlanetType) -> &'static str {
+    match planet_type {
+        PlanetType::Dwarf => "Dwarf",
+        PlanetType::Terrestrial => "Terrestrial",
+        PlanetType::SuperEarth => "SuperEarth",
+        PlanetType::Desert => "Desert",
+        PlanetType::Ocean => "Ocean",
+        PlanetType::Lava => "Lava",
+        PlanetType::MiniNeptune => "MiniNeptune",
+        PlanetType::SubNeptune => "SubNeptune",
+        PlanetType::IceGiant => "IceGiant",
+        PlanetType::GasGiant => "GasGiant",
+        PlanetType::HotJupiter => "HotJupiter",
+        PlanetType::Chthonian => "Chthonian",
+        PlanetType::Carbon => "Carbon",
+        PlanetType::Coreless => "Coreless",
+    }
+}
+
+/// Every distinct planet of a system in lettering order: by orbital radius,
+/// then mass. Includes planets that only appear in `stellar_components`;
+/// a planet listed both there and at the top level appears once.
+pub fn lettered_planets(system: &StarSystem) -> Vec<&Planet> {
+    let component_planets = system
+        .stellar_components
+        .iter()
+        .flat_map(|c| c.inner_planets.iter().chain(c.outer_planets.iter()));
+    let mut planets: Vec<&Planet> = system
+        .inner_planets
+        .iter()
+        .chain(system.outer_planets.iter())
+        .chain(component_planets)
+        .collect();
+    planets.sort_by(|a, b| {
+        a.position
+            .x
+            .total_cmp(&b.position.x)
+            .then(a.mass.total_cmp(&b.mass))
+    });
+    planets.dedup_by(|a, b| a.position.x == b.position.x && a.mass == b.mass);
+    planets
+}
+
+/// Extracts what search filters and summaries look at from a generated system.
+pub fn system_facts(system: &StarSystem) -> SystemFacts {
+    let mut planets: Vec<_> = system
+        .inner_planets
+        .iter()
+        .chain(system.outer_planets.iter())
+        .collect();
+    planets.sort_by(|a, b| a.position.x.total_cmp(&b.position.x));
+
+    SystemFacts {
+        star_types: system
+            .stars
+            .iter()
+            .map(|s| format!("{:?}", s.star_type))
+            .collect(),
+        configuration: configuration_name(&system.configuration).to_string(),
+        planet_types: planets
+            .iter()
+            .map(|p| planet_type_name(&p.planet_type).to_string())
+            .collect(),
+        dominant_planet_type: planets
+            .iter()
+            .max_by(|a, b| a.mass.total_cmp(&b.mass))
+            .map(|p| planet_type_name(&p.planet_type).to_string()),
+        habitable_planets: planets
+            .iter()
+            .filter(|p| {
+                (system.habitable_zone_inner..=system.habitable_zone_outer).contains(&p.position.x)
+            })
+            .count(),
+        moon_count: planets.iter().map(|p| p.moons.len()).sum(),
+        asteroid_belts: system.asteroid_belts.len(),
+        has_oort_cloud: system.oort_cloud.is_some(),
+    }
+}
+
+/// System-wide values needed to derive body properties MEI does not store.
+pub struct BodyContext {
+    /// Hash of the galaxy seed and star ID, mixed into every body seed
+    pub system_seed: u64,
+    /// Catalog designation of the system, prefix of every body designation
+    pub designation: String,
+    /// Orbital radius and mass of each planet in [`lettered_planets`] order;
+    /// a planet's rank gives its letter
+    pub planet_keys: Vec<[f64; 2]>,
+    /// Combined luminosity of all stars in solar luminosities
+    pub luminosity: f64,
+    /// Combined mass of all stars in solar masses
+    pub stellar_mass: f64,
+    /// Orbital radii of giant planets in AU, for asteroid belt resonances
+    pub giant_orbits: Vec<f64>,
+}
+
+impl BodyContext {
+    pub fn new(galaxy_seed: u64, star_id: &str, system: &StarSystem, cell_size: f64) -> Self {
+        let planets = || {
+            system
+                .inner_planets
+                .iter()
+                .chain(system.outer_planets.iter())
+        };
+
+        Self {
+            system_seed: star_seed(galaxy_seed, star_id),
+            designation: catalog_designation(
+                [system.position.x, system.position.y, system.position.z],
+                cell_size,
+            ),
+            planet_keys: lettered_planets(system)
+                .iter()
+                .map(|p| [p.position.x, p.mass])
+                .collect(),
+            luminosity: system.stars.iter().map(|s| s.luminosity()).sum(),
+            stellar_mass: system.stars.iter().map(|s| s.mass).sum(),
+            giant_orbits: planets()
+                .filter(|p| {
+                    matches!(
+                        p.planet_type,
+                        PlanetType::GasGiant | PlanetType::HotJupiter | PlanetType::IceGiant
+                    )
+                })
+                .map(|p| p.position.x)
+                .collect(),
+        }
+    }
+}
+
+/// Builds the `get_star_system` record.
+pub fn system_record(
+    galaxy_seed: u64,
+    star_id: &str,
+    style: &NameStyle,
+    system: &StarSystem,
+    cell_size: f64,
+) -> Record {
+    let ctx = BodyContext::new(galaxy_seed, star_id, system, cell_size);
+
+    let mut result = Record::new();
+    result.set("star_id", star_id);
+    result.set("name", style.proper_name(ctx.system_seed));
+    result.set("designation", ctx.designation.clone());
+    result.set("frost_line", system.frost_line);
+    result.set("habitable_zone_inner", system.habitable_zone_inner);
+    result.set("habitable_zone_outer", system.habitable_zone_outer);
+    result.set("position", vec3_record(&system.position));
+
+    // Stars (can be multiple in binary/trinary systems)
+    result.set(
+        "stars",
+        system.stars.iter().map(star_record).collect::<Vec<_>>(),
+    );
+
+    // Stellar configuration
+    let mut configuration = Record::new();
+    configuration.set("type", configuration_name(&system.configuration));
+    match &system.configuration {
+        StellarConfiguration::CloseBinary {
+            separation_au,
+            is_contact,
+        } => {
+            configuration.set("separation_au", *separation_au);
+            configuration.set("is_contact", *is_contact);
+        }
+        StellarConfiguration::WideBinary { separation_au } => {
+            configuration.set("separation_au", *separation_au);
+        }
+        StellarConfiguration::HierarchicalTriple {
+            inner_separation_au,
+            outer_separation_au,
+        } => {
+            configuration.set("inner_separation_au", *inner_separation_au);
+            configuration.set("outer_separation_au", *outer_separation_au);
+        }
+        StellarConfiguration::Single | StellarConfiguration::UnstableTriple => {}
+    }
+    result.set("configuration", configuration);
+
+    // Stellar components (each can have planets orbiting)
+    let components: Vec<Record> = system
+        .stellar_components
+        .iter()
+        .map(|component| {
+            let mut record = Record::new();
+            record.set(
+                "star_indices",
+                component
+                    .star_indices
+                    .iter()
+                    .map(|i| *i as i64)
+                    .collect::<Vec<_>>(),
+            );
+            // Barycenter position (AU, system local frame)
+            record.set("barycenter", local_vec3_record(&component.barycenter));
+            record.set("combined_mass", component.combined_mass);
+            record.set("internal_separation", component.internal_separation);
+            record.set("is_interacting", component.is_interacting);
+            record.set("planet_inner_limit", component.planet_inner_limit);
+            record.set("planet_outer_limit", component.planet_outer_limit);
+            record.set("frost_line", component.frost_line);
+            record.set("habitable_zone_inner", component.habitable_zone_inner);
+            record.set("habitable_zone_outer", component.habitable_zone_outer);
+            record.set(
+                "inner_planets",
+                planet_records(&component.inner_planets, &ctx),
+            );
+            record.set(
+                "outer_planets",
+                planet_records(&component.outer_planets, &ctx),
+            );
+            record
+        })
+        .collect();
+    result.set("stellar_components", components);
+
+    result.set("inner_planets", planet_records(&system.inner_planets, &ctx));
+    result.set("outer_planets", planet_records(&system.outer_planets, &ctx));
+    result.set(
+        "asteroid_belts",
+        system
+            .asteroid_belts
+            .iter()
+            .map(|belt| asteroid_belt_record(belt, &ctx))
+            .collect::<Vec<_>>(),
+    );
+    if let Some(oort) = &system.oort_cloud {
+        result.set("oort_cloud", oort_cloud_record(oort, &ctx));
+    }
+    result
+}
+
+/// Builds the compact summary record used by `get_star_systems_batch`.
+pub fn summary_record(
+    galaxy_seed: u64,
+    star_id: &str,
+    style: &NameStyle,
+    system: &StarSystem,
+    cell_size: f64,
+) -> Record {
+    let facts = system_facts(system);
+    let position = [system.position.x, system.position.y, system.position.z];
+
+    let mut result = Record::new();
+    result.set("star_id", star_id);
+    result.set("name", style.proper_name(star_seed(galaxy_seed, star_id)));
+    result.set("designation", catalog_designation(position, cell_size));
+    result.set("position", vec3_record(&system.position));
+    result.set("star_types", facts.star_types);
+    result.set("configuration_type", facts.configuration);
+    let planet_count = facts.planet_types.len() as i64;
+    result.set("planet_types", facts.planet_types);
+    result.set("planet_count", planet_count);
+    result.set(
+        "dominant_planet_type",
+        facts.dominant_planet_type.unwrap_or_default(),
+    );
+    result.set("moon_count", facts.moon_count as i64);
+    result.set("habitable_planets", facts.habitable_planets as i64);
+    result.set("asteroid_belt_count", facts.asteroid_belts as i64);
+    result.set("has_oort_cloud", facts.has_oort_cloud);
+    result
+}
+
+fn planet_records(planets: &[Planet], ctx: &BodyContext) -> Vec<Record> {
+    planets.iter().map(|p| planet_record(p, ctx)).collect()
+}
+
+/// Builds a planet record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `planet_type`: Type of planet (Terrestrial, GasGiant, IceGiant, Dwarf, ...)
+/// - `mass`: Planet mass in Earth masses
+/// - `orbital_radius`: Distance from star in AU
+/// - `letter`: Planet letter by orbital order ("b", "c", ...)
+/// - `designation`: Full designation, e.g. "Sector NAMQ+3 1234567 c"
+/// - `seed`: Deterministic body seed (stable across visits, used for textures)
+/// - `temperature`: Equilibrium temperature in Kelvin
+/// - `atmosphere`: Estimated atmosphere thickness (0.0 - 1.0)
+/// - `position`: Position around the star in AU, in the system local frame (Y up)
+/// - `moons`: Array of moon dictionaries
+/// - `moon_count`: Number of moons
+pub fn planet_record(planet: &Planet, ctx: &BodyContext) -> Record {
+    let planet_type = planet_type_name(&planet.planet_type);
+    let mut record = Record::new();
+    record.set("planet_type", planet_type);
+    record.set("mass", planet.mass);
+    record.set("orbital_radius", planet.position.x); // x position is orbital radius in AU
+
+    let letter = ctx
+        .planet_keys
+        .iter()
+        .position(|key| *key == [planet.position.x, planet.mass])
+        .map(planet_letter)
+        .unwrap_or_default();
+    let designation = format!("{} {}", ctx.designation, letter);
+    record.set("letter", letter);
+    record.set("designation", designation.clone());
+
+    // Seeded from orbit and mass so the planet keeps its seed in every list it appears in
+    let seed = hash_f64s(
+        ctx.system_seed,
+        &[
+            planet.position.x,
+            planet.position.y,
+            planet.position.z,
+            planet.mass,
+        ],
+    );
+    let temperature = equilibrium_temperature(ctx.luminosity, planet.position.x);
+    record.set("seed", seed as i64);
+    record.set("temperature", temperature);
+    record.set(
+        "atmosphere",
+        estimate_atmosphere(planet_type, planet.mass, temperature),
+    );
+    record.set("position", local_vec3_record(&planet.position));
+
+    let mut moon_orbits: Vec<f64> = planet.moons.iter().map(|m| m.position.x).collect();
+    moon_orbits.sort_by(f64::total_cmp);
+    let moons: Vec<Record> = planet
+        .moons
+        .iter()
+        .map(|moon| {
+            let rank = moon_orbits
+                .iter()
+                .position(|r| *r == moon.position.x)
+                .unwrap_or(0);
+            let designation = format!("{} {}", designation, moon_numeral(rank));
+            moon_record(moon, seed, temperature, &designation)
+        })
+        .collect();
+    record.set("moons", moons);
+    record.set("moon_count", planet.moons.len() as i64);
+    record
+}
+
+/// Builds a moon record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `moon_type`: Type of moon (Rocky, Icy, IceRock, Ocean, ...)
+/// - `mass`: Moon mass in lunar masses
+/// - `orbital_radius`: Distance from planet in kilometers
+/// - `designation`: Full designation, e.g. "Sector NAMQ+3 1234567 c II"
+/// - `seed`: Deterministic body seed
+/// - `temperature`: Equilibrium temperature in Kelvin (same as the parent planet)
+/// - `position`: Position around the planet in kilometers, in the system local frame (Y up)
+pub fn moon_record(moon: &Moon, planet_seed: u64, temperature: f64, designation: &str) -> Record {
+    let moon_type = match moon.moon_type {
+        MoonType::Rocky => "Rocky",
+        MoonType::Icy => "Icy",
+        MoonType::IceRock => "IceRock",
+        MoonType::Ocean => "Ocean",
+        MoonType::Volcanic => "Volcanic",
+        MoonType::Captured => "Captured",
+        MoonType::Atmospheric => "Atmospheric",
+    };
+    let mut record = Record::new();
+    record.set("moon_type", moon_type);
+    record.set("mass", moon.mass);
+    record.set("orbital_radius", moon.position.x); // x position is orbital radius in km
+    record.set("designation", designation);
+    record.set(
+        "seed",
+        hash_f64s(
+            planet_seed,
+            &[moon.position.x, moon.position.y, moon.position.z, moon.mass],
+        ) as i64,
+    );
+    record.set("temperature", temperature);
+    record.set("position", local_vec3_record(&moon.position));
+    record
+}
+
+/// Builds an asteroid belt record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `name`: Belt name
+/// - `inner_radius`: Inner radius in AU
+/// - `outer_radius`: Outer radius in AU
+/// - `total_mass`: Total mass of the belt
+/// - `asteroid_count`: Number of asteroids
+/// - `seed`: Deterministic belt seed (for `MeiAsteroidBelt`)
+/// - `perturber_radius`: Orbit of the nearest giant planet outside the belt in AU (0.0 if none)
+/// - `largest_bodies`: Array of notable asteroid dictionaries
+pub fn asteroid_belt_record(belt: &AsteroidBelt, ctx: &BodyContext) -> Record {
+    let mut record = Record::new();
+    record.set("name", belt.name.clone());
+    record.set("inner_radius", belt.inner_radius);
+    record.set("outer_radius", belt.outer_radius);
+    record.set("total_mass", belt.total_mass);
+    record.set("asteroid_count", belt.asteroid_count as i64);
+    record.set(
+        "seed",
+        hash_f64s(
+            ctx.system_seed,
+            &[belt.inner_radius, belt.outer_radius, belt.total_mass],
+        ) as i64,
+    );
+
+    // Innermost giant beyond the belt carves its resonance gaps
+    let perturber = ctx
+        .giant_orbits
+        .iter()
+        .copied()
+        .filter(|r| *r > belt.outer_radius)
+        .fold(f64::INFINITY, f64::min);
+    record.set(
+        "perturber_radius",
+        if perturber.is_finite() {
+            perturber
+        } else {
+            0.0
+        },
+    );
+    record.set(
+        "largest_bodies",
+        belt.largest_bodies
+            .iter()
+            .map(asteroid_record)
+            .collect::<Vec<_>>(),
+    );
+    record
+}
+
+/// Builds an asteroid record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `asteroid_type`: Type of asteroid (Carbonaceous, Silicate, Metallic)
+/// - `mass`: Asteroid mass
+/// - `diameter`: Diameter in kilometers
+/// - `orbital_radius`: Distance from star in AU
+/// - `position`: Position around the star in AU, in the system local frame (Y up)
+pub fn asteroid_record(asteroid: &Asteroid) -> Record {
+    let asteroid_type = match asteroid.asteroid_type {
+        AsteroidType::Carbonaceous => "Carbonaceous",
+        AsteroidType::Silicate => "Silicate",
+        AsteroidType::Metallic => "Metallic",
+    };
+    let mut record = Record::new();
+    record.set("asteroid_type", asteroid_type);
+    record.set("mass", asteroid.mass);
+    record.set("diameter", asteroid.diameter);
+    record.set("orbital_radius", asteroid.orbital_radius);
+    record.set("position", local_vec3_record(&asteroid.position));
+    record
+}
+
+/// Builds an Oort cloud record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `inner_radius`: Inner radius in AU
+/// - `outer_radius`: Outer radius in AU
+/// - `estimated_population`: Estimated number of objects
+/// - `total_mass`: Total mass of the cloud
+/// - `notable_comets`: Array of notable comet dictionaries
+pub fn oort_cloud_record(oort: &OortCloud, ctx: &BodyContext) -> Record {
+    let mut record = Record::new();
+    record.set("inner_radius", oort.inner_radius);
+    record.set("outer_radius", oort.outer_radius);
+    record.set("estimated_population", oort.estimated_population as i64);
+    record.set("total_mass", oort.total_mass);
+    record.set(
+        "notable_comets",
+        oort.notable_comets
+            .iter()
+            .map(|comet| comet_record(comet, ctx))
+            .collect::<Vec<_>>(),
+    );
+    record
+}
+
+/// Builds a comet record.
+///
+/// # Returns
+///
+/// A record containing:
+/// - `comet_type`: Type of comet (ShortPeriod, LongPeriod, Hyperbolic)
+/// - `mass`: Comet mass
+/// - `nucleus_diameter`: Diameter of nucleus in kilometers
+/// - `orbital_radius`: Semi-major axis in AU
+/// - `eccentricity`: Orbital eccentricity
+/// - `seed`: Deterministic body seed
+/// - `semi_major_axis`: Semi-major axis in AU (absolute value for hyperbolic orbits)
+/// - `inclination`: Inclination to the system plane in radians
+/// - `longitude_of_ascending_node`: Longitude of the ascending node in radians
+/// - `argument_of_periapsis`: Argument of periapsis in radians
+/// - `time_of_perihelion`: Perihelion passage in years relative to the epoch
+/// - `period`: Orbital period in years (0.0 for unbound orbits)
+/// - `periapsis`: Perihelion distance in AU
+/// - `central_mass`: Mass the comet orbits in solar masses
+/// - `host_luminosity`: Luminosity driving the tail in solar luminosities
+/// - `passage_start`, `passage_end`: Active window in years (unbound orbits only)
+/// - `position`: Position around the star in AU at the epoch, in the system local frame
+///   (Y up), matching `MeiComet.get_comet_state(comet, 0)`
+pub fn comet_record(comet: &Comet, ctx: &BodyContext) -> Record {
+    let (comet_type, class) = match comet.comet_type {
+        CometType::ShortPeriod => ("ShortPeriod", CometClass::ShortPeriod),
+        CometType::LongPeriod => ("LongPeriod", CometClass::LongPeriod),
+        CometType::Hyperbolic => ("Hyperbolic", CometClass::Hyperbolic),
+    };
+    let mut record = Record::new();
+    record.set("comet_type", comet_type);
+    record.set("mass", comet.mass);
+    record.set("nucleus_diameter", comet.nucleus_diameter);
+    record.set("orbital_radius", comet.orbital_radius);
+    record.set("eccentricity", comet.eccentricity);
+
+    // MEI has no orientation or phase for comets, so those come from the seed
+    let seed = hash_f64s(
+        ctx.system_seed,
+        &[
+            comet.orbital_radius,
+            comet.eccentricity,
+            comet.mass,
+            comet.nucleus_diameter,
+        ],
+    );
+    let orbit = CometOrbit::derive(
+        seed,
+        class,
+        comet.orbital_radius,
+        comet.eccentricity,
+        ctx.stellar_mass,
+    );
+    record.set("seed", seed as i64);
+    record.set("semi_major_axis", orbit.elements.semi_major_axis);
+    record.set("inclination", orbit.elements.inclination);
+    record.set(
+        "longitude_of_ascending_node",
+        orbit.elements.longitude_of_ascending_node,
+    );
+    record.set(
+        "argument_of_periapsis",
+        orbit.elements.argument_of_periapsis,
+    );
+    record.set("time_of_perihelion", orbit.time_of_perihelion);
+    record.set("period", orbit.period().unwrap_or(0.0));
+    record.set("periapsis", orbit.elements.periapsis());
+    record.set("central_mass", ctx.stellar_mass);
+    record.set("host_luminosity", ctx.luminosity);
+    if !orbit.is_bound() {
+        if let Some((start, end)) = orbit.passage_window(ACTIVITY_RADIUS_AU * ctx.luminosity.sqrt())
+        {
+            record.set("passage_start", start);
+            record.set("passage_end", end);
+        }
+    }
+    // Where get_comet_state puts it at the epoch, not MEI's unphased position
+    let [x, y, z] = orbit.state_at(0.0).position;
+    record.set("position", vec3_record(&Vec3::new(x, y, z)));
+    record
+}
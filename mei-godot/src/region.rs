@@ -0,0 +1,238 @@
+//! Star queries over regions wider than one MEI nearby query.
+//!
+//! MEI answers `get_nearby_stars` for at most `nearby_max_radius`. A wider
+//! sphere is covered with cube tiles small enough for one query each, visited
+//! nearest first so searches can stop as soon as they have enough matches.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use mei::api::galaxy_api::GalaxyAPI;
+use mei::util::vec::Vec3;
+
+/// Most stars taken from a single tile query.
+const STARS_PER_TILE: usize = 10_000;
+
+/// One cube of a [`RegionTiles`] cover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    /// Cube center in light-years
+    pub center: [f64; 3],
+    /// Radius of the nearby query that covers the cube
+    pub query_radius: f64,
+    /// Distance from the region center to the nearest point of the cube
+    pub min_distance: f64,
+    edge: f64,
+}
+
+impl Tile {
+    /// Whether a point lies in this tile's cube. Cubes are half-open, so
+    /// every point belongs to exactly one tile.
+    pub fn contains(&self, p: [f64; 3]) -> bool {
+        (0..3).all(|axis| {
+            let offset = p[axis] - self.center[axis];
+            offset >= -self.edge / 2.0 && offset < self.edge / 2.0
+        })
+    }
+}
+
+/// Tiles covering a sphere, nearest first.
+///
+/// Tiles are generated a ring (a shell of the cube grid) at a time, only as
+/// far out as the caller iterates.
+pub struct RegionTiles {
+    center: [f64; 3],
+    radius: f64,
+    edge: f64,
+    query_radius: f64,
+    queue: BinaryHeap<Reverse<(u64, [i64; 3])>>,
+    next_ring: i64,
+}
+
+impl RegionTiles {
+    /// # Arguments
+    ///
+    /// * `center` - Region center in light-years
+    /// * `radius` - Region radius in light-years
+    /// * `max_query_radius` - Largest radius one nearby query may use
+    pub fn new(center: [f64; 3], radius: f64, max_query_radius: f64) -> Self {
+        let radius = radius.max(0.0);
+        let (edge, query_radius) = if radius <= max_query_radius || max_query_radius <= 0.0 {
+            // One query covers it all (or is all MEI allows)
+            (f64::INFINITY, radius.min(max_query_radius.max(0.0)))
+        } else {
+            // The largest cube a query sphere covers, shrunk a hair for rounding
+            let edge = 2.0 * max_query_radius / 3f64.sqrt() * (1.0 - 1e-9);
+            (edge, max_query_radius)
+        };
+        Self {
+            center,
+            radius,
+            edge,
+            query_radius,
+            queue: BinaryHeap::new(),
+            next_ring: 0,
+        }
+    }
+
+    /// A lower bound on the distance from the center of every point in tiles
+    /// not yet returned, or infinity once every tile has been.
+    pub fn horizon(&self) -> f64 {
+        let queued = self
+            .queue
+            .peek()
+            .map_or(f64::INFINITY, |Reverse((bits, _))| f64::from_bits(*bits));
+        queued.min(self.ring_bound(self.next_ring))
+    }
+
+    /// Nearest possible distance of any tile in a ring, or infinity if the
+    /// ring lies wholly outside the region.
+    fn ring_bound(&self, ring: i64) -> f64 {
+        let bound = if ring == 0 {
+            0.0
+        } else {
+            (ring as f64 - 0.5) * self.edge
+        };
+        if bound <= self.radius {
+            bound
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    fn min_distance(&self, index: [i64; 3]) -> f64 {
+        if index == [0; 3] {
+            return 0.0;
+        }
+        index
+            .iter()
+            .map(|i| ((i.abs() as f64 - 0.5) * self.edge).max(0.0).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn push_ring(&mut self, ring: i64) {
+        let mut indices = Vec::new();
+        for i in -ring..=ring {
+            for j in -ring..=ring {
+                if i.abs() == ring || j.abs() == ring {
+                    indices.extend((-ring..=ring).map(|k| [i, j, k]));
+                } else {
+                    indices.extend([[i, j, -ring], [i, j, ring]]);
+                }
+            }
+        }
+        for index in indices {
+            let distance = self.min_distance(index);
+            if distance <= self.radius {
+                self.queue.push(Reverse((distance.to_bits(), index)));
+            }
+        }
+    }
+}
+
+impl Iterator for RegionTiles {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        // Fill the queue until no unseen ring could hold a nearer tile
+        loop {
+            let bound = self.ring_bound(self.next_ring);
+            let queued = self
+                .queue
+                .peek()
+                .map_or(f64::INFINITY, |Reverse((bits, _))| f64::from_bits(*bits));
+            if !bound.is_finite() || bound > queued {
+                break;
+            }
+            self.push_ring(self.next_ring);
+            self.next_ring += 1;
+            if !self.edge.is_finite() {
+                // A single tile: there are no further rings
+                self.next_ring = i64::MAX;
+            }
+        }
+
+        let Reverse((bits, index)) = self.queue.pop()?;
+        let offset = |axis: usize| {
+            if self.edge.is_finite() {
+                index[axis] as f64 * self.edge
+            } else {
+                0.0
+            }
+        };
+        Some(Tile {
+            center: [
+                self.center[0] + offset(0),
+                self.center[1] + offset(1),
+                self.center[2] + offset(2),
+            ],
+            query_radius: self.query_radius,
+            min_distance: f64::from_bits(bits),
+            edge: self.edge,
+        })
+    }
+}
+
+/// A star found in a region.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionStar {
+    pub id: String,
+    /// Galactic position in light-years
+    pub position: [f64; 3],
+    /// Distance from the region center in light-years
+    pub distance: f64,
+}
+
+/// Visits the stars within `radius` of `center` in batches, nearest first
+/// across all batches, querying only as many tiles as needed.
+///
+/// `visit` gets the API back for generating systems and returns `false` to
+/// stop.
+pub fn visit_nearest_first(
+    api: &mut GalaxyAPI,
+    center: [f64; 3],
+    radius: f64,
+    mut visit: impl FnMut(&GalaxyAPI, Vec<RegionStar>) -> bool,
+) {
+    let mut tiles = RegionTiles::new(center, radius, api.generator.config.nearby_max_radius);
+    let distance = |p: [f64; 3]| {
+        ((p[0] - center[0]).powi(2) + (p[1] - center[1]).powi(2) + (p[2] - center[2]).powi(2)).sqrt()
+    };
+
+    // Found but not yet visited, farthest first
+    let mut pending: Vec<RegionStar> = Vec::new();
+    loop {
+        let tile = tiles.next();
+        if let Some(tile) = &tile {
+            let query_center = Vec3::new(tile.center[0], tile.center[1], tile.center[2]);
+            for star in api
+                .generator
+                .get_nearby_stars(&query_center, tile.query_radius, STARS_PER_TILE)
+            {
+                let position = [star.position.x, star.position.y, star.position.z];
+                let d = distance(position);
+                if d <= radius && tile.contains(position) {
+                    pending.push(RegionStar {
+                        id: (star.id as i64).to_string(),
+                        position,
+                        distance: d,
+                    });
+                }
+            }
+        }
+
+        // Everything nearer than any unqueried tile is in its final order
+        let horizon = tiles.horizon();
+        pending.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+        let split = pending.partition_point(|s| s.distance > horizon);
+        let mut ready = pending.split_off(split);
+        ready.reverse();
+        if !ready.is_empty() && !visit(api, ready) {
+            return;
+        }
+        if tile.is_none() {
+            return;
+        }
+    }
+}
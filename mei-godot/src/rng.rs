@@ -0,0 +1,79 @@
+//! Deterministic hashing and random number helpers.
+//!
+//! MEI only stores what it needs to generate a galaxy, so the bindings derive
+//! extra per-body values (texture seeds, orientations, ...) by hashing data MEI
+//! already gives us. Everything here is pure integer arithmetic so the same
+//! input produces the same output on every platform.
+
+/// Mixes a 64-bit value using the SplitMix64 finalizer.
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Combines two hash values into one, order-dependent.
+pub fn hash_combine(a: u64, b: u64) -> u64 {
+    mix64(
+        a ^ b
+            .wrapping_add(0x9e37_79b9_7f4a_7c15)
+            .wrapping_add(a << 6)
+            .wrapping_add(a >> 2),
+    )
+}
+
+/// Hashes a string with FNV-1a and finalizes it with [`mix64`].
+pub fn hash_str(s: &str) -> u64 {
+    hash_bytes(s.as_bytes())
+}
+
+/// Hashes bytes with FNV-1a and finalizes them with [`mix64`].
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        h ^= *byte as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    mix64(h)
+}
+
+/// Hashes a slice of floats by their bit patterns.
+pub fn hash_f64s(seed: u64, values: &[f64]) -> u64 {
+    values
+        .iter()
+        .fold(seed, |h, v| hash_combine(h, v.to_bits()))
+}
+
+/// Small deterministic generator (SplitMix64) for per-body randomness.
+#[derive(Debug, Clone)]
+pub struct DetRng {
+    state: u64,
+}
+
+impl DetRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next raw 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix64(self.state)
+    }
+
+    /// Returns a float uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns a float uniformly distributed in `[min, max)`.
+    pub fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    /// Returns an index uniformly distributed in `[0, n)`. `n` must be non-zero.
+    pub fn index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
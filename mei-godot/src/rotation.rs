@@ -0,0 +1,146 @@
+//! Galactic rotation and stellar peculiar motion.
+//!
+//! MEI places every star where it is today. The bindings move stars through
+//! time with a rotation curve (solid-body in the core, flat beyond it) plus a
+//! deterministic per-star peculiar velocity, so inner stars lap outer ones
+//! and the arms shear the way a real disk does. Peculiar motion is an
+//! epicycle-like oscillation around the star's guiding orbit rather than a
+//! straight line, so stars stay in their part of the galaxy over long spans.
+//!
+//! Times are in millions of years (Myr) and velocities in light-years per
+//! Myr. The disk rotates counterclockwise seen from +Y, so the spiral arms of
+//! [`Morphology`] trail.
+
+use std::f64::consts::TAU;
+
+use crate::morphology::{Morphology, Region};
+use crate::rng::{hash_combine, DetRng};
+
+/// One km/s in light-years per million years.
+pub const KM_S_IN_LY_PER_MYR: f64 = 3.335_64;
+
+/// Speed of the flat part of the rotation curve, in km/s.
+pub const CIRCULAR_SPEED_KM_S: f64 = 220.0;
+
+/// Ratio of the vertical oscillation frequency to the epicyclic frequency,
+/// as measured near the Sun.
+const VERTICAL_FREQUENCY_RATIO: f64 = 2.0;
+
+/// Salt separating peculiar velocity draws from other per-star hashes.
+const VELOCITY_SALT: u64 = 0x524f_5441_5445_3031;
+
+/// Circular speed as a function of galactocentric radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationCurve {
+    /// Speed of the flat part of the curve, in ly/Myr
+    pub circular_speed: f64,
+    /// Radius inside which the disk rotates like a solid body
+    pub core_radius: f64,
+}
+
+impl RotationCurve {
+    /// A Milky Way curve for `morphology`: 220 km/s, turning over at the bulge.
+    pub fn milky_way(morphology: &Morphology) -> Self {
+        Self {
+            circular_speed: CIRCULAR_SPEED_KM_S * KM_S_IN_LY_PER_MYR,
+            core_radius: morphology.bulge_radius,
+        }
+    }
+
+    /// Circular speed at radius `r`, in ly/Myr.
+    pub fn speed(&self, r: f64) -> f64 {
+        r * self.angular_speed(r)
+    }
+
+    /// Angular speed at radius `r`, in radians per Myr.
+    pub fn angular_speed(&self, r: f64) -> f64 {
+        self.circular_speed / r.hypot(self.core_radius)
+    }
+
+    /// Frequency of radial oscillations around a circular orbit at `r`, in
+    /// radians per Myr: twice the angular speed in the core, √2 times it
+    /// where the curve is flat.
+    pub fn epicyclic_frequency(&self, r: f64) -> f64 {
+        let flatness = r * r / (r * r + self.core_radius * self.core_radius);
+        self.angular_speed(r) * (4.0 - 2.0 * flatness).sqrt()
+    }
+
+    /// Orbital period at radius `r`, in Myr.
+    pub fn period(&self, r: f64) -> f64 {
+        TAU / self.angular_speed(r)
+    }
+}
+
+/// Velocity dispersion of a region's stars in km/s, as (radial,
+/// tangential, vertical). Young arm stars move almost on circles; old
+/// populations are kinematically hot.
+pub fn velocity_dispersion(region: Region) -> [f64; 3] {
+    match region {
+        Region::Arm => [10.0, 7.0, 5.0],
+        Region::Disk => [30.0, 20.0, 15.0],
+        Region::ThickDisk => [60.0, 40.0, 40.0],
+        Region::Bulge => [110.0, 110.0, 100.0],
+        Region::Bar => [100.0, 80.0, 70.0],
+        Region::Halo => [150.0, 100.0, 100.0],
+    }
+}
+
+/// Deterministic peculiar velocity of a star in ly/Myr, as (radial,
+/// tangential, vertical) components relative to its circular orbit.
+pub fn peculiar_velocity(galaxy_seed: u64, id: i64, region: Region) -> [f64; 3] {
+    let mut rng = DetRng::new(hash_combine(
+        hash_combine(galaxy_seed, VELOCITY_SALT),
+        id as u64,
+    ));
+    velocity_dispersion(region).map(|sigma| sigma * KM_S_IN_LY_PER_MYR * normal(&mut rng))
+}
+
+/// Standard normal deviate (Box–Muller).
+fn normal(rng: &mut DetRng) -> f64 {
+    let u = 1.0 - rng.next_f64();
+    (-2.0 * u.ln()).sqrt() * (TAU * rng.next_f64()).cos()
+}
+
+/// Position of a star `time_myr` million years from now, negative for the
+/// past. `position` is where MEI places the star today.
+///
+/// The star's guiding center turns with the rotation curve at its current
+/// radius while the star oscillates around it with its peculiar velocity,
+/// so zero time returns `position` unchanged.
+pub fn position_at_time(
+    galaxy_seed: u64,
+    id: i64,
+    position: [f64; 3],
+    morphology: &Morphology,
+    curve: &RotationCurve,
+    time_myr: f64,
+) -> [f64; 3] {
+    let [x, y, z] = position;
+    let r = x.hypot(z);
+    let [u, v, w] = peculiar_velocity(galaxy_seed, id, morphology.region(position));
+
+    // Displacements along the local radial, tangential and vertical axes
+    let kappa = curve.epicyclic_frequency(r);
+    let nu = VERTICAL_FREQUENCY_RATIO * kappa;
+    let radial = u * (kappa * time_myr).sin() / kappa;
+    let tangential = v * (kappa * time_myr).sin() / kappa;
+    let vertical = w * (nu * time_myr).sin() / nu;
+
+    // Local axes today; at the center any horizontal pair will do
+    let (radial_axis, tangential_axis) = if r > 0.0 {
+        ([x / r, z / r], [z / r, -x / r])
+    } else {
+        ([1.0, 0.0], [0.0, -1.0])
+    };
+    let moved_x = x + radial * radial_axis[0] + tangential * tangential_axis[0];
+    let moved_z = z + radial * radial_axis[1] + tangential * tangential_axis[1];
+
+    // Counterclockwise seen from +Y, so the arms trail
+    let angle = -curve.angular_speed(r) * time_myr;
+    let (sin, cos) = angle.sin_cos();
+    [
+        moved_x * cos - moved_z * sin,
+        y + vertical,
+        moved_x * sin + moved_z * cos,
+    ]
+}
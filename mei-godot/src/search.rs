@@ -0,0 +1,254 @@
+use godot::prelude::*;
+
+/// The parts of a generated star system that search filters look at.
+///
+/// Kept free of MEI types so filters can be evaluated on worker threads and
+/// reused for summaries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemFacts {
+    /// Star type names, e.g. "MainSequenceG"
+    pub star_types: Vec<String>,
+    /// Stellar configuration name, e.g. "Single" or "CloseBinary"
+    pub configuration: String,
+    /// Planet type names in orbital order
+    pub planet_types: Vec<String>,
+    /// Type of the most massive planet, if there are planets
+    pub dominant_planet_type: Option<String>,
+    /// Number of planets orbiting inside the habitable zone
+    pub habitable_planets: usize,
+    pub moon_count: usize,
+    pub asteroid_belts: usize,
+    pub has_oort_cloud: bool,
+}
+
+/// Stellar configuration names, indexed by the codes in `get_system_summaries`.
+pub const CONFIGURATION_NAMES: [&str; 5] = [
+    "Single",
+    "CloseBinary",
+    "WideBinary",
+    "HierarchicalTriple",
+    "UnstableTriple",
+];
+
+/// Planet type names, indexed by the codes in `get_system_summaries`.
+pub const PLANET_TYPE_NAMES: [&str; 14] = [
+    "Dwarf",
+    "Terrestrial",
+    "SuperEarth",
+    "Desert",
+    "Ocean",
+    "Lava",
+    "MiniNeptune",
+    "SubNeptune",
+    "IceGiant",
+    "GasGiant",
+    "HotJupiter",
+    "Chthonian",
+    "Carbon",
+    "Coreless",
+];
+
+/// Code used in packed summaries when there is no value (e.g. no planets).
+pub const NO_CODE: u8 = u8::MAX;
+
+/// Index of `name` in a name table, or [`NO_CODE`].
+pub fn name_code(names: &[&str], name: &str) -> u8 {
+    names
+        .iter()
+        .position(|n| *n == name)
+        .map_or(NO_CODE, |i| i as u8)
+}
+
+/// Constraints for `MeiGalaxy.search_systems`. Unset fields match anything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemFilter {
+    /// Some star's type name must start with one of these, so "WhiteDwarf"
+    /// matches every white dwarf subtype
+    pub star_types: Vec<String>,
+    /// The configuration must be one of these
+    pub configurations: Vec<String>,
+    pub min_planets: Option<usize>,
+    pub max_planets: Option<usize>,
+    /// Every listed planet type must be present
+    pub planet_types: Vec<String>,
+    pub habitable_planets: Option<bool>,
+    pub asteroid_belts: Option<bool>,
+    pub oort_cloud: Option<bool>,
+}
+
+/// Filter keys accepted by [`SystemFilter::from_dict`].
+pub const FILTER_KEYS: [&str; 8] = [
+    "star_types",
+    "configurations",
+    "min_planets",
+    "max_planets",
+    "planet_types",
+    "habitable_planets",
+    "asteroid_belts",
+    "oort_cloud",
+];
+
+impl SystemFilter {
+    /// Reads a filter from a GDScript dictionary.
+    ///
+    /// Plain `Array`s of strings are accepted where a `PackedStringArray` is
+    /// expected, and whole floats where a count is. Returns the unknown keys
+    /// and the keys whose values have the wrong type alongside the filter, so
+    /// callers can report them instead of silently matching everything.
+    pub fn from_dict(dict: &Dictionary) -> (Self, Vec<String>, Vec<String>) {
+        let mut invalid = Vec::new();
+        let filter = Self {
+            star_types: read(dict, "star_types", &mut invalid, strings).unwrap_or_default(),
+            configurations: read(dict, "configurations", &mut invalid, strings).unwrap_or_default(),
+            min_planets: read(dict, "min_planets", &mut invalid, count),
+            max_planets: read(dict, "max_planets", &mut invalid, count),
+            planet_types: read(dict, "planet_types", &mut invalid, strings).unwrap_or_default(),
+            habitable_planets: read(dict, "habitable_planets", &mut invalid, flag),
+            asteroid_belts: read(dict, "asteroid_belts", &mut invalid, flag),
+            oort_cloud: read(dict, "oort_cloud", &mut invalid, flag),
+        };
+
+        let unknown = dict
+            .keys_array()
+            .iter_shared()
+            .map(|k| k.to_string())
+            .filter(|k| !FILTER_KEYS.contains(&k.as_str()))
+            .collect();
+        (filter, unknown, invalid)
+    }
+
+    /// Checks a system against the filter.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the system does not match, otherwise one human-readable
+    /// reason per constraint that was set
+    pub fn matches(&self, facts: &SystemFacts) -> Option<Vec<String>> {
+        let mut reasons = Vec::new();
+
+        if !self.star_types.is_empty() {
+            let star = facts.star_types.iter().find(|t| {
+                let t = t.to_ascii_lowercase();
+                self.star_types
+                    .iter()
+                    .any(|wanted| t.starts_with(&wanted.to_ascii_lowercase()))
+            })?;
+            reasons.push(format!("star type {}", star));
+        }
+
+        if !self.configurations.is_empty() {
+            self.configurations
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(&facts.configuration))?;
+            reasons.push(format!("{} configuration", facts.configuration));
+        }
+
+        let planets = facts.planet_types.len();
+        if self.min_planets.is_some_and(|min| planets < min)
+            || self.max_planets.is_some_and(|max| planets > max)
+        {
+            return None;
+        }
+        if self.min_planets.is_some() || self.max_planets.is_some() {
+            reasons.push(format!("{} planets", planets));
+        }
+
+        for wanted in &self.planet_types {
+            let count = facts
+                .planet_types
+                .iter()
+                .filter(|t| t.eq_ignore_ascii_case(wanted))
+                .count();
+            if count == 0 {
+                return None;
+            }
+            reasons.push(format!("{} {} planet(s)", count, wanted));
+        }
+
+        if let Some(wanted) = self.habitable_planets {
+            if (facts.habitable_planets > 0) != wanted {
+                return None;
+            }
+            reasons.push(if wanted {
+                format!(
+                    "{} planet(s) in the habitable zone",
+                    facts.habitable_planets
+                )
+            } else {
+                "no habitable-zone planets".to_string()
+            });
+        }
+
+        if let Some(wanted) = self.asteroid_belts {
+            if (facts.asteroid_belts > 0) != wanted {
+                return None;
+            }
+            reasons.push(if wanted {
+                format!("{} asteroid belt(s)", facts.asteroid_belts)
+            } else {
+                "no asteroid belts".to_string()
+            });
+        }
+
+        if let Some(wanted) = self.oort_cloud {
+            if facts.has_oort_cloud != wanted {
+                return None;
+            }
+            reasons.push(
+                if wanted {
+                    "Oort cloud"
+                } else {
+                    "no Oort cloud"
+                }
+                .to_string(),
+            );
+        }
+
+        Some(reasons)
+    }
+}
+
+/// Reads `key` with `convert`, recording it in `invalid` if it is set to a
+/// value `convert` does not accept.
+fn read<T>(
+    dict: &Dictionary,
+    key: &str,
+    invalid: &mut Vec<String>,
+    convert: fn(&Variant) -> Option<T>,
+) -> Option<T> {
+    let value = dict.get(key)?;
+    let converted = convert(&value);
+    if converted.is_none() {
+        invalid.push(key.to_string());
+    }
+    converted
+}
+
+fn strings(value: &Variant) -> Option<Vec<String>> {
+    if let Ok(packed) = value.try_to::<PackedStringArray>() {
+        return Some(packed.as_slice().iter().map(|s| s.to_string()).collect());
+    }
+    value
+        .try_to::<VariantArray>()
+        .ok()?
+        .iter_shared()
+        .map(|item| {
+            item.try_to::<GString>()
+                .map(|s| s.to_string())
+                .or_else(|_| item.try_to::<StringName>().map(|s| s.to_string()))
+                .ok()
+        })
+        .collect()
+}
+
+fn count(value: &Variant) -> Option<usize> {
+    let n = value.try_to::<i64>().ok().or_else(|| {
+        let f = value.try_to::<f64>().ok()?;
+        (f.fract() == 0.0).then_some(f as i64)
+    })?;
+    Some(n.max(0) as usize)
+}
+
+fn flag(value: &Variant) -> Option<bool> {
+    value.try_to::<bool>().ok()
+}
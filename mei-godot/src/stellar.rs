@@ -0,0 +1,351 @@
+//! Per-star physical values shared by every query that returns stars.
+//!
+//! MEI offers two luminosities for a star: `Star::luminosity()`, computed
+//! from the star's own mass, and `StarType::luminosity()`, a typical value
+//! for its whole class. The bindings only ever use the per-star values, and
+//! derive everything else from them here, so a star looks the same whether
+//! it came from `get_structure`, `get_nearby_stars` or `get_star_system`.
+
+use std::fmt;
+
+use mei::space_objects::star::Star;
+
+/// Effective temperature of the Sun in Kelvin.
+pub const SOLAR_TEMPERATURE: f64 = 5772.0;
+
+/// Bolometric absolute magnitude of the Sun.
+pub const SOLAR_ABSOLUTE_MAGNITUDE: f64 = 4.74;
+
+/// Schwarzschild radius of one solar mass, in solar radii (2.953 km).
+const SCHWARZSCHILD_RADIUS_PER_MASS: f64 = 4.245e-6;
+
+/// Typical neutron star radius in solar radii (12 km).
+const NEUTRON_STAR_RADIUS: f64 = 1.725e-5;
+
+/// Surface gravity of the Sun, log10 of cm/s^2.
+const SOLAR_LOG_G: f64 = 4.438;
+
+/// Second radiation constant hc/k in nm K.
+const C2: f64 = 1.438_777e7;
+
+/// Star type names whose remnants have no photosphere to classify.
+const REMNANT_TYPES: [&str; 4] = ["NeutronStar", "Pulsar", "Magnetar", "BlackHole"];
+
+/// Temperature classes in order, hottest first.
+const CLASSES: [&str; 10] = ["O", "B", "A", "F", "G", "K", "M", "L", "T", "Y"];
+
+/// Main sequence effective temperatures by spectral class and subclass,
+/// hottest first (Pecaut & Mamajek 2013, rounded).
+const SPECTRAL_TEMPERATURES: [(char, u8, f64); 53] = [
+    ('O', 3, 44_900.0),
+    ('O', 5, 41_400.0),
+    ('O', 6, 39_500.0),
+    ('O', 7, 37_100.0),
+    ('O', 8, 35_100.0),
+    ('O', 9, 33_300.0),
+    ('B', 0, 31_400.0),
+    ('B', 1, 26_000.0),
+    ('B', 2, 20_600.0),
+    ('B', 3, 17_000.0),
+    ('B', 5, 15_700.0),
+    ('B', 6, 14_500.0),
+    ('B', 7, 14_000.0),
+    ('B', 8, 12_300.0),
+    ('B', 9, 10_700.0),
+    ('A', 0, 9_700.0),
+    ('A', 1, 9_300.0),
+    ('A', 2, 8_800.0),
+    ('A', 3, 8_600.0),
+    ('A', 5, 8_100.0),
+    ('A', 7, 7_650.0),
+    ('F', 0, 7_200.0),
+    ('F', 2, 6_810.0),
+    ('F', 5, 6_510.0),
+    ('F', 8, 6_170.0),
+    ('G', 0, 5_920.0),
+    ('G', 2, 5_770.0),
+    ('G', 5, 5_660.0),
+    ('G', 8, 5_490.0),
+    ('K', 0, 5_280.0),
+    ('K', 1, 5_170.0),
+    ('K', 2, 5_040.0),
+    ('K', 3, 4_830.0),
+    ('K', 4, 4_600.0),
+    ('K', 5, 4_410.0),
+    ('K', 7, 4_070.0),
+    ('M', 0, 3_850.0),
+    ('M', 1, 3_660.0),
+    ('M', 2, 3_560.0),
+    ('M', 3, 3_430.0),
+    ('M', 4, 3_210.0),
+    ('M', 5, 3_060.0),
+    ('M', 6, 2_810.0),
+    ('M', 7, 2_680.0),
+    ('M', 8, 2_570.0),
+    ('M', 9, 2_380.0),
+    ('L', 0, 2_270.0),
+    ('L', 2, 2_100.0),
+    ('L', 5, 1_700.0),
+    ('L', 8, 1_400.0),
+    ('T', 2, 1_200.0),
+    ('T', 6, 950.0),
+    ('Y', 0, 450.0),
+];
+
+/// Everything the bindings report about one star.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StarProperties {
+    pub id: i64,
+    /// Debug name of the MEI star type, e.g. "MainSequenceG"
+    pub star_type: String,
+    pub position: [f64; 3],
+    /// Solar masses
+    pub mass: f64,
+    /// Solar luminosities
+    pub luminosity: f64,
+    /// Effective temperature in Kelvin
+    pub temperature: f64,
+    /// Solar radii
+    pub radius: f64,
+    /// Bolometric absolute magnitude
+    pub absolute_magnitude: f64,
+    /// `None` for remnants without a photosphere
+    pub spectral_class: Option<SpectralClass>,
+    /// sRGB color of the star's blackbody spectrum
+    pub color: [f32; 3],
+}
+
+impl StarProperties {
+    /// The canonical conversion of an MEI star.
+    pub fn of(star: &Star) -> Self {
+        let star_type = format!("{:?}", star.star_type);
+        let luminosity = star.luminosity();
+        let temperature = star.temperature();
+        let radius = stellar_radius(&star_type, luminosity, temperature, star.mass);
+        Self {
+            id: star.id as i64,
+            position: [star.position.x, star.position.y, star.position.z],
+            mass: star.mass,
+            luminosity,
+            temperature,
+            radius,
+            absolute_magnitude: absolute_magnitude(luminosity),
+            spectral_class: SpectralClass::classify(
+                &star_type,
+                temperature,
+                luminosity,
+                star.mass,
+                radius,
+            ),
+            color: blackbody_color(temperature),
+            star_type,
+        }
+    }
+
+    /// Full spectral class such as "G2V", empty for remnants.
+    pub fn spectral_class_name(&self) -> String {
+        self.spectral_class
+            .as_ref()
+            .map_or_else(String::new, SpectralClass::to_string)
+    }
+}
+
+/// Radius in solar radii.
+///
+/// Stars with a photosphere follow the Stefan-Boltzmann law; neutron stars
+/// get a typical 12 km and black holes their event horizon.
+pub fn stellar_radius(star_type: &str, luminosity: f64, temperature: f64, mass: f64) -> f64 {
+    if star_type.starts_with("BlackHole") {
+        SCHWARZSCHILD_RADIUS_PER_MASS * mass
+    } else if REMNANT_TYPES.iter().any(|t| star_type.starts_with(t)) {
+        NEUTRON_STAR_RADIUS
+    } else if luminosity <= 0.0 || temperature <= 0.0 {
+        0.0
+    } else {
+        luminosity.sqrt() * (SOLAR_TEMPERATURE / temperature).powi(2)
+    }
+}
+
+/// Bolometric absolute magnitude, infinite for stars that emit nothing.
+pub fn absolute_magnitude(luminosity: f64) -> f64 {
+    if luminosity <= 0.0 {
+        f64::INFINITY
+    } else {
+        SOLAR_ABSOLUTE_MAGNITUDE - 2.5 * luminosity.log10()
+    }
+}
+
+/// A Morgan-Keenan spectral classification, e.g. G2V or B2.5Ib.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralClass {
+    /// Temperature class: O to Y, "DA" for white dwarfs or "WN" for
+    /// Wolf-Rayet stars
+    pub class: &'static str,
+    /// Subclass within `class` in steps of 0.5, 0 hottest
+    pub subclass: f64,
+    /// Luminosity class from "Ia" (bright supergiant) to "V" (dwarf), empty
+    /// for white and brown dwarfs
+    pub luminosity_class: &'static str,
+}
+
+impl SpectralClass {
+    /// Classifies a star from its physical values.
+    ///
+    /// Class and subclass are interpolated along the main sequence
+    /// temperature scale. Stars with a dwarf's surface gravity get class V or
+    /// IV; evolved stars are ranked by luminosity, since cool giants and
+    /// supergiants overlap in gravity. Returns `None` for neutron stars and
+    /// black holes.
+    pub fn classify(
+        star_type: &str,
+        temperature: f64,
+        luminosity: f64,
+        mass: f64,
+        radius: f64,
+    ) -> Option<Self> {
+        if REMNANT_TYPES.iter().any(|t| star_type.starts_with(t)) || temperature <= 0.0 {
+            return None;
+        }
+        if star_type.starts_with("WhiteDwarf") {
+            // White dwarf temperature index, 50,400 K / T
+            return Some(Self {
+                class: "DA",
+                subclass: half_step(50_400.0 / temperature).max(0.5),
+                luminosity_class: "",
+            });
+        }
+        if star_type.starts_with("WolfRayet") {
+            // WN2 at ~141,000 K down to WN9 at ~32,000 K
+            let t = (141_000.0 / temperature).ln() / (141_000.0_f64 / 32_000.0).ln();
+            return Some(Self {
+                class: "WN",
+                subclass: half_step(2.0 + 7.0 * t).clamp(2.0, 9.0),
+                luminosity_class: "",
+            });
+        }
+
+        let code = temperature_code(temperature);
+        let class = CLASSES[(code / 10.0) as usize];
+        let substellar = star_type.starts_with("BrownDwarf") || matches!(class, "L" | "T" | "Y");
+        let log_g = if radius > 0.0 && mass > 0.0 {
+            SOLAR_LOG_G + mass.log10() - 2.0 * radius.log10()
+        } else {
+            SOLAR_LOG_G
+        };
+        let luminosity_class = if substellar {
+            ""
+        } else if log_g >= 3.9 {
+            "V"
+        } else if log_g >= 3.4 {
+            "IV"
+        } else if luminosity >= 1.0e5 {
+            "Ia"
+        } else if luminosity >= 1.0e4 {
+            "Ib"
+        } else if luminosity >= 1.0e3 {
+            "II"
+        } else {
+            "III"
+        };
+        Some(Self {
+            class,
+            subclass: code % 10.0,
+            luminosity_class,
+        })
+    }
+}
+
+impl fmt::Display for SpectralClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.class, self.subclass, self.luminosity_class
+        )
+    }
+}
+
+/// Rounds to the nearest half.
+fn half_step(v: f64) -> f64 {
+    (v * 2.0).round() / 2.0
+}
+
+/// Position on the temperature scale as class index * 10 + subclass,
+/// interpolated in log temperature and rounded to half a subclass.
+fn temperature_code(temperature: f64) -> f64 {
+    let code = |(class, subclass, _): (char, u8, f64)| {
+        let index = CLASSES.iter().position(|c| c.starts_with(class)).unwrap();
+        (index * 10) as f64 + subclass as f64
+    };
+    let first = SPECTRAL_TEMPERATURES[0];
+    let last = SPECTRAL_TEMPERATURES[SPECTRAL_TEMPERATURES.len() - 1];
+    if temperature >= first.2 {
+        return code(first);
+    }
+    if temperature <= last.2 {
+        return code(last);
+    }
+    let (hot, cool) = SPECTRAL_TEMPERATURES
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|(_, cool)| temperature > cool.2)
+        .unwrap();
+    let t = (hot.2 / temperature).ln() / (hot.2 / cool.2).ln();
+    half_step(code(hot) + t * (code(cool) - code(hot))).min(code(cool))
+}
+
+/// sRGB color of a blackbody at `temperature`, normalized so the brightest
+/// channel is 1.
+///
+/// Integrates Planck's law against the CIE 1931 2-degree color matching
+/// functions, converts XYZ to linear sRGB (D65), clips the colors of cool
+/// stars that fall outside the sRGB gamut and applies the sRGB transfer
+/// curve. Returns black for non-positive temperatures.
+pub fn blackbody_color(temperature: f64) -> [f32; 3] {
+    if temperature <= 0.0 {
+        return [0.0; 3];
+    }
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    for step in 0..=94 {
+        let nm = 360.0 + 5.0 * step as f64;
+        let radiance = 1.0 / (nm.powi(5) * ((C2 / (nm * temperature)).exp() - 1.0));
+        let [xb, yb, zb] = color_matching(nm);
+        x += radiance * xb;
+        y += radiance * yb;
+        z += radiance * zb;
+    }
+
+    let linear = [
+        3.2406 * x - 1.5372 * y - 0.4986 * z,
+        -0.9689 * x + 1.8758 * y + 0.0415 * z,
+        0.0557 * x - 0.2040 * y + 1.0570 * z,
+    ]
+    .map(|c: f64| c.max(0.0));
+    let max = linear.iter().copied().fold(0.0, f64::max);
+    if max <= 0.0 || !max.is_finite() {
+        return [0.0; 3];
+    }
+    linear.map(|c| {
+        let c = c / max;
+        let encoded = if c <= 0.003_130_8 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        encoded as f32
+    })
+}
+
+/// CIE 1931 2-degree color matching functions at a wavelength in nm, using
+/// the multi-lobe Gaussian fit of Wyman, Sloan and Shirley (2013).
+fn color_matching(nm: f64) -> [f64; 3] {
+    let g = |mu: f64, below: f64, above: f64| {
+        let sigma = if nm < mu { below } else { above };
+        (-0.5 * ((nm - mu) / sigma).powi(2)).exp()
+    };
+    [
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    ]
+}
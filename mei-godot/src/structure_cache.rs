@@ -0,0 +1,271 @@
+//! Compact binary snapshots of `get_structure` results.
+//!
+//! Layout (little-endian):
+//!
+//! ```text
+//! magic "MEIS" | version u32 | key u64 | estimated_total i64 | count u64
+//! type_count u16 | type_count x (len u16, utf-8 bytes)
+//! class_count u16 | class_count x (len u16, utf-8 bytes)
+//! count x (x f32, y f32, z f32, id i64, luminosity f32, temperature f32, mass f32,
+//!          radius f32, absolute_magnitude f32, r f32, g f32, b f32, type u16, class u16)
+//! checksum u64 (hash of every preceding byte)
+//! ```
+
+use std::fmt;
+
+use crate::rng::hash_bytes;
+
+const MAGIC: &[u8; 4] = b"MEIS";
+
+/// Bump whenever the layout or the meaning of a field changes.
+pub const FORMAT_VERSION: u32 = 3;
+
+/// Bytes per star record.
+const RECORD_SIZE: usize = 3 * 4 + 8 + 8 * 4 + 2 * 2;
+
+/// One star of the galactic structure, as rendered by the viewer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StructureStar {
+    pub position: [f32; 3],
+    pub id: i64,
+    pub luminosity: f32,
+    pub temperature: f32,
+    pub mass: f32,
+    /// Solar radii
+    pub radius: f32,
+    pub absolute_magnitude: f32,
+    /// sRGB
+    pub color: [f32; 3],
+    /// Index into [`StructureData::type_names`]
+    pub star_type: u16,
+    /// Index into [`StructureData::spectral_class_names`]
+    pub spectral_class: u16,
+}
+
+/// A whole `get_structure` result.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructureData {
+    pub stars: Vec<StructureStar>,
+    pub type_names: Vec<String>,
+    pub spectral_class_names: Vec<String>,
+    pub estimated_total: i64,
+}
+
+/// Floats per star in [`StructureData::fill_interleaved`]: x, y, z,
+/// luminosity, temperature, mass, star type code and radius, i.e. two
+/// `vec4`s per star in a shader.
+pub const INTERLEAVED_STRIDE: usize = 8;
+
+impl StructureData {
+    /// Writes every star into `out`, [`INTERLEAVED_STRIDE`] floats each.
+    ///
+    /// `out` must hold exactly `stars.len() * INTERLEAVED_STRIDE` floats.
+    pub fn fill_interleaved(&self, out: &mut [f32]) {
+        assert_eq!(out.len(), self.stars.len() * INTERLEAVED_STRIDE);
+        for (chunk, star) in out.chunks_exact_mut(INTERLEAVED_STRIDE).zip(&self.stars) {
+            chunk.copy_from_slice(&[
+                star.position[0],
+                star.position[1],
+                star.position[2],
+                star.luminosity,
+                star.temperature,
+                star.mass,
+                star.star_type as f32,
+                star.radius,
+            ]);
+        }
+    }
+
+    /// Index of a star type name, adding it to the table if needed.
+    pub fn type_index(&mut self, name: &str) -> u16 {
+        intern(&mut self.type_names, name)
+    }
+
+    /// Index of a spectral class, adding it to the table if needed.
+    pub fn spectral_class_index(&mut self, name: &str) -> u16 {
+        intern(&mut self.spectral_class_names, name)
+    }
+}
+
+fn intern(names: &mut Vec<String>, name: &str) -> u16 {
+    match names.iter().position(|n| n == name) {
+        Some(i) => i as u16,
+        None => {
+            names.push(name.to_string());
+            (names.len() - 1) as u16
+        }
+    }
+}
+
+/// Why a cache file could not be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    BadMagic,
+    UnsupportedVersion(u32),
+    /// Written for a different seed, config or star count
+    Stale,
+    Truncated,
+    ChecksumMismatch,
+    Malformed(&'static str),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::BadMagic => write!(f, "not a structure cache file"),
+            CacheError::UnsupportedVersion(v) => {
+                write!(f, "format version {} (expected {})", v, FORMAT_VERSION)
+            }
+            CacheError::Stale => write!(f, "written for a different seed or config"),
+            CacheError::Truncated => write!(f, "file is truncated"),
+            CacheError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            CacheError::Malformed(what) => write!(f, "malformed {}", what),
+        }
+    }
+}
+
+/// Serializes structure data for the given cache key.
+pub fn encode(data: &StructureData, key: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(64 + data.stars.len() * RECORD_SIZE);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&key.to_le_bytes());
+    out.extend_from_slice(&data.estimated_total.to_le_bytes());
+    out.extend_from_slice(&(data.stars.len() as u64).to_le_bytes());
+
+    write_names(&mut out, &data.type_names);
+    write_names(&mut out, &data.spectral_class_names);
+
+    for star in &data.stars {
+        for c in star.position {
+            out.extend_from_slice(&c.to_le_bytes());
+        }
+        out.extend_from_slice(&star.id.to_le_bytes());
+        out.extend_from_slice(&star.luminosity.to_le_bytes());
+        out.extend_from_slice(&star.temperature.to_le_bytes());
+        out.extend_from_slice(&star.mass.to_le_bytes());
+        out.extend_from_slice(&star.radius.to_le_bytes());
+        out.extend_from_slice(&star.absolute_magnitude.to_le_bytes());
+        for c in star.color {
+            out.extend_from_slice(&c.to_le_bytes());
+        }
+        out.extend_from_slice(&star.star_type.to_le_bytes());
+        out.extend_from_slice(&star.spectral_class.to_le_bytes());
+    }
+
+    let checksum = hash_bytes(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+fn write_names(out: &mut Vec<u8>, names: &[String]) {
+    out.extend_from_slice(&(names.len() as u16).to_le_bytes());
+    for name in names {
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+    }
+}
+
+/// Parses a cache file, checking it was written for `key`.
+pub fn decode(bytes: &[u8], key: u64) -> Result<StructureData, CacheError> {
+    let mut reader = Reader { bytes, at: 0 };
+    if reader.take(4)? != MAGIC {
+        return Err(CacheError::BadMagic);
+    }
+    let version = reader.u32()?;
+    if version != FORMAT_VERSION {
+        return Err(CacheError::UnsupportedVersion(version));
+    }
+    if reader.u64()? != key {
+        return Err(CacheError::Stale);
+    }
+
+    let (body, checksum) = bytes.split_at(bytes.len().checked_sub(8).ok_or(CacheError::Truncated)?);
+    if hash_bytes(body) != u64::from_le_bytes(checksum.try_into().unwrap()) {
+        return Err(CacheError::ChecksumMismatch);
+    }
+
+    let estimated_total = reader.u64()? as i64;
+    let count = reader.u64()? as usize;
+    let type_names = reader.names("star type name")?;
+    let spectral_class_names = reader.names("spectral class")?;
+
+    if body.len() - reader.at != count.saturating_mul(RECORD_SIZE) {
+        return Err(CacheError::Malformed("star count"));
+    }
+    let stars = (0..count)
+        .map(|_| {
+            let star = StructureStar {
+                position: [reader.f32()?, reader.f32()?, reader.f32()?],
+                id: reader.u64()? as i64,
+                luminosity: reader.f32()?,
+                temperature: reader.f32()?,
+                mass: reader.f32()?,
+                radius: reader.f32()?,
+                absolute_magnitude: reader.f32()?,
+                color: [reader.f32()?, reader.f32()?, reader.f32()?],
+                star_type: reader.u16()?,
+                spectral_class: reader.u16()?,
+            };
+            if star.star_type as usize >= type_names.len() {
+                return Err(CacheError::Malformed("star type index"));
+            }
+            if star.spectral_class as usize >= spectral_class_names.len() {
+                return Err(CacheError::Malformed("spectral class index"));
+            }
+            Ok(star)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(StructureData {
+        stars,
+        type_names,
+        spectral_class_names,
+        estimated_total,
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CacheError> {
+        let end = self.at.checked_add(n).ok_or(CacheError::Truncated)?;
+        let slice = self.bytes.get(self.at..end).ok_or(CacheError::Truncated)?;
+        self.at = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CacheError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u16(&mut self) -> Result<u16, CacheError> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, CacheError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, CacheError> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32, CacheError> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    /// A u16 count followed by that many length-prefixed strings.
+    fn names(&mut self, what: &'static str) -> Result<Vec<String>, CacheError> {
+        let count = self.u16()? as usize;
+        (0..count)
+            .map(|_| {
+                let len = self.u16()? as usize;
+                String::from_utf8(self.take(len)?.to_vec()).map_err(|_| CacheError::Malformed(what))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use godot::classes::{DirAccess, IResource, Resource};
+use godot::prelude::*;
+
+use crate::rng::{hash_combine, DetRng};
+
+/// Data-driven mapping from body type and temperature to artist-authored textures.
+///
+/// `rules` maps a lookup key to a list of texture family names. Keys are tried
+/// from most to least specific:
+/// - `"<Type>/<Band>"`, e.g. `"Terrestrial/Temperate"`
+/// - `"<Type>"`, e.g. `"GasGiant"`
+/// - `"*"`
+///
+/// where `<Type>` is a `planet_type` or `moon_type` and `<Band>` is one of
+/// `Frozen`, `Cold`, `Temperate`, `Hot`, `Scorching`.
+///
+/// `families` maps a family name to a `PackedStringArray` of texture paths. It
+/// is filled from `texture_root` (one subfolder per family) the first time a
+/// texture is selected, unless it was authored by hand.
+#[derive(GodotClass)]
+#[class(base=Resource, tool)]
+pub struct MeiTextureCatalog {
+    base: Base<Resource>,
+    /// Folder scanned for texture families when `families` is empty
+    #[export]
+    texture_root: GString,
+    /// Lookup key -> `PackedStringArray` of family names
+    #[export]
+    rules: Dictionary,
+    /// Family name -> `PackedStringArray` of texture paths
+    #[export]
+    families: Dictionary,
+}
+
+#[godot_api]
+impl IResource for MeiTextureCatalog {
+    /// Initializes a catalog with the default rules for the bundled texture pack.
+    fn init(base: Base<Resource>) -> Self {
+        let mut rules = Dictionary::new();
+        for (key, families) in DEFAULT_RULES {
+            let families: PackedStringArray = families.iter().map(|f| GString::from(*f)).collect();
+            rules.set(*key, families);
+        }
+
+        Self {
+            base,
+            texture_root: "res://assets/PlanetTextures".into(),
+            rules,
+            families: Dictionary::new(),
+        }
+    }
+}
+
+#[godot_api]
+impl MeiTextureCatalog {
+    /// Picks a texture path for a planet or moon dictionary.
+    ///
+    /// The choice depends only on the body's type, temperature and `seed`, so
+    /// the same body always gets the same texture.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - A planet or moon `Dictionary` from `get_star_system`
+    ///
+    /// # Returns
+    ///
+    /// A texture path, or an empty string if no family matched
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var catalog = MeiTextureCatalog.new()
+    /// var path = catalog.select_texture(system["inner_planets"][0])
+    /// ```
+    #[func]
+    fn select_texture(&mut self, body: Dictionary) -> GString {
+        if self.families.is_empty() {
+            let root = self.texture_root.clone();
+            self.scan_directory(root);
+        }
+
+        let body_type = body
+            .get("planet_type")
+            .or_else(|| body.get("moon_type"))
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        let key = BodyKey {
+            body_type: &body_type,
+            temperature: body
+                .get("temperature")
+                .and_then(|v| v.try_to::<f64>().ok())
+                .unwrap_or(288.0),
+            seed: body
+                .get("seed")
+                .and_then(|v| v.try_to::<i64>().ok())
+                .unwrap_or(0) as u64,
+        };
+
+        select_texture(
+            &key,
+            &string_table(&self.rules),
+            &string_table(&self.families),
+        )
+        .map(GString::from)
+        .unwrap_or_default()
+    }
+
+    /// Fills `families` from a folder with one subfolder per texture family.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Root folder, e.g. `"res://assets/PlanetTextures"`
+    ///
+    /// # Returns
+    ///
+    /// Number of families found
+    #[func]
+    fn scan_directory(&mut self, path: GString) -> i64 {
+        let root = path.to_string().trim_end_matches('/').to_string();
+        let mut families = Dictionary::new();
+
+        for family in DirAccess::get_directories_at(&path).as_slice() {
+            let folder = format!("{}/{}", root, family);
+            let mut textures: Vec<String> =
+                DirAccess::get_files_at(&GString::from(folder.as_str()))
+                    .as_slice()
+                    .iter()
+                    .map(|f| f.to_string())
+                    // Exported projects only list the .import sidecar files
+                    .map(|f| f.trim_end_matches(".import").to_string())
+                    .filter(|f| f.ends_with(".png"))
+                    .map(|f| format!("{}/{}", folder, f))
+                    .collect();
+            textures.sort();
+            textures.dedup();
+
+            if !textures.is_empty() {
+                let packed: PackedStringArray =
+                    textures.iter().map(|t| GString::from(t.as_str())).collect();
+                families.set(family.clone(), packed);
+            }
+        }
+
+        let count = families.len() as i64;
+        self.families = families;
+        godot_print!(
+            "MeiTextureCatalog found {} texture families in {}",
+            count,
+            root
+        );
+        count
+    }
+
+    /// Gets the temperature band name used in rule keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `temperature` - Temperature in Kelvin
+    ///
+    /// # Returns
+    ///
+    /// One of `Frozen`, `Cold`, `Temperate`, `Hot`, `Scorching`
+    #[func]
+    fn get_temperature_band(temperature: f64) -> GString {
+        TemperatureBand::from_kelvin(temperature).name().into()
+    }
+}
+
+/// Converts a Dictionary of `String -> PackedStringArray` into a Rust map.
+fn string_table(dict: &Dictionary) -> HashMap<String, Vec<String>> {
+    dict.iter_shared()
+        .filter_map(|(k, v)| {
+            let values = v.try_to::<PackedStringArray>().ok()?;
+            Some((
+                k.to_string(),
+                values.as_slice().iter().map(|s| s.to_string()).collect(),
+            ))
+        })
+        .collect()
+}
+
+/// Coarse temperature classes used to pick texture families.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureBand {
+    Frozen,
+    Cold,
+    Temperate,
+    Hot,
+    Scorching,
+}
+
+impl TemperatureBand {
+    /// Classifies a temperature in Kelvin.
+    pub fn from_kelvin(temperature: f64) -> Self {
+        if temperature < 200.0 {
+            TemperatureBand::Frozen
+        } else if temperature < 260.0 {
+            TemperatureBand::Cold
+        } else if temperature < 330.0 {
+            TemperatureBand::Temperate
+        } else if temperature < 700.0 {
+            TemperatureBand::Hot
+        } else {
+            TemperatureBand::Scorching
+        }
+    }
+
+    /// Band name as used in rule keys.
+    pub fn name(self) -> &'static str {
+        match self {
+            TemperatureBand::Frozen => "Frozen",
+            TemperatureBand::Cold => "Cold",
+            TemperatureBand::Temperate => "Temperate",
+            TemperatureBand::Hot => "Hot",
+            TemperatureBand::Scorching => "Scorching",
+        }
+    }
+}
+
+/// The body properties texture selection depends on.
+pub struct BodyKey<'a> {
+    pub body_type: &'a str,
+    pub temperature: f64,
+    pub seed: u64,
+}
+
+/// Deterministically picks a texture path for a body.
+///
+/// Families without any textures are skipped, so a catalog can list families
+/// that are not installed yet.
+pub fn select_texture(
+    body: &BodyKey,
+    rules: &HashMap<String, Vec<String>>,
+    families: &HashMap<String, Vec<String>>,
+) -> Option<String> {
+    let band = TemperatureBand::from_kelvin(body.temperature);
+    let candidates = [
+        format!("{}/{}", body.body_type, band.name()),
+        body.body_type.to_string(),
+        "*".to_string(),
+    ];
+
+    let available: Vec<&Vec<String>> = candidates
+        .iter()
+        .filter_map(|key| rules.get(key))
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| families.get(name))
+                .filter(|textures| !textures.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .find(|found| !found.is_empty())?;
+
+    let mut rng = DetRng::new(hash_combine(body.seed, 0x7e8_70e5));
+    let family = available[rng.index(available.len())];
+    Some(family[rng.index(family.len())].clone())
+}
+
+/// Default rules for the bundled Screaming Brain Studios texture pack.
+const DEFAULT_RULES: &[(&str, &[&str])] = &[
+    ("*", &["Barren"]),
+    // Planets
+    ("Dwarf", &["Barren", "Dusty", "Martian"]),
+    ("Dwarf/Frozen", &["Snowy", "Barren"]),
+    ("Terrestrial", &["Barren", "Dusty"]),
+    ("Terrestrial/Frozen", &["Snowy", "Tundra"]),
+    ("Terrestrial/Cold", &["Tundra", "Marshy"]),
+    ("Terrestrial/Temperate", &["Grassland", "Jungle", "Marshy"]),
+    ("Terrestrial/Hot", &["Arid", "Sandy"]),
+    ("Terrestrial/Scorching", &["Martian", "Barren"]),
+    ("SuperEarth", &["Barren", "Dusty"]),
+    ("SuperEarth/Frozen", &["Snowy", "Tundra"]),
+    ("SuperEarth/Cold", &["Tundra", "Marshy"]),
+    ("SuperEarth/Temperate", &["Jungle", "Grassland", "Marshy"]),
+    ("SuperEarth/Hot", &["Arid", "Sandy"]),
+    ("Desert", &["Sandy", "Arid", "Dusty"]),
+    ("Desert/Frozen", &["Dusty", "Martian"]),
+    ("Desert/Cold", &["Martian", "Dusty"]),
+    ("Ocean", &["Marshy"]),
+    ("Ocean/Frozen", &["Snowy"]),
+    ("Ocean/Cold", &["Snowy", "Tundra"]),
+    ("Ocean/Temperate", &["Marshy", "Jungle"]),
+    ("Lava", &["Martian", "Barren"]),
+    ("Chthonian", &["Barren", "Dusty"]),
+    ("Carbon", &["Barren", "Dusty"]),
+    ("MiniNeptune", &["Methane", "Gaseous"]),
+    ("SubNeptune", &["Methane", "Gaseous"]),
+    ("IceGiant", &["Methane", "Snowy"]),
+    ("GasGiant", &["Gaseous"]),
+    ("GasGiant/Frozen", &["Gaseous", "Methane"]),
+    ("HotJupiter", &["Gaseous"]),
+    ("Coreless", &["Gaseous"]),
+    // Moons
+    ("Rocky", &["Barren", "Dusty"]),
+    ("Icy", &["Snowy", "Tundra"]),
+    ("IceRock", &["Tundra", "Barren"]),
+    ("Volcanic", &["Martian", "Dusty"]),
+    ("Captured", &["Barren", "Dusty"]),
+    ("Atmospheric", &["Dusty", "Arid"]),
+    ("Atmospheric/Frozen", &["Methane"]),
+];
@@ -0,0 +1,321 @@
+//! Unit conversions and coordinate frames.
+//!
+//! Lengths convert through kilometres and masses through kilograms, using
+//! the IAU's exact astronomical unit and light-year and its nominal solar,
+//! Earth and Jupiter values. Frames:
+//!
+//! - Galactic cartesian: MEI's frame in light-years, centered on the core,
+//!   disk in the X/Z plane, Y up.
+//! - Galactic spherical: longitude, latitude and distance seen from an
+//!   observer. Longitude 0° points at the galactic core and latitude +90° is
+//!   +Y. MEI's disk turns the opposite way to the Milky Way's as seen from
+//!   galactic north, so stars orbit towards longitude 270° instead of 90°.
+//! - System ecliptic: astronomical units around a star with Z up, the frame
+//!   orbital elements use. MEI gives systems no tilt, so the ecliptic is
+//!   parallel to the galactic plane.
+//! - System local: the ecliptic turned Y-up for Godot, still in AU (km for
+//!   moons around their planet). Every position inside a `get_star_system`
+//!   result and every orbit mesh uses it.
+
+use std::f64::consts::PI;
+
+use godot::prelude::*;
+
+/// Kilometres in one astronomical unit (exact, IAU 2012).
+pub const KM_PER_AU: f64 = 149_597_870.7;
+
+/// Kilometres in one light-year (exact: a Julian year at the speed of light).
+pub const KM_PER_LY: f64 = 9_460_730_472_580.8;
+
+/// Astronomical units in one parsec (exact, IAU 2015).
+pub const AU_PER_PC: f64 = 648_000.0 / PI;
+
+/// Astronomical units in one light-year.
+pub const AU_PER_LY: f64 = KM_PER_LY / KM_PER_AU;
+
+/// Light-years in one parsec.
+pub const LY_PER_PC: f64 = AU_PER_PC * KM_PER_AU / KM_PER_LY;
+
+/// Newtonian constant of gravitation in m³/(kg·s²) (CODATA 2018).
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+
+/// Masses from the IAU 2015 nominal mass parameters (GM, in m³/s²).
+pub const KG_PER_SOLAR_MASS: f64 = 1.327_124_4e20 / GRAVITATIONAL_CONSTANT;
+pub const KG_PER_EARTH_MASS: f64 = 3.986_004e14 / GRAVITATIONAL_CONSTANT;
+pub const KG_PER_JUPITER_MASS: f64 = 1.266_865_3e17 / GRAVITATIONAL_CONSTANT;
+
+/// IAU 2015 nominal radii (equatorial for Earth and Jupiter), in km.
+pub const KM_PER_SOLAR_RADIUS: f64 = 695_700.0;
+pub const KM_PER_EARTH_RADIUS: f64 = 6_378.1;
+pub const KM_PER_JUPITER_RADIUS: f64 = 71_492.0;
+
+/// What a unit measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Length,
+    Mass,
+}
+
+/// Unit names accepted by [`convert`], with what they measure and their size
+/// in kilometres or kilograms.
+pub const UNITS: [(&str, Quantity, f64); 12] = [
+    ("m", Quantity::Length, 1.0e-3),
+    ("km", Quantity::Length, 1.0),
+    ("au", Quantity::Length, KM_PER_AU),
+    ("ly", Quantity::Length, KM_PER_LY),
+    ("pc", Quantity::Length, AU_PER_PC * KM_PER_AU),
+    ("solar_radius", Quantity::Length, KM_PER_SOLAR_RADIUS),
+    ("earth_radius", Quantity::Length, KM_PER_EARTH_RADIUS),
+    ("jupiter_radius", Quantity::Length, KM_PER_JUPITER_RADIUS),
+    ("kg", Quantity::Mass, 1.0),
+    ("solar_mass", Quantity::Mass, KG_PER_SOLAR_MASS),
+    ("earth_mass", Quantity::Mass, KG_PER_EARTH_MASS),
+    ("jupiter_mass", Quantity::Mass, KG_PER_JUPITER_MASS),
+];
+
+/// Looks up a unit by name, ignoring case.
+pub fn unit(name: &str) -> Option<(Quantity, f64)> {
+    UNITS
+        .iter()
+        .find(|(unit, _, _)| unit.eq_ignore_ascii_case(name))
+        .map(|&(_, quantity, size)| (quantity, size))
+}
+
+/// Converts `value` between two units of the same quantity.
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, String> {
+    let lookup = |name: &str| unit(name).ok_or_else(|| format!("Unknown unit \"{}\"", name));
+    let (from_quantity, from_size) = lookup(from)?;
+    let (to_quantity, to_size) = lookup(to)?;
+    if from_quantity != to_quantity {
+        return Err(format!(
+            "Cannot convert {:?} \"{}\" to {:?} \"{}\"",
+            from_quantity, from, to_quantity, to
+        ));
+    }
+    Ok(value * from_size / to_size)
+}
+
+/// A direction and distance seen from an observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spherical {
+    /// Degrees in `0..360`, 0 towards the galactic core
+    pub longitude: f64,
+    /// Degrees in `-90..=90`, positive towards +Y
+    pub latitude: f64,
+    /// Light-years
+    pub distance: f64,
+}
+
+/// Longitude axes at an observer: towards the core and 90° from it, both
+/// horizontal. An observer at the core looks along +X.
+fn longitude_axes(observer: [f64; 3]) -> ([f64; 3], [f64; 3]) {
+    let r = observer[0].hypot(observer[2]);
+    let (a, c) = if r > 0.0 {
+        (-observer[0] / r, -observer[2] / r)
+    } else {
+        (1.0, 0.0)
+    };
+    // Y cross the core direction, so the frame is right-handed with Y up
+    ([a, 0.0, c], [c, 0.0, -a])
+}
+
+/// Galactic spherical coordinates of `position` seen from `observer`, both
+/// galactic cartesian positions in light-years.
+pub fn galactic_to_spherical(position: [f64; 3], observer: [f64; 3]) -> Spherical {
+    let d = [0, 1, 2].map(|axis| position[axis] - observer[axis]);
+    let (core, side) = longitude_axes(observer);
+    let x = d[0] * core[0] + d[2] * core[2];
+    let y = d[0] * side[0] + d[2] * side[2];
+    let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+    let latitude = if distance > 0.0 {
+        (d[1] / distance).clamp(-1.0, 1.0).asin().to_degrees()
+    } else {
+        0.0
+    };
+    Spherical {
+        longitude: y.atan2(x).to_degrees().rem_euclid(360.0),
+        latitude,
+        distance,
+    }
+}
+
+/// Galactic cartesian position of a direction and distance seen from
+/// `observer`.
+pub fn spherical_to_galactic(spherical: Spherical, observer: [f64; 3]) -> [f64; 3] {
+    let (core, side) = longitude_axes(observer);
+    let (sin_l, cos_l) = spherical.longitude.to_radians().sin_cos();
+    let (sin_b, cos_b) = spherical.latitude.to_radians().sin_cos();
+    let horizontal = spherical.distance * cos_b;
+    [
+        observer[0] + horizontal * (cos_l * core[0] + sin_l * side[0]),
+        observer[1] + spherical.distance * sin_b,
+        observer[2] + horizontal * (cos_l * core[2] + sin_l * side[2]),
+    ]
+}
+
+/// System ecliptic position in AU of a galactic offset from the star in
+/// light-years.
+pub fn galactic_to_ecliptic(offset: [f64; 3]) -> [f64; 3] {
+    let [x, y, z] = offset.map(|c| c * AU_PER_LY);
+    // Y-up to Z-up by a quarter turn about X, the inverse of how orbital
+    // elements are turned into Godot's frame. A rotation, not an axis swap,
+    // so prograde orbits stay counter-clockwise seen from above.
+    [x, -z, y]
+}
+
+/// Galactic offset from the star in light-years of a system ecliptic
+/// position in AU.
+pub fn ecliptic_to_galactic(ecliptic: [f64; 3]) -> [f64; 3] {
+    ecliptic_to_local(ecliptic.map(|c| c / AU_PER_LY))
+}
+
+/// System local (Y up) vector of a system ecliptic (Z up) one, in the same
+/// units.
+pub fn ecliptic_to_local([x, y, z]: [f64; 3]) -> [f64; 3] {
+    // A quarter turn about X, not an axis swap, so the frame keeps its
+    // handedness
+    [x, z, -y]
+}
+
+/// Unit conversions and coordinate frame transforms.
+///
+/// All methods are static, so the class works as a singleton:
+/// `MeiUnits.convert(1.0, "pc", "ly")`. Unit names are "m", "km", "au",
+/// "ly", "pc", "solar_radius", "earth_radius", "jupiter_radius", "kg",
+/// "solar_mass", "earth_mass" and "jupiter_mass".
+///
+/// MEI's own units: star masses, luminosities and radii are solar; planet
+/// masses are Earth masses; galactic positions are light-years; planet
+/// orbital radii are AU and moon orbital radii km.
+#[derive(GodotClass)]
+#[class(base=RefCounted, init)]
+pub struct MeiUnits {
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl MeiUnits {
+    /// Converts a value between units.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value in `from` units
+    /// * `from` - Unit name, e.g. "km"
+    /// * `to` - Unit name of the same quantity, e.g. "au"
+    ///
+    /// # Returns
+    ///
+    /// The value in `to` units, or NAN if a unit is unknown or the units
+    /// measure different quantities
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var moon_distance_au = MeiUnits.convert(moon["orbital_radius"], "km", "au")
+    /// ```
+    #[func]
+    fn convert(value: f64, from: GString, to: GString) -> f64 {
+        convert(value, &from.to_string(), &to.to_string()).unwrap_or_else(|error| {
+            godot_error!("MeiUnits.convert: {}", error);
+            f64::NAN
+        })
+    }
+
+    /// Gets galactic longitude, latitude and distance of a position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Galactic position in light-years
+    /// * `observer` - Galactic position of the observer in light-years
+    ///
+    /// # Returns
+    ///
+    /// A `Dictionary` containing:
+    /// - `longitude`: Degrees in 0..360, 0 towards the galactic core
+    /// - `latitude`: Degrees in -90..90, positive towards +Y
+    /// - `distance`: Light-years
+    ///
+    /// # Examples
+    ///
+    /// ```gdscript
+    /// var sky = MeiUnits.galactic_to_spherical(star_position, home_position)
+    /// print("l = %.1f, b = %.1f" % [sky["longitude"], sky["latitude"]])
+    /// ```
+    #[func]
+    fn galactic_to_spherical(position: Vector3, observer: Vector3) -> Dictionary {
+        let spherical = galactic_to_spherical(to_array(position), to_array(observer));
+        let mut result = Dictionary::new();
+        result.set("longitude", spherical.longitude);
+        result.set("latitude", spherical.latitude);
+        result.set("distance", spherical.distance);
+        result
+    }
+
+    /// Gets the galactic position of a direction and distance from an observer.
+    ///
+    /// # Arguments
+    ///
+    /// * `longitude` - Galactic longitude in degrees
+    /// * `latitude` - Galactic latitude in degrees
+    /// * `distance` - Distance in light-years
+    /// * `observer` - Galactic position of the observer in light-years
+    ///
+    /// # Returns
+    ///
+    /// The galactic position in light-years
+    #[func]
+    fn spherical_to_galactic(
+        longitude: f64,
+        latitude: f64,
+        distance: f64,
+        observer: Vector3,
+    ) -> Vector3 {
+        to_vector3(spherical_to_galactic(
+            Spherical {
+                longitude,
+                latitude,
+                distance,
+            },
+            to_array(observer),
+        ))
+    }
+
+    /// Converts a galactic offset from a star to its system's ecliptic frame.
+    ///
+    /// Take offsets from `MeiCoordinate.relative_to` or
+    /// `get_nearby_stars_relative` to keep them precise.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Galactic offset from the star in light-years
+    ///
+    /// # Returns
+    ///
+    /// The ecliptic position in AU, Z up
+    #[func]
+    fn galactic_to_ecliptic(offset: Vector3) -> Vector3 {
+        to_vector3(galactic_to_ecliptic(to_array(offset)))
+    }
+
+    /// Converts a system ecliptic position to a galactic offset from the star.
+    ///
+    /// # Arguments
+    ///
+    /// * `ecliptic` - Ecliptic position in AU, Z up
+    ///
+    /// # Returns
+    ///
+    /// The galactic offset from the star in light-years
+    #[func]
+    fn ecliptic_to_galactic(ecliptic: Vector3) -> Vector3 {
+        to_vector3(ecliptic_to_galactic(to_array(ecliptic)))
+    }
+}
+
+fn to_array(v: Vector3) -> [f64; 3] {
+    [v.x as f64, v.y as f64, v.z as f64]
+}
+
+fn to_vector3(v: [f64; 3]) -> Vector3 {
+    Vector3::new(v[0] as f32, v[1] as f32, v[2] as f32)
+}
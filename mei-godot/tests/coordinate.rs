@@ -0,0 +1,100 @@
+//! Precise coordinates and origin-relative queries.
+
+use mei::api::galaxy_api::GalaxyAPI;
+use mei::util::vec::Vec3;
+use mei_godot::coordinate::Coordinate;
+use mei_godot::naming::SectorCoord;
+use mei_godot::records::{nearby_from_stars, structure_data};
+
+const CELL_SIZE: f64 = 0.25;
+
+fn close(a: [f64; 3], b: [f64; 3], tolerance: f64) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() <= tolerance)
+}
+
+#[test]
+fn galactic_round_trip() {
+    for position in [
+        [0.0, 0.0, 0.0],
+        [41_250.3, 12.5, -8_000.126],
+        [-0.1, -249.9, 250.0],
+        [-49_999.999, 3.0, 49_999.999],
+    ] {
+        let coordinate = Coordinate::from_galactic(position, CELL_SIZE);
+        assert!(
+            close(coordinate.galactic(), position, 1e-9),
+            "{:?} became {:?}",
+            position,
+            coordinate.galactic()
+        );
+        assert_eq!(coordinate.sector, SectorCoord::containing(position));
+        for axis in 0..3 {
+            assert!((0..1000).contains(&coordinate.cell[axis]));
+            assert!(coordinate.local[axis] >= 0.0 && coordinate.local[axis] < CELL_SIZE);
+        }
+    }
+}
+
+#[test]
+fn offsets_stay_precise_far_from_the_core() {
+    let far = Coordinate::from_galactic([49_876.543_21, -12.5, -31_234.567_89], CELL_SIZE);
+    // A hundredth of an AU, far below what an f32 position can resolve out here
+    let step = 1.6e-7;
+    let moved = far.translated([step, 0.0, -step]);
+    let offset = moved.offset_from(&far);
+    assert!(close(offset, [step, 0.0, -step], 1e-12), "{:?}", offset);
+}
+
+#[test]
+fn translation_carries_into_cells_and_sectors() {
+    let start = [249.9, -0.05, 12_345.0];
+    let coordinate = Coordinate::from_galactic(start, CELL_SIZE);
+    for offset in [
+        [0.2, 0.1, 0.0],
+        [-500.0, 0.0, 1_000.25],
+        [3.0, -250.0, -12_345.0],
+    ] {
+        let moved = coordinate.translated(offset);
+        let target = [0, 1, 2].map(|axis| start[axis] + offset[axis]);
+        let expected = Coordinate::from_galactic(target, CELL_SIZE);
+        assert_eq!(moved.sector, expected.sector, "offset {:?}", offset);
+        assert_eq!(moved.cell, expected.cell, "offset {:?}", offset);
+        assert!(close(moved.galactic(), target, 1e-9));
+        assert!(close(moved.offset_from(&coordinate), offset, 1e-9));
+    }
+}
+
+#[test]
+fn relative_nearby_positions_keep_precision() {
+    let mut api = GalaxyAPI::new(42);
+    // The star farthest from the core, where f32 positions are coarsest
+    let structure = structure_data(&api, 2000);
+    let far = structure
+        .stars
+        .iter()
+        .max_by(|a, b| {
+            let r = |p: [f32; 3]| p[0].hypot(p[2]);
+            r(a.position).total_cmp(&r(b.position))
+        })
+        .unwrap();
+    let origin = Coordinate::from_galactic(far.position.map(f64::from), CELL_SIZE).cell_origin();
+
+    let center = Vec3::new(origin[0], origin[1], origin[2]);
+    let stars = api.generator.get_nearby_stars(&center, 15.0, 10_000);
+    let relative = nearby_from_stars(&stars, origin);
+    assert!(!stars.is_empty());
+    for (star, compact) in stars.iter().zip(&relative.stars) {
+        let exact = [
+            star.position.x - origin[0],
+            star.position.y - origin[1],
+            star.position.z - origin[2],
+        ];
+        assert!(
+            close(compact.position.map(f64::from), exact, 1e-5),
+            "star {}: {:?} vs {:?}",
+            star.id,
+            compact.position,
+            exact
+        );
+    }
+}
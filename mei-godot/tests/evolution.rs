@@ -0,0 +1,192 @@
+//! Stellar ages and evolution.
+
+use mei_godot::evolution::{evolve, main_sequence_lifetime, stellar_age, Phase, MAX_STELLAR_AGE};
+use mei_godot::morphology::{Morphology, Region};
+use mei_godot::stellar::{StarProperties, SOLAR_TEMPERATURE};
+
+const SEED: u64 = 42;
+
+fn star(
+    id: i64,
+    star_type: &str,
+    position: [f64; 3],
+    mass: f64,
+    luminosity: f64,
+) -> StarProperties {
+    let temperature = match star_type {
+        "WhiteDwarf" => 9000.0,
+        _ => SOLAR_TEMPERATURE * mass.powf(0.5),
+    };
+    StarProperties {
+        id,
+        star_type: star_type.to_string(),
+        position,
+        mass,
+        luminosity,
+        temperature,
+        radius: 1.0,
+        absolute_magnitude: 4.74,
+        spectral_class: None,
+        color: [1.0; 3],
+    }
+}
+
+/// A point on the first spiral arm, in the thin disk.
+fn arm_position(morphology: &Morphology) -> [f64; 3] {
+    let r = morphology.radius * 0.5;
+    let angle = morphology.arm_angle(0, r);
+    [r * angle.cos(), 0.0, r * angle.sin()]
+}
+
+#[test]
+fn today_is_unchanged() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let sun = star(7, "MainSequenceG", arm_position(&morphology), 1.0, 1.0);
+    let age = stellar_age(SEED, &sun, &morphology);
+    let now = evolve(&sun, &age, 0.0);
+    assert_eq!(now.phase, Phase::MainSequence);
+    assert_eq!(now.star_type, sun.star_type);
+    assert_eq!(now.luminosity, sun.luminosity);
+    assert_eq!(now.temperature, sun.temperature);
+    assert_eq!(now.age, age.age);
+}
+
+#[test]
+fn ages_are_deterministic_and_follow_region() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let arm = arm_position(&morphology);
+    assert_eq!(morphology.region(arm), Region::Arm);
+    assert_eq!(morphology.region([0.0; 3]), Region::Bulge);
+
+    for id in 0..50 {
+        // Light stars, so the main sequence lifetime does not cap the age
+        let young = star(id, "MainSequenceM", arm, 0.3, 0.01);
+        let old = star(id, "MainSequenceM", [100.0, 0.0, 100.0], 0.3, 0.01);
+        let young_age = stellar_age(SEED, &young, &morphology);
+        let old_age = stellar_age(SEED, &old, &morphology);
+        assert_eq!(young_age, stellar_age(SEED, &young, &morphology));
+        assert!(
+            young_age.age <= 1.0e9,
+            "arm star {} is {} years old",
+            id,
+            young_age.age
+        );
+        assert!(
+            old_age.age >= 8.0e9,
+            "bulge star {} is {} years old",
+            id,
+            old_age.age
+        );
+    }
+}
+
+#[test]
+fn main_sequence_stars_stay_on_the_main_sequence() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let massive = star(3, "MainSequenceO", [100.0, 0.0, 100.0], 30.0, 1.0e5);
+    let age = stellar_age(SEED, &massive, &morphology);
+    assert!(age.age < main_sequence_lifetime(30.0));
+
+    // ...and die soon after
+    let later = evolve(&massive, &age, main_sequence_lifetime(30.0));
+    assert_eq!(later.phase, Phase::BlackHole);
+    let earlier = evolve(&massive, &age, -age.age - 1.0);
+    assert_eq!(earlier.phase, Phase::Unformed);
+}
+
+#[test]
+fn sun_like_star_ends_as_white_dwarf() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let sun = star(7, "MainSequenceG", arm_position(&morphology), 1.0, 1.0);
+    let age = stellar_age(SEED, &sun, &morphology);
+
+    let phases: Vec<Phase> = [5.0e9, 1.02e10, 1.1e10, 2.0e10]
+        .iter()
+        .map(|years| evolve(&sun, &age, years - age.age).phase)
+        .collect();
+    assert_eq!(
+        phases,
+        [
+            Phase::MainSequence,
+            Phase::Subgiant,
+            Phase::Giant,
+            Phase::WhiteDwarf
+        ]
+    );
+
+    let giant = evolve(&sun, &age, 1.1e10 - age.age);
+    assert!(giant.radius > 10.0 && giant.temperature < 5000.0);
+    let remnant = evolve(&sun, &age, 2.0e10 - age.age);
+    assert!(remnant.mass < 1.0 && remnant.radius < 0.02);
+}
+
+#[test]
+fn white_dwarfs_have_a_past() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let dwarf = star(11, "WhiteDwarf", [100.0, 0.0, 100.0], 0.6, 1.0e-3);
+    let age = stellar_age(SEED, &dwarf, &morphology);
+    assert!(age.initial_mass > 0.6);
+
+    let cooler = evolve(&dwarf, &age, 1.0e9);
+    assert_eq!(cooler.phase, Phase::WhiteDwarf);
+    assert!(cooler.luminosity < dwarf.luminosity);
+
+    let progenitor = evolve(&dwarf, &age, -0.5 * age.age);
+    assert_eq!(progenitor.phase, Phase::MainSequence);
+    assert!(progenitor.mass > dwarf.mass);
+}
+
+#[test]
+fn supergiants_and_subgiants_are_evolved() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let disk = [100.0, 0.0, 100.0];
+
+    for (star_type, mass) in [("RedSupergiant", 15.0), ("BlueSupergiant", 20.0)] {
+        let supergiant = star(5, star_type, disk, mass, 1.0e5);
+        let age = stellar_age(SEED, &supergiant, &morphology);
+        assert!(age.age > main_sequence_lifetime(mass), "{} age {}", star_type, age.age);
+        let soon = evolve(&supergiant, &age, 1.0);
+        assert_eq!(soon.phase, Phase::Supergiant, "{}", star_type);
+        assert_eq!(soon.star_type, star_type);
+        assert_eq!(evolve(&supergiant, &age, 1.0e8).phase, Phase::NeutronStar);
+    }
+
+    let subgiant = star(6, "Subgiant", disk, 1.2, 3.0);
+    let age = stellar_age(SEED, &subgiant, &morphology);
+    assert_eq!(evolve(&subgiant, &age, 1.0).phase, Phase::Subgiant);
+    assert_eq!(
+        evolve(&subgiant, &age, -0.5 * age.age).phase,
+        Phase::MainSequence
+    );
+}
+
+#[test]
+fn no_star_is_older_than_the_universe() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let halo = [0.0, 30_000.0, 0.0];
+    for id in 0..50 {
+        for (star_type, mass, luminosity) in [
+            ("WhiteDwarf", 0.6, 1.0e-5),
+            ("WhiteDwarf", 0.5, 1.0e-3),
+            ("NeutronStar", 1.4, 1.0e-3),
+            ("BlackHole", 10.0, 0.0),
+            ("RedGiant", 0.8, 100.0),
+            ("Subgiant", 0.9, 2.0),
+        ] {
+            let evolved = star(id, star_type, halo, mass, luminosity);
+            let age = stellar_age(SEED, &evolved, &morphology);
+            assert!(
+                age.age <= MAX_STELLAR_AGE,
+                "{} {} is {} years old",
+                star_type,
+                id,
+                age.age
+            );
+            // The progenitor still had time to reach today's phase
+            assert_eq!(
+                evolve(&evolved, &age, 1.0).phase,
+                evolve(&evolved, &age, 0.0).phase
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Galaxy shape: regions, named arms and location descriptions.
+
+use std::f64::consts::TAU;
+
+use mei::api::galaxy_api::GalaxyAPI;
+use mei_godot::morphology::{
+    Morphology, Region, ARM_NAMES, HALO_METALLICITY, THICK_DISK_METALLICITY,
+};
+use mei_godot::records::{location_record, morphology_record, structure_data, Record, Value};
+use mei_godot::rng::DetRng;
+
+#[test]
+fn bar_lies_between_bulge_and_arms() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let (sin, cos) = morphology.bar_angle.sin_cos();
+    let along = |d: f64, height: f64| [d * cos, height, d * sin];
+    assert_eq!(morphology.region(along(0.0, 0.0)), Region::Bulge);
+    assert_eq!(morphology.region(along(6_000.0, 0.0)), Region::Bar);
+    assert_eq!(morphology.region(along(-6_000.0, 500.0)), Region::Bar);
+    assert_eq!(
+        morphology.region(along(6_000.0, 1_500.0)),
+        Region::ThickDisk
+    );
+    // Across the bar instead of along it
+    assert_eq!(
+        morphology.region([-6_000.0 * sin, 0.0, 6_000.0 * cos]),
+        Region::Disk
+    );
+    // Past the ends of the bar, where the arms start
+    assert_ne!(morphology.region(along(8_000.0, 0.0)), Region::Bar);
+}
+
+#[test]
+fn nearest_arm_is_named() {
+    let morphology = Morphology::milky_way(50_000.0);
+    for (arm, name) in ARM_NAMES.iter().enumerate() {
+        let (nearest, distance) = morphology
+            .nearest_arm(morphology.arm_point(arm, 20_000.0))
+            .unwrap();
+        assert_eq!(nearest, arm);
+        assert!(distance < 1e-6, "arm {} is {} ly away", arm, distance);
+        assert_eq!(morphology.arm_name(arm), Some(*name));
+    }
+    assert_eq!(morphology.arm_name(ARM_NAMES.len()), None);
+    assert_eq!(morphology.nearest_arm([1_000.0, 0.0, 0.0]), None);
+}
+
+#[test]
+fn arm_splines_follow_the_arms() {
+    let morphology = Morphology::milky_way(50_000.0);
+    for arm in 0..morphology.arm_count {
+        let spline = morphology.arm_spline(arm, 33);
+        assert_eq!(spline.len(), 33);
+        let radius = |p: &[f64; 3]| p[0].hypot(p[2]);
+        assert!((radius(&spline[0]) - morphology.arm_start_radius()).abs() < 1e-6);
+        assert!((radius(&spline[32]) - morphology.radius).abs() < 1e-6);
+        for pair in spline.windows(2) {
+            assert!(radius(&pair[1]) > radius(&pair[0]));
+        }
+        for point in &spline {
+            assert_eq!(point[1], 0.0);
+            let (nearest, distance) = morphology.nearest_arm(*point).unwrap();
+            assert_eq!(nearest, arm);
+            assert!(distance < 1e-6);
+        }
+    }
+    // Arms 0 and 2 leave the ends of the bar
+    let (sin, cos) = morphology.bar_angle.sin_cos();
+    let end = morphology.arm_start_radius();
+    let start = morphology.arm_spline(0, 2)[0];
+    assert!((start[0] - end * cos).abs() < 1e-6 && (start[2] - end * sin).abs() < 1e-6);
+    let start = morphology.arm_spline(2, 2)[0];
+    assert!((start[0] + end * cos).abs() < 1e-6 && (start[2] + end * sin).abs() < 1e-6);
+    assert!(morphology.arm_spline(0, 0).is_empty());
+}
+
+#[test]
+fn arms_run_through_overdense_structure() {
+    // The model is not read from MEI, so check it against what MEI
+    // generates: more disk stars lie on the modeled arms than would if the
+    // same stars were spun to random angles at their own radius and height
+    let api = GalaxyAPI::new(42);
+    let morphology = Morphology::milky_way(api.generator.galaxy.radius);
+    let structure = structure_data(&api, 20_000);
+    let disk: Vec<[f64; 3]> = structure
+        .stars
+        .iter()
+        .map(|star| star.position.map(f64::from))
+        .filter(|p| {
+            let r = p[0].hypot(p[2]);
+            r > 1.2 * morphology.arm_start_radius()
+                && r < 0.8 * morphology.radius
+                && p[1].abs() < morphology.thick_disk_height
+        })
+        .collect();
+    assert!(disk.len() > 1_000, "only {} disk stars", disk.len());
+
+    let on_arm = |p: [f64; 3]| {
+        morphology
+            .arm_distance(p)
+            .is_some_and(|d| d < morphology.arm_half_width)
+    };
+    let observed = disk.iter().filter(|p| on_arm(**p)).count() as f64;
+    let mut rng = DetRng::new(7);
+    let spins = 8;
+    let spun = disk
+        .iter()
+        .flat_map(|p| {
+            let r = p[0].hypot(p[2]);
+            (0..spins).map(move |_| (r, p[1]))
+        })
+        .filter(|&(r, height)| {
+            let angle = rng.range(0.0, TAU);
+            on_arm([r * angle.cos(), height, r * angle.sin()])
+        })
+        .count() as f64
+        / spins as f64;
+    assert!(
+        observed > 1.2 * spun,
+        "{} stars on the arms, {} expected without them",
+        observed,
+        spun
+    );
+}
+
+#[test]
+fn morphology_record_uses_degrees() {
+    let record = morphology_record(&Morphology::milky_way(50_000.0));
+    assert_eq!(record.get("arm_count"), Some(&Value::Int(4)));
+    assert_eq!(
+        record.get("arm_names"),
+        Some(&Value::from(
+            ARM_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        ))
+    );
+    assert!((float(&record, "arm_pitch") - 12.0).abs() < 1e-9);
+    assert!((float(&record, "bar_angle") - 25.0).abs() < 1e-9);
+    assert_eq!(float(&record, "bar_length"), 15_000.0);
+}
+
+#[test]
+fn metallicity_follows_gradients() {
+    for radius in [50_000.0, 25_000.0] {
+        let morphology = Morphology::milky_way(radius);
+        let scale = radius / 50_000.0;
+        let at = |r: f64, height: f64| morphology.metallicity([r * scale, height * scale, 0.0]);
+        assert!(at(26_000.0, 0.0).abs() < 1e-12);
+        assert!(at(15_000.0, 0.0) > 0.0 && at(40_000.0, 0.0) < 0.0);
+        assert!(at(26_000.0, 250.0) < at(26_000.0, 0.0));
+        // Flat inside the ends of the bar
+        assert_eq!(at(0.0, 0.0), at(7_000.0, 0.0));
+        assert_eq!(at(26_000.0, 1_500.0), THICK_DISK_METALLICITY);
+        assert_eq!(at(26_000.0, 10_000.0), HALO_METALLICITY);
+    }
+}
+
+fn float(record: &Record, key: &str) -> f64 {
+    match record.get(key) {
+        Some(Value::Float(v)) => *v,
+        other => panic!("{} is {:?}", key, other),
+    }
+}
+
+#[test]
+fn location_describes_a_populated_position() {
+    let mut api = GalaxyAPI::new(42);
+    let morphology = Morphology::milky_way(api.generator.galaxy.radius);
+    let structure = structure_data(&api, 200);
+    let position = structure.stars[0].position.map(f64::from);
+    let reference = [position[0] + 100.0, position[1], position[2]];
+
+    let record = location_record(&mut api, position, reference);
+    assert!((float(&record, "radius") - position[0].hypot(position[2])).abs() < 1e-9);
+    assert_eq!(float(&record, "height"), position[1]);
+    assert!((float(&record, "distance") - 100.0).abs() < 1e-9);
+    assert_eq!(
+        record.get("region"),
+        Some(&Value::from(morphology.region(position).name()))
+    );
+    assert_eq!(
+        float(&record, "metallicity"),
+        morphology.metallicity(position)
+    );
+    // The star itself is in the sample
+    assert!(float(&record, "stellar_density") > 0.0);
+
+    let core = location_record(&mut api, [0.0; 3], reference);
+    assert_eq!(core.get("region"), Some(&Value::from("Bulge")));
+    assert_eq!(core.get("arm"), Some(&Value::Int(-1)));
+    assert_eq!(core.get("arm_name"), Some(&Value::from("")));
+    // The core, seen along the reference's longitude 0
+    let longitude = float(&core, "longitude");
+    assert!(longitude.min(360.0 - longitude) < 1e-9, "{}", longitude);
+}
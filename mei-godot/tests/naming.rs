@@ -0,0 +1,68 @@
+//! Designation grammar.
+
+use mei_godot::naming::{
+    catalog_designation, cells_per_sector, parse_designation, DesignationQuery, NameStyle, SectorCoord,
+};
+
+const CELL_SIZE: f64 = 0.25;
+
+#[test]
+fn catalog_designations_round_trip() {
+    let style = NameStyle::default();
+    let position = [8_123.4, -3.2, -26_400.9];
+    let designation = catalog_designation(position, CELL_SIZE);
+    let parsed = parse_designation(
+        &format!("{} c II", designation),
+        &style,
+        cells_per_sector(CELL_SIZE),
+    )
+    .unwrap();
+    let sector = SectorCoord::containing(position);
+    assert!(matches!(
+        parsed.query,
+        DesignationQuery::Catalog { sector: s, .. } if s == sector
+    ));
+    assert_eq!(parsed.planet, Some(1));
+    assert_eq!(parsed.moon, Some(1));
+
+    // Sector codes are case-insensitive
+    let lower = parse_designation(&designation.to_lowercase(), &style, cells_per_sector(CELL_SIZE));
+    assert_eq!(lower.unwrap().query, parsed.query);
+}
+
+#[test]
+fn sector_codes_need_two_letters_per_axis() {
+    // Codes are two letters for X, two for Z, then the signed Y index, as
+    // catalog_designation writes them. Shorter forms like "KX-7" are not codes.
+    let style = NameStyle::default();
+    let error = parse_designation("Sector KX-7 1423 c", &style, cells_per_sector(CELL_SIZE)).unwrap_err();
+    assert_eq!(error.code, "bad_sector_code");
+    assert_eq!(error.offset, 7);
+
+    let parsed = parse_designation("Sector KXAB-7 1423 c", &style, cells_per_sector(CELL_SIZE)).unwrap();
+    assert!(matches!(parsed.query, DesignationQuery::Catalog { sector, number: 1423 } if sector.y == -7));
+    assert_eq!(parsed.planet, Some(1));
+}
+
+#[test]
+fn malformed_designations() {
+    let style = NameStyle::default();
+    let cells = cells_per_sector(CELL_SIZE);
+    for (text, code) in [
+        ("", "empty"),
+        ("Sector", "missing_sector_code"),
+        ("Sector NAMQ+3", "missing_number"),
+        ("Sector NAMQ+3 x", "bad_number"),
+        ("Sector NAMQ+3 1 ab", "bad_planet_letter"),
+        ("Sector NAMQ+3 1 b IIII", "bad_moon_numeral"),
+        ("Sector NAMQ+3 1 b I more", "trailing_input"),
+        ("1423", "unknown_format"),
+    ] {
+        assert_eq!(
+            parse_designation(text, &style, cells).unwrap_err().code,
+            code,
+            "{:?}",
+            text
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Tiling regions wider than one nearby query.
+
+use mei_godot::region::{RegionTiles, Tile};
+use mei_godot::rng::DetRng;
+
+const MAX_QUERY_RADIUS: f64 = 16.0;
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[test]
+fn small_regions_are_one_query() {
+    let center = [100.0, -3.0, 7.5];
+    let tiles: Vec<Tile> = RegionTiles::new(center, 10.0, MAX_QUERY_RADIUS).collect();
+    assert_eq!(tiles.len(), 1);
+    assert_eq!(tiles[0].center, center);
+    assert_eq!(tiles[0].query_radius, 10.0);
+    assert!(tiles[0].contains([1.0e6, 0.0, 0.0]));
+}
+
+#[test]
+fn tiles_cover_the_region_nearest_first() {
+    let center = [26_000.0, 12.0, -400.0];
+    let radius = 70.0;
+    let mut tiles = RegionTiles::new(center, radius, MAX_QUERY_RADIUS);
+    let mut visited = Vec::new();
+    while let Some(tile) = tiles.next() {
+        assert!(tile.query_radius <= MAX_QUERY_RADIUS);
+        assert!(tile.min_distance <= radius);
+        // Nothing later can be nearer than the horizon
+        assert!(tiles.horizon() >= tile.min_distance);
+        visited.push(tile);
+    }
+    assert_eq!(tiles.horizon(), f64::INFINITY);
+    assert!(visited
+        .windows(2)
+        .all(|pair| pair[0].min_distance <= pair[1].min_distance));
+
+    // Every point of the region is in exactly one tile, within its query
+    let mut rng = DetRng::new(7);
+    for _ in 0..2000 {
+        let offset = [0, 1, 2].map(|_| rng.range(-radius, radius));
+        let point = [0, 1, 2].map(|axis| center[axis] + offset[axis]);
+        if distance(point, center) > radius {
+            continue;
+        }
+        let owners: Vec<&Tile> = visited.iter().filter(|t| t.contains(point)).collect();
+        assert_eq!(owners.len(), 1, "{:?}", point);
+        assert!(distance(point, owners[0].center) <= owners[0].query_radius);
+        assert!(distance(point, center) >= owners[0].min_distance - 1e-9);
+    }
+}
+
+#[test]
+fn tiles_are_generated_lazily() {
+    // A galaxy-sized region only builds the rings it is asked for
+    let mut tiles = RegionTiles::new([0.0; 3], 1.0e12, MAX_QUERY_RADIUS);
+    let first: Vec<Tile> = tiles.by_ref().take(30).collect();
+    assert_eq!(first[0].min_distance, 0.0);
+    assert!(first.iter().all(|t| t.min_distance < 3.0 * MAX_QUERY_RADIUS));
+    assert!(tiles.horizon() < 3.0 * MAX_QUERY_RADIUS);
+}
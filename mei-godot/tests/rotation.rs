@@ -0,0 +1,122 @@
+//! Galactic rotation and peculiar motion.
+
+use mei_godot::morphology::{Morphology, Region};
+use mei_godot::rotation::{
+    peculiar_velocity, position_at_time, RotationCurve, CIRCULAR_SPEED_KM_S, KM_S_IN_LY_PER_MYR,
+};
+
+const SEED: u64 = 42;
+
+fn galaxy() -> (Morphology, RotationCurve) {
+    let morphology = Morphology::milky_way(50_000.0);
+    let curve = RotationCurve::milky_way(&morphology);
+    (morphology, curve)
+}
+
+fn angle(position: [f64; 3]) -> f64 {
+    position[2].atan2(position[0])
+}
+
+#[test]
+fn today_is_unchanged() {
+    let (morphology, curve) = galaxy();
+    for (id, position) in [
+        (1, [26_000.0, 20.0, -3_000.0]),
+        (2, [0.0, 0.0, 0.0]),
+        (3, [-1_200.0, 4_000.0, 800.0]),
+    ] {
+        let now = position_at_time(SEED, id, position, &morphology, &curve, 0.0);
+        for (a, b) in now.iter().zip(position) {
+            assert!((a - b).abs() < 1e-9, "star {} moved: {:?}", id, now);
+        }
+    }
+}
+
+#[test]
+fn positions_are_deterministic() {
+    let (morphology, curve) = galaxy();
+    let position = [26_000.0, 20.0, -3_000.0];
+    let at = |seed, id| position_at_time(seed, id, position, &morphology, &curve, 50.0);
+    assert_eq!(at(SEED, 7), at(SEED, 7));
+    assert_ne!(at(SEED, 7), at(SEED, 8));
+    assert_ne!(at(SEED, 7), at(SEED + 1, 7));
+}
+
+#[test]
+fn rotation_curve_is_flat_outside_the_core() {
+    let (_, curve) = galaxy();
+    let flat = CIRCULAR_SPEED_KM_S * KM_S_IN_LY_PER_MYR;
+    assert!((curve.speed(40_000.0) - flat).abs() / flat < 0.01);
+    assert!(curve.speed(1_000.0) < 0.25 * flat);
+    // The Sun goes around in a little over 200 million years
+    let period = curve.period(26_000.0);
+    assert!(period > 200.0 && period < 250.0, "period {}", period);
+}
+
+#[test]
+fn inner_stars_overtake_outer_ones() {
+    let (morphology, curve) = galaxy();
+    let turned = |r: f64| {
+        // Mean over many stars, so peculiar motion averages out
+        let total: f64 = (0..200)
+            .map(|id| {
+                let later = position_at_time(SEED, id, [r, 0.0, 0.0], &morphology, &curve, 20.0);
+                angle(later)
+            })
+            .sum();
+        total / 200.0
+    };
+    let inner = turned(10_000.0);
+    let outer = turned(30_000.0);
+    // Turning towards negative angles, so the arms trail
+    assert!(
+        inner < outer && outer < 0.0,
+        "inner {} outer {}",
+        inner,
+        outer
+    );
+}
+
+#[test]
+fn old_populations_move_faster() {
+    let mean_speed = |region| {
+        (0..500)
+            .map(|id| {
+                let [u, v, w] = peculiar_velocity(SEED, id, region);
+                (u * u + v * v + w * w).sqrt()
+            })
+            .sum::<f64>()
+            / 500.0
+    };
+    let arm = mean_speed(Region::Arm) / KM_S_IN_LY_PER_MYR;
+    let disk = mean_speed(Region::Disk) / KM_S_IN_LY_PER_MYR;
+    let halo = mean_speed(Region::Halo) / KM_S_IN_LY_PER_MYR;
+    assert!(arm > 5.0 && arm < 20.0, "arm {} km/s", arm);
+    assert!(arm < disk && disk < halo);
+}
+
+#[test]
+fn peculiar_motion_stays_bounded() {
+    let (morphology, curve) = galaxy();
+    let position = [26_000.0, 0.0, 0.0];
+    for id in 0..50 {
+        for time in [10.0, 100.0, 1_000.0, 10_000.0] {
+            let later = position_at_time(SEED, id, position, &morphology, &curve, time);
+            let r = later[0].hypot(later[2]);
+            assert!(
+                (r - 26_000.0).abs() < 5_000.0,
+                "star {} at {} Myr: r = {}",
+                id,
+                time,
+                r
+            );
+            assert!(
+                later[1].abs() < 2_000.0,
+                "star {} at {} Myr: y = {}",
+                id,
+                time,
+                later[1]
+            );
+        }
+    }
+}
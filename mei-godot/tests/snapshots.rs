@@ -0,0 +1,245 @@
+//! Golden snapshots of what the bindings hand to GDScript.
+//!
+//! Each test generates data for a fixed seed, converts it with the same
+//! pure functions `MeiGalaxy` uses and compares the text form against
+//! `tests/snapshots/<name>.snap`. A diff means players would see different
+//! stars or systems, whether the change came from MEI or from the bindings.
+//!
+//! After an intended change, regenerate the goldens and review the diff:
+//!
+//! ```text
+//! MEI_BLESS=1 cargo test --test snapshots
+//! git diff tests/snapshots
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use mei::api::galaxy_api::GalaxyAPI;
+use mei_godot::naming::NameStyle;
+use mei_godot::records::{
+    generate_system, nearby_data, structure_data, summary_record, system_record, Record, Value,
+};
+use mei_godot::rng::hash_bytes;
+use mei_godot::structure_cache::{encode, StructureData};
+
+const SEEDS: [u64; 3] = [0, 42, 1337];
+
+/// Stars sampled for the structure snapshot.
+const STRUCTURE_STARS: i64 = 2000;
+
+/// Stars written out in full; the rest are covered by the checksum.
+const LISTED_STARS: usize = 20;
+
+/// Radius in light-years of the nearby-stars query.
+const NEARBY_RADIUS: f64 = 15.0;
+
+/// Systems snapshotted in full per seed.
+const SYSTEMS_PER_SEED: usize = 3;
+
+/// Compares `actual` with the named golden file, or rewrites it when
+/// `MEI_BLESS` is set.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.snap", name));
+
+    if std::env::var_os("MEI_BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run `MEI_BLESS=1 cargo test --test snapshots` to create it",
+            path.display()
+        )
+    });
+    if expected != actual {
+        // Walk the longer of the two so added or removed trailing lines show up
+        let expected_lines: Vec<&str> = expected.lines().collect();
+        let actual_lines: Vec<&str> = actual.lines().collect();
+        let mut diff: Vec<String> = (0..expected_lines.len().max(actual_lines.len()))
+            .filter_map(|i| {
+                let (e, a) = (expected_lines.get(i), actual_lines.get(i));
+                if e == a {
+                    return None;
+                }
+                let side = |sign: &str, line: Option<&&str>| match line {
+                    Some(line) => format!("  {} {}", sign, line),
+                    None => format!("  {} (no line)", sign),
+                };
+                Some(format!("line {}:\n{}\n{}", i + 1, side("-", e), side("+", a)))
+            })
+            .take(20)
+            .collect();
+        if diff.is_empty() {
+            diff.push("  (only line endings or the final newline differ)".to_string());
+        }
+        panic!(
+            "snapshot {} changed ({} -> {} lines), first differences:\n{}\n\
+             if this is intended, rerun with MEI_BLESS=1 and review the diff",
+            name,
+            expected_lines.len(),
+            actual_lines.len(),
+            diff.join("\n")
+        );
+    }
+}
+
+/// Summary of structure data: totals, a checksum of every star and the
+/// first few stars in full.
+fn structure_record(data: &StructureData) -> Record {
+    let mut record = Record::new();
+    record.set("count", data.stars.len() as i64);
+    record.set("estimated_total_stars", data.estimated_total);
+    record.set("type_names", data.type_names.clone());
+    record.set("checksum", format!("{:016x}", hash_bytes(&encode(data, 0))));
+    record.set(
+        "stars",
+        data.stars
+            .iter()
+            .take(LISTED_STARS)
+            .map(|star| {
+                let mut record = Record::new();
+                record.set("id", star.id);
+                record.set(
+                    "star_type",
+                    data.type_names[star.star_type as usize].clone(),
+                );
+                record.set("x", star.position[0] as f64);
+                record.set("y", star.position[1] as f64);
+                record.set("z", star.position[2] as f64);
+                record.set("luminosity", star.luminosity as f64);
+                record.set("temperature", star.temperature as f64);
+                record.set("mass", star.mass as f64);
+                record.set("radius", star.radius as f64);
+                record.set("absolute_magnitude", star.absolute_magnitude as f64);
+                record.set(
+                    "spectral_class",
+                    data.spectral_class_names[star.spectral_class as usize].clone(),
+                );
+                record.set("color", Value::Color(star.color));
+                record
+            })
+            .collect::<Vec<_>>(),
+    );
+    record
+}
+
+/// Stars around the first structure star, so the query always lands in a
+/// populated region whatever the galaxy's shape.
+fn nearby(api: &mut GalaxyAPI) -> StructureData {
+    let structure = structure_data(api, 1);
+    let center = structure
+        .stars
+        .first()
+        .map_or([0.0; 3], |star| star.position.map(f64::from));
+    nearby_data(api, center, NEARBY_RADIUS, 10000, [0.0; 3])
+}
+
+#[test]
+fn structure() {
+    for seed in SEEDS {
+        let api = GalaxyAPI::new(seed);
+        let data = structure_data(&api, STRUCTURE_STARS);
+        assert_snapshot(
+            &format!("structure_seed{}", seed),
+            &structure_record(&data).to_snapshot(),
+        );
+    }
+}
+
+#[test]
+fn nearby_stars() {
+    for seed in SEEDS {
+        let mut api = GalaxyAPI::new(seed);
+        let data = nearby(&mut api);
+        assert_snapshot(
+            &format!("nearby_seed{}", seed),
+            &structure_record(&data).to_snapshot(),
+        );
+    }
+}
+
+#[test]
+fn star_systems() {
+    let style = NameStyle::default();
+    for seed in SEEDS {
+        let mut api = GalaxyAPI::new(seed);
+        let cell_size = api.generator.config.cell_size;
+        let data = nearby(&mut api);
+        assert!(
+            data.stars.len() >= SYSTEMS_PER_SEED,
+            "seed {} has only {} nearby stars",
+            seed,
+            data.stars.len()
+        );
+
+        for star in data.stars.iter().take(SYSTEMS_PER_SEED) {
+            let id = star.id.to_string();
+            let system = generate_system(&api, &id).unwrap();
+            assert_snapshot(
+                &format!("system_seed{}_{}", seed, id),
+                &system_record(seed, &id, &style, &system, cell_size).to_snapshot(),
+            );
+            assert_snapshot(
+                &format!("summary_seed{}_{}", seed, id),
+                &summary_record(seed, &id, &style, &system, cell_size).to_snapshot(),
+            );
+        }
+    }
+}
+
+#[test]
+fn systems_are_deterministic() {
+    let style = NameStyle::default();
+    let mut api = GalaxyAPI::new(SEEDS[1]);
+    let cell_size = api.generator.config.cell_size;
+    let id = nearby(&mut api).stars[0].id.to_string();
+
+    let first = system_record(
+        SEEDS[1],
+        &id,
+        &style,
+        &generate_system(&api, &id).unwrap(),
+        cell_size,
+    );
+    let fresh = GalaxyAPI::new(SEEDS[1]);
+    let second = system_record(
+        SEEDS[1],
+        &id,
+        &style,
+        &generate_system(&fresh, &id).unwrap(),
+        cell_size,
+    );
+    assert_eq!(first, second);
+}
+
+#[test]
+fn snapshot_format() {
+    let mut planet = Record::new();
+    planet.set("planet_type", "Terrestrial");
+    planet.set("mass", 1.5);
+    planet.set("moons", Vec::<Record>::new());
+
+    let mut record = Record::new();
+    record.set("star_id", "42");
+    record.set("count", 0.0);
+    record.set("ids", vec![1i64, 2]);
+    record.set("planets", vec![planet]);
+    record.set("position", Record::new());
+    record.set("count", 3i64);
+
+    assert_eq!(
+        record.to_snapshot(),
+        "star_id = \"42\"\n\
+         count = 3\n\
+         ids = [1, 2]\n\
+         planets[0].planet_type = \"Terrestrial\"\n\
+         planets[0].mass = 1.500000000e0\n\
+         planets[0].moons = []\n\
+         position = {}\n"
+    );
+}
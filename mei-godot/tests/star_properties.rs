@@ -0,0 +1,120 @@
+//! Star values must not depend on which query returned the star.
+
+use std::collections::HashMap;
+
+use mei::api::galaxy_api::GalaxyAPI;
+use mei_godot::records::{nearby_data, structure_data};
+use mei_godot::stellar::{
+    absolute_magnitude, blackbody_color, stellar_radius, SpectralClass, SOLAR_TEMPERATURE,
+};
+use mei_godot::structure_cache::{StructureData, StructureStar};
+
+const SEEDS: [u64; 3] = [0, 42, 1337];
+
+const STRUCTURE_STARS: i64 = 2000;
+
+const NEARBY_RADIUS: f64 = 15.0;
+
+/// Stars by ID, with their type and spectral class resolved to names.
+fn by_id(data: &StructureData) -> HashMap<i64, (StructureStar, &str, &str)> {
+    data.stars
+        .iter()
+        .map(|star| {
+            (
+                star.id,
+                (
+                    *star,
+                    data.type_names[star.star_type as usize].as_str(),
+                    data.spectral_class_names[star.spectral_class as usize].as_str(),
+                ),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn structure_and_nearby_agree() {
+    for seed in SEEDS {
+        let mut api = GalaxyAPI::new(seed);
+        let structure = structure_data(&api, STRUCTURE_STARS);
+        let center = structure.stars[0].position.map(f64::from);
+        let nearby = nearby_data(&mut api, center, NEARBY_RADIUS, 10000, [0.0; 3]);
+
+        let structure_stars = by_id(&structure);
+        let mut shared = 0;
+        for (id, (star, star_type, spectral_class)) in by_id(&nearby) {
+            let Some((other, other_type, other_class)) = structure_stars.get(&id) else {
+                continue;
+            };
+            shared += 1;
+            // Table indices depend on the order types were first seen
+            let star = StructureStar {
+                star_type: 0,
+                spectral_class: 0,
+                ..star
+            };
+            let other = StructureStar {
+                star_type: 0,
+                spectral_class: 0,
+                ..*other
+            };
+            assert_eq!(star, other, "seed {} star {}", seed, id);
+            assert_eq!(star_type, *other_type, "seed {} star {} type", seed, id);
+            assert_eq!(
+                spectral_class, *other_class,
+                "seed {} star {} spectral class",
+                seed, id
+            );
+        }
+        assert!(shared > 0, "seed {}: no star in both queries", seed);
+    }
+}
+
+/// Spectral class of a star, with its radius derived as `StarProperties` does.
+fn classify(star_type: &str, temperature: f64, luminosity: f64, mass: f64) -> String {
+    let radius = stellar_radius(star_type, luminosity, temperature, mass);
+    SpectralClass::classify(star_type, temperature, luminosity, mass, radius)
+        .map_or_else(String::new, |class| class.to_string())
+}
+
+#[test]
+fn solar_values() {
+    assert!((stellar_radius("MainSequenceG", 1.0, SOLAR_TEMPERATURE, 1.0) - 1.0).abs() < 1e-9);
+    assert!((absolute_magnitude(1.0) - 4.74).abs() < 1e-9);
+    assert!((absolute_magnitude(100.0) - (4.74 - 5.0)).abs() < 1e-9);
+    assert_eq!(absolute_magnitude(0.0), f64::INFINITY);
+    assert_eq!(
+        classify("MainSequenceG", SOLAR_TEMPERATURE, 1.0, 1.0),
+        "G2V"
+    );
+}
+
+#[test]
+fn spectral_classes() {
+    assert_eq!(classify("MainSequenceM", 3060.0, 0.005, 0.15), "M5V");
+    assert_eq!(classify("MainSequenceB", 10_000.0, 40.0, 2.5), "B9.5V");
+    assert_eq!(classify("RedGiant", 4400.0, 60.0, 1.2), "K5III");
+    assert_eq!(classify("RedSupergiant", 3600.0, 1.0e5, 15.0), "M1.5Ia");
+    assert_eq!(classify("WhiteDwarf", 25_200.0, 0.01, 0.6), "DA2");
+    assert_eq!(classify("BrownDwarf", 1700.0, 1.0e-4, 0.05), "L5");
+    assert_eq!(classify("NeutronStar", 600_000.0, 0.1, 1.4), "");
+    assert_eq!(classify("BlackHole", 0.0, 0.0, 10.0), "");
+    assert!(stellar_radius("BlackHole", 0.0, 0.0, 10.0) < 1e-4);
+
+    let giant = SpectralClass::classify("RedGiant", 4400.0, 60.0, 1.2, 13.3).unwrap();
+    assert_eq!(
+        (giant.class, giant.subclass, giant.luminosity_class),
+        ("K", 5.0, "III")
+    );
+}
+
+#[test]
+fn blackbody_colors() {
+    let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 0.01);
+    // The Sun is a faintly warm white
+    assert!(close(blackbody_color(5772.0), [1.0, 0.945, 0.917]));
+    // Cool stars are orange-red, hot stars blue-white
+    assert!(close(blackbody_color(3000.0), [1.0, 0.725, 0.430]));
+    assert!(close(blackbody_color(20_000.0), [0.670, 0.759, 1.0]));
+    assert_eq!(blackbody_color(0.0), [0.0; 3]);
+}
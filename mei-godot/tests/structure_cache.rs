@@ -0,0 +1,104 @@
+//! Encoding and decoding structure cache files.
+
+use mei_godot::rng::hash_bytes;
+use mei_godot::structure_cache::{
+    decode, encode, CacheError, StructureData, StructureStar, FORMAT_VERSION,
+};
+
+const KEY: u64 = 0x5354_5255_4354_5552;
+
+fn sample() -> StructureData {
+    let mut data = StructureData {
+        estimated_total: 123_456_789,
+        ..StructureData::default()
+    };
+    for i in 0..3 {
+        let star_type = data.type_index(["MainSequence", "WhiteDwarf"][i % 2]);
+        let spectral_class = data.spectral_class_index(["G2V", "K5V", ""][i]);
+        data.stars.push(StructureStar {
+            position: [i as f32 * 10.5, -2.25, 26_000.0 + i as f32],
+            id: 1_000_000_007 * i as i64,
+            luminosity: 0.5 + i as f32,
+            temperature: 5772.0 - 300.0 * i as f32,
+            mass: 1.0,
+            radius: 0.9,
+            absolute_magnitude: 4.83,
+            color: [1.0, 0.9, 0.8],
+            star_type,
+            spectral_class,
+        });
+    }
+    data
+}
+
+/// Replaces the trailing checksum so edits reach the checks after it.
+fn reseal(bytes: &mut [u8]) {
+    let body = bytes.len() - 8;
+    let checksum = hash_bytes(&bytes[..body]);
+    bytes[body..].copy_from_slice(&checksum.to_le_bytes());
+}
+
+#[test]
+fn round_trip() {
+    let data = sample();
+    assert_eq!(decode(&encode(&data, KEY), KEY), Ok(data));
+
+    let empty = StructureData::default();
+    assert_eq!(decode(&encode(&empty, KEY), KEY), Ok(empty));
+}
+
+#[test]
+fn flipped_byte_fails_the_checksum() {
+    let mut bytes = encode(&sample(), KEY);
+    // Inside the first star record, past the header the early checks read
+    let at = bytes.len() / 2;
+    bytes[at] ^= 0x10;
+    assert_eq!(decode(&bytes, KEY), Err(CacheError::ChecksumMismatch));
+}
+
+#[test]
+fn other_key_is_stale() {
+    let bytes = encode(&sample(), KEY);
+    assert_eq!(decode(&bytes, KEY ^ 1), Err(CacheError::Stale));
+}
+
+#[test]
+fn truncated_files_are_rejected() {
+    let bytes = encode(&sample(), KEY);
+    assert_eq!(decode(&bytes[..10], KEY), Err(CacheError::Truncated));
+    assert_eq!(decode(&[], KEY), Err(CacheError::Truncated));
+    // Cut after the header, the checksum no longer matches the bytes before it
+    assert!(decode(&bytes[..bytes.len() - 5], KEY).is_err());
+}
+
+#[test]
+fn other_version_is_unsupported() {
+    let mut bytes = encode(&sample(), KEY);
+    bytes[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        decode(&bytes, KEY),
+        Err(CacheError::UnsupportedVersion(FORMAT_VERSION + 1))
+    );
+}
+
+#[test]
+fn out_of_range_indices_are_malformed() {
+    // The last record ends with its type and spectral class indices
+    let mut bytes = encode(&sample(), KEY);
+    let type_at = bytes.len() - 8 - 4;
+    bytes[type_at..type_at + 2].copy_from_slice(&2u16.to_le_bytes());
+    reseal(&mut bytes);
+    assert_eq!(
+        decode(&bytes, KEY),
+        Err(CacheError::Malformed("star type index"))
+    );
+
+    let mut bytes = encode(&sample(), KEY);
+    let class_at = bytes.len() - 8 - 2;
+    bytes[class_at..class_at + 2].copy_from_slice(&3u16.to_le_bytes());
+    reseal(&mut bytes);
+    assert_eq!(
+        decode(&bytes, KEY),
+        Err(CacheError::Malformed("spectral class index"))
+    );
+}
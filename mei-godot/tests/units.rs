@@ -0,0 +1,148 @@
+//! Unit conversions and coordinate frames.
+
+use std::f64::consts::FRAC_PI_2;
+
+use mei_godot::morphology::Morphology;
+use mei_godot::orbit::OrbitalElements;
+use mei_godot::rotation::{position_at_time, RotationCurve};
+use mei_godot::units::{
+    convert, ecliptic_to_galactic, ecliptic_to_local, galactic_to_ecliptic, galactic_to_spherical,
+    spherical_to_galactic, Spherical, AU_PER_LY, LY_PER_PC,
+};
+
+fn close(a: f64, b: f64, relative: f64) -> bool {
+    (a - b).abs() <= relative * b.abs().max(1e-300)
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(convert(1.0, "au", "km"), Ok(149_597_870.7));
+    assert!(close(LY_PER_PC, 3.261_563_777, 1e-9));
+    assert!(close(AU_PER_LY, 63_241.077_084, 1e-9));
+    assert!(close(convert(1.0, "pc", "ly").unwrap(), LY_PER_PC, 1e-15));
+    assert!(close(
+        convert(1.0, "solar_mass", "earth_mass").unwrap(),
+        332_946.0,
+        1e-6
+    ));
+    assert!(close(
+        convert(1.0, "jupiter_mass", "earth_mass").unwrap(),
+        317.83,
+        1e-4
+    ));
+    assert!(close(
+        convert(1.0, "jupiter_radius", "earth_radius").unwrap(),
+        11.209,
+        1e-4
+    ));
+    assert!(close(
+        convert(1.0, "solar_radius", "au").unwrap(),
+        0.004_650_5,
+        1e-4
+    ));
+    assert!(close(
+        convert(1.0, "solar_mass", "kg").unwrap(),
+        1.988_4e30,
+        1e-4
+    ));
+    // Round trips and case
+    let there = convert(12.5, "LY", "km").unwrap();
+    assert!(close(convert(there, "km", "ly").unwrap(), 12.5, 1e-15));
+}
+
+#[test]
+fn conversion_errors() {
+    assert_eq!(
+        convert(1.0, "furlong", "km"),
+        Err("Unknown unit \"furlong\"".to_string())
+    );
+    assert!(convert(1.0, "kg", "km").is_err());
+}
+
+fn near(a: [f64; 3], b: [f64; 3], tolerance: f64) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() <= tolerance)
+}
+
+#[test]
+fn spherical_coordinates() {
+    let observer = [26_000.0, 20.0, 0.0];
+    let core = galactic_to_spherical([0.0, 20.0, 0.0], observer);
+    assert!(core.longitude.abs() < 1e-9 && core.latitude.abs() < 1e-9);
+    assert!((core.distance - 26_000.0).abs() < 1e-9);
+
+    let above = galactic_to_spherical([26_000.0, 120.0, 0.0], observer);
+    assert!((above.latitude - 90.0).abs() < 1e-9);
+
+    for spherical in [
+        Spherical {
+            longitude: 45.0,
+            latitude: -12.0,
+            distance: 300.0,
+        },
+        Spherical {
+            longitude: 300.0,
+            latitude: 80.0,
+            distance: 5.0,
+        },
+    ] {
+        let position = spherical_to_galactic(spherical, observer);
+        let back = galactic_to_spherical(position, observer);
+        assert!((back.longitude - spherical.longitude).abs() < 1e-9);
+        assert!((back.latitude - spherical.latitude).abs() < 1e-9);
+        assert!((back.distance - spherical.distance).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn rotation_heads_towards_longitude_270() {
+    let morphology = Morphology::milky_way(50_000.0);
+    let curve = RotationCurve::milky_way(&morphology);
+    let observer = [26_000.0, 0.0, 0.0];
+    // Mean over many stars, so peculiar motion averages out
+    let mut ahead = [0.0; 3];
+    for id in 0..200 {
+        let later = position_at_time(42, id, observer, &morphology, &curve, 1.0);
+        for axis in 0..3 {
+            ahead[axis] += later[axis] / 200.0;
+        }
+    }
+    let heading = galactic_to_spherical(ahead, observer);
+    assert!(
+        (heading.longitude - 270.0).abs() < 5.0,
+        "{}",
+        heading.longitude
+    );
+}
+
+#[test]
+fn ecliptic_frame() {
+    let offset = [1.0 / AU_PER_LY, 2.0 / AU_PER_LY, -3.0 / AU_PER_LY];
+    let ecliptic = galactic_to_ecliptic(offset);
+    assert!(near(ecliptic, [1.0, 3.0, 2.0], 1e-12));
+    assert!(near(ecliptic_to_galactic(ecliptic), offset, 1e-18));
+
+    // A rotation keeps handedness: the axes' determinant is +1, not -1
+    let [a, b, c] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        .map(|axis| galactic_to_ecliptic(axis.map(|v| v / AU_PER_LY)));
+    let determinant = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+        + a[2] * (b[0] * c[1] - b[1] * c[0]);
+    assert!((determinant - 1.0).abs() < 1e-12, "{}", determinant);
+
+    // Orbits are drawn in the same frame: a prograde orbit a quarter turn
+    // past periapsis is at +Y in the ecliptic
+    let orbit = OrbitalElements {
+        semi_major_axis: 1.0,
+        eccentricity: 0.0,
+        inclination: 0.0,
+        longitude_of_ascending_node: 0.0,
+        argument_of_periapsis: 0.0,
+    };
+    let quarter = orbit.position_at(FRAC_PI_2).map(|v| v / AU_PER_LY);
+    assert!(near(galactic_to_ecliptic(quarter), [0.0, 1.0, 0.0], 1e-12));
+    // and system positions turn into the frame the orbit is drawn in
+    assert!(near(
+        ecliptic_to_local([0.0, 1.0, 0.0]),
+        orbit.position_at(FRAC_PI_2),
+        1e-12
+    ));
+}
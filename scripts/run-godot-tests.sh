@@ -0,0 +1,21 @@
+#!/bin/bash
+# Runs the headless Godot integration tests against a debug build of the
+# extension. Needs no GPU or display: --headless uses the dummy renderer.
+#
+# Usage: scripts/run-godot-tests.sh    (set GODOT to pick the Godot binary)
+set -euo pipefail
+
+GODOT="${GODOT:-godot}"
+cd "$(dirname "$0")/.."
+
+echo "Building mei-godot (debug)"
+cargo build
+
+# A fresh checkout has no .godot/ folder, so import once to register the extension
+if [ ! -f .godot/extension_list.cfg ]; then
+    echo "Importing project"
+    "$GODOT" --headless --path . --import
+fi
+
+echo "Running MeiGalaxy integration tests"
+"$GODOT" --headless --path . --script res://tests/godot/test_mei_galaxy.gd
@@ -11,7 +11,7 @@ signal star_selected(star_data: Dictionary, system_data: Dictionary)
 @export var max_stars: int = 500000
 
 var mei_galaxy: MeiGalaxy
-var current_stars: Dictionary = {}  # Packed arrays: positions, ids, luminosities, temperatures, masses, star_types
+var current_stars: Dictionary = {}  # Packed arrays: positions, ids, luminosities, temperatures, masses, star_type_codes, star_type_names
 var current_nearby_stars: Dictionary = {}
 var last_query_position: Vector3 = Vector3.ZERO
 var galaxy_center: Vector3 = Vector3.ZERO
@@ -22,8 +22,15 @@ var _pick_grid: Dictionary = {}  # Vector2i -> Array of star indices
 var _pick_grid_valid: bool = false
 var _pick_grid_camera: Camera3D = null
 
-# Galaxy rotation
+# Galaxy rotation: stars follow MeiGalaxy's rotation curve and peculiar motions
 var rotation_enabled: bool = false
+var rotation_speed: float = 2.0  # Millions of years per second
+var galaxy_time_myr: float = 0.0  # Epoch currently shown, 0 is today
+const ROTATION_REFRESH_INTERVAL: float = 0.5  # Seconds between position updates
+var _rotation_refresh_timer: float = 0.0
+
+# Spiral arm name labels on the galaxy map
+var _arm_labels: Array[Label3D] = []
 
 func _format_number(n: int) -> String:
     if n >= 1_000_000_000:
@@ -35,9 +42,6 @@ func _format_number(n: int) -> String:
     else:
         return str(n)
 
-var rotation_speed: float = 0.03
-var rotation_angle: float = 0.0
-
 func _ready():
     _setup_multimesh_materials()
 
@@ -84,6 +88,7 @@ func initialize(galaxy_seed: int):
     print("Galaxy initialized - Estimated total stars: ", _format_number(estimated_total))
     
     render_stars(current_stars)
+    _update_arm_labels()
     
     # Initial nearby stars query
     update_nearby_stars(galaxy_center, true)
@@ -108,13 +113,36 @@ func reinitialize(galaxy_seed: int):
     current_nearby_stars = {}
     last_query_position = Vector3.ZERO
     galaxy_center = Vector3.ZERO
+    galaxy_time_myr = 0.0
     
     var estimated_total = current_stars.get("estimated_total_stars", 0)
     print("Galaxy reinitialized - Estimated total stars: ", _format_number(estimated_total))
     
     render_stars(current_stars)
+    _update_arm_labels()
     update_nearby_stars(galaxy_center, true)
 
+func _update_arm_labels():
+    """Label each spiral arm halfway along its length."""
+    for label in _arm_labels:
+        label.queue_free()
+    _arm_labels.clear()
+    if mei_galaxy == null or not is_instance_valid(mei_galaxy):
+        return
+    var morphology = mei_galaxy.get_morphology()
+    for arm in morphology.get("arm_count", 0):
+        var spline = mei_galaxy.get_arm_spline(arm, 16)
+        if spline.is_empty():
+            continue
+        var label = Label3D.new()
+        label.text = morphology["arm_names"][arm] + " Arm"
+        label.position = spline[spline.size() / 2] * galaxy_scale
+        label.billboard = BaseMaterial3D.BILLBOARD_ENABLED
+        label.pixel_size = morphology["radius"] * galaxy_scale * 0.0005
+        label.modulate = Color(0.7, 0.8, 1.0, 0.6)
+        add_child(label)
+        _arm_labels.append(label)
+
 func update_nearby_stars(camera_pos_visual: Vector3, force: bool = false):
     if mei_galaxy == null or nearby_stars == null or not is_instance_valid(mei_galaxy):
         return
@@ -129,6 +157,7 @@ func update_nearby_stars(camera_pos_visual: Vector3, force: bool = false):
     if force or distance_moved >= nearby_refresh_distance:
         last_query_position = current_pos_ly
         current_nearby_stars = mei_galaxy.get_nearby_stars(query_x, query_y, query_z, nearby_radius)
+        _apply_epoch(current_nearby_stars)
         var count = current_nearby_stars.get("count", 0)
         print("Galaxy: Updated nearby stars, got ", count, " stars at position ", current_pos_ly)
         render_nearby_stars(current_nearby_stars)
@@ -188,18 +217,45 @@ func invalidate_pick_grid():
     _pick_grid_valid = false
 
 func update_rotation(delta: float):
-    if not rotation_enabled:
+    if not rotation_enabled or mei_galaxy == null or not is_instance_valid(mei_galaxy):
         return
     
-    rotation_angle += rotation_speed * delta
-    var rotation_transform = Transform3D()
-    rotation_transform = rotation_transform.rotated(Vector3.UP, rotation_angle)
-    rotation_transform.origin = galaxy_center
+    galaxy_time_myr += rotation_speed * delta
+    _rotation_refresh_timer += delta
+    if _rotation_refresh_timer < ROTATION_REFRESH_INTERVAL:
+        return
+    _rotation_refresh_timer = 0.0
     
-    if star_points != null:
-        star_points.transform = rotation_transform
-    if nearby_stars != null:
-        nearby_stars.transform = rotation_transform
+    _apply_epoch(current_stars)
+    render_stars(current_stars)
+    _apply_epoch(current_nearby_stars)
+    render_nearby_stars(current_nearby_stars)
+    _pick_grid_valid = false
+
+func _apply_epoch(star_data: Dictionary):
+    """Replace today's star positions with those at galaxy_time_myr.
+    Positions are always derived from today's, so errors don't accumulate."""
+    if galaxy_time_myr == 0.0 or not star_data.has("ids"):
+        return
+    star_data["positions"] = mei_galaxy.get_star_positions_at_time(star_data["ids"], galaxy_time_myr)
+
+func get_nearby_stars_around(star_position: Dictionary) -> Dictionary:
+    """Nearby stars relative to star_position.
+    Offsets from a nearby origin keep full precision far from the galactic core."""
+    if mei_galaxy == null or not is_instance_valid(mei_galaxy):
+        return {}
+    var here: MeiCoordinate = mei_galaxy.get_coordinate(
+        star_position.get("x", 0.0), star_position.get("y", 0.0), star_position.get("z", 0.0))
+    if here == null:
+        return {}
+    return mei_galaxy.get_nearby_stars_relative(here, Vector3.ZERO, nearby_radius)
+
+func describe_location(camera_pos_visual: Vector3, reference: Vector3 = Vector3.ZERO) -> Dictionary:
+    """Region, nearest arm, metallicity and stellar density at a visual position.
+    Longitude and latitude are seen from reference, in light years."""
+    if mei_galaxy == null or not is_instance_valid(mei_galaxy):
+        return {}
+    return mei_galaxy.describe_location(camera_pos_visual / galaxy_scale, reference)
 
 func get_star_system(star_id: String) -> Dictionary:
     if mei_galaxy == null or not is_instance_valid(mei_galaxy):
@@ -219,14 +275,12 @@ func find_star_at_screen_pos(screen_pos: Vector2, camera: Camera3D, _click_thres
     var galactic_luminosities: PackedFloat32Array = current_stars.get("luminosities", PackedFloat32Array())
     var galactic_temperatures: PackedFloat32Array = current_stars.get("temperatures", PackedFloat32Array())
     var galactic_masses: PackedFloat32Array = current_stars.get("masses", PackedFloat32Array())
-    var galactic_star_types: PackedStringArray = current_stars.get("star_types", PackedStringArray())
     
     var nearby_positions: PackedVector3Array = current_nearby_stars.get("positions", PackedVector3Array())
     var nearby_ids: PackedInt64Array = current_nearby_stars.get("ids", PackedInt64Array())
     var nearby_luminosities: PackedFloat32Array = current_nearby_stars.get("luminosities", PackedFloat32Array())
     var nearby_temperatures: PackedFloat32Array = current_nearby_stars.get("temperatures", PackedFloat32Array())
     var nearby_masses: PackedFloat32Array = current_nearby_stars.get("masses", PackedFloat32Array())
-    var nearby_star_types: PackedStringArray = current_nearby_stars.get("star_types", PackedStringArray())
     
     if galactic_positions.is_empty() and nearby_positions.is_empty():
         return {}
@@ -297,7 +351,7 @@ func find_star_at_screen_pos(screen_pos: Vector2, camera: Camera3D, _click_thres
         return {
             "id": str(galactic_ids[best_idx]),
             "position": {"x": galactic_positions[best_idx].x, "y": galactic_positions[best_idx].y, "z": galactic_positions[best_idx].z},
-            "star_type": galactic_star_types[best_idx],
+            "star_type": MeiUtils.get_star_type(current_stars, best_idx),
             "luminosity": galactic_luminosities[best_idx],
             "temperature": galactic_temperatures[best_idx],
             "mass": galactic_masses[best_idx]
@@ -306,7 +360,7 @@ func find_star_at_screen_pos(screen_pos: Vector2, camera: Camera3D, _click_thres
         return {
             "id": str(nearby_ids[best_idx]),
             "position": {"x": nearby_positions[best_idx].x, "y": nearby_positions[best_idx].y, "z": nearby_positions[best_idx].z},
-            "star_type": nearby_star_types[best_idx],
+            "star_type": MeiUtils.get_star_type(current_nearby_stars, best_idx),
             "luminosity": nearby_luminosities[best_idx],
             "temperature": nearby_temperatures[best_idx],
             "mass": nearby_masses[best_idx]
@@ -321,7 +375,6 @@ func find_star_along_ray(ray_origin: Vector3, ray_dir: Vector3, threshold: float
     var luminosities: PackedFloat32Array = current_stars.get("luminosities", PackedFloat32Array())
     var temperatures: PackedFloat32Array = current_stars.get("temperatures", PackedFloat32Array())
     var masses: PackedFloat32Array = current_stars.get("masses", PackedFloat32Array())
-    var star_types: PackedStringArray = current_stars.get("star_types", PackedStringArray())
     
     var closest_idx = -1
     var best_score = INF
@@ -360,7 +413,7 @@ func find_star_along_ray(ray_origin: Vector3, ray_dir: Vector3, threshold: float
     return {
         "id": str(ids[closest_idx]),
         "position": {"x": positions[closest_idx].x, "y": positions[closest_idx].y, "z": positions[closest_idx].z},
-        "star_type": star_types[closest_idx],
+        "star_type": MeiUtils.get_star_type(current_stars, closest_idx),
         "luminosity": luminosities[closest_idx],
         "temperature": temperatures[closest_idx],
         "mass": masses[closest_idx]
@@ -369,7 +422,7 @@ func find_star_along_ray(ray_origin: Vector3, ray_dir: Vector3, threshold: float
 func render_stars(star_data: Dictionary):
     var positions: PackedVector3Array = star_data.get("positions", PackedVector3Array())
     var luminosities: PackedFloat32Array = star_data.get("luminosities", PackedFloat32Array())
-    var temperatures: PackedFloat32Array = star_data.get("temperatures", PackedFloat32Array())
+    var colors: PackedColorArray = star_data.get("colors", PackedColorArray())
     
     var star_count = positions.size()
     if star_count == 0:
@@ -390,7 +443,7 @@ func render_stars(star_data: Dictionary):
         star_transform = star_transform.scaled(Vector3(size, size, size))
         
         multimesh.set_instance_transform(i, star_transform)
-        multimesh.set_instance_color(i, temperature_to_color(temperatures[i]))
+        multimesh.set_instance_color(i, colors[i])
 
 func render_nearby_stars(star_data: Dictionary):
     if nearby_stars == null or nearby_stars.multimesh == null:
@@ -398,7 +451,7 @@ func render_nearby_stars(star_data: Dictionary):
     
     var positions: PackedVector3Array = star_data.get("positions", PackedVector3Array())
     var luminosities: PackedFloat32Array = star_data.get("luminosities", PackedFloat32Array())
-    var temperatures: PackedFloat32Array = star_data.get("temperatures", PackedFloat32Array())
+    var colors: PackedColorArray = star_data.get("colors", PackedColorArray())
     
     var star_count = positions.size()
     if star_count == 0:
@@ -421,7 +474,4 @@ func render_nearby_stars(star_data: Dictionary):
         star_transform.origin = star_position
         
         multimesh.set_instance_transform(i, star_transform)
-        multimesh.set_instance_color(i, temperature_to_color(temperatures[i]))
-
-func temperature_to_color(temp: float) -> Color:
-    return MeiUtils.temperature_to_color(temp)
+        multimesh.set_instance_color(i, colors[i])
@@ -3,6 +3,7 @@ extends Node3D
 # Scene templates
 var SelectionMarkerScene = preload("res://scenes/objects/selection_marker.tscn")
 var CrosshairScene = preload("res://scenes/objects/crosshair.tscn")
+const SystemView = preload("res://src/system.gd")
 
 # Sub-scene references
 @export var galaxy_scene: Node3D
@@ -23,6 +24,7 @@ var CrosshairScene = preload("res://scenes/objects/crosshair.tscn")
 @export var seed_input: LineEdit
 @export var velocity_slider: HSlider
 @export var velocity_label: Label
+@export var location_label: Label
 
 @export_group("Goto Position")
 @export var goto_button: Button
@@ -179,8 +181,7 @@ func _update_velocity_label():
         var unit: String
         if in_system_view:
             # Convert visual units/s to AU/s
-            const SYSTEM_SCALE = 1000.0  # Must match system.gd
-            display_speed = camera_speed / SYSTEM_SCALE
+            display_speed = camera_speed / SystemView.SYSTEM_SCALE
             unit = "AU/s"
         else:
             display_speed = camera_speed
@@ -658,8 +659,9 @@ func _complete_fly_to_system():
         system_scene.visible = true
         # Set camera reference so distant stars can follow it
         system_scene.set_camera(camera)
-        # Pass nearby stars from galaxy for distant star rendering
-        var nearby = galaxy_scene.current_nearby_stars if galaxy_scene else {}
+        # Pass nearby stars from galaxy for distant star rendering, relative to the
+        # system so directions don't jitter far from the galactic core
+        var nearby = galaxy_scene.get_nearby_stars_around(selected_star.get("position", {})) if galaxy_scene else {}
         var nearby_count = nearby.get("count", 0) if nearby else 0
         print("Passing ", nearby_count, " nearby stars to system scene")
         system_scene.load_system(selected_system, selected_star, nearby)
@@ -726,6 +728,8 @@ func _update_view_mode_label():
             view_mode_label.text = "System View" + vr_suffix
         else:
             view_mode_label.text = "Galaxy View" + vr_suffix
+    if location_label:
+        location_label.visible = not in_system_view
     if fly_button:
         if is_flying_to_star:
             fly_button.text = "Flying..."
@@ -1003,9 +1007,12 @@ func _update_selected_object_info():
         "Star":
             info = "★ STAR ★\n"
             info += "Type: " + str(data.get("star_type", "Unknown")) + "\n"
+            if data.get("spectral_class", "") != "":
+                info += "Class: " + data["spectral_class"] + "\n"
             info += "Temp: " + str(int(data.get("temperature", 0))) + " K\n"
             info += "Mass: " + str(snapped(data.get("mass", 0), 0.01)) + " M☉\n"
-            info += "Luminosity: " + str(snapped(data.get("luminosity", 0), 0.01))
+            info += "Luminosity: " + str(snapped(data.get("luminosity", 0), 0.01)) + " L☉\n"
+            info += "Abs. magnitude: " + str(snapped(data.get("absolute_magnitude", 0), 0.01))
         "Planet":
             info = "● PLANET ●\n"
             info += "Type: " + str(data.get("planet_type", "Unknown")) + "\n"
@@ -1067,8 +1074,22 @@ func _maybe_update_nearby_stars():
             galaxy_scene.update_nearby_stars(camera.global_position)
             galaxy_scene.rebuild_pick_grid(camera)  # Rebuild pick grid when stopped
             _update_nearest_stars_list()
+            _update_location_label()
             _nearby_stars_dirty = false  # Only update once after stopping
 
+func _update_location_label():
+    if location_label == null:
+        return
+    var here = galaxy_scene.describe_location(camera.global_position)
+    if here.is_empty():
+        location_label.text = ""
+        return
+    var text = here["region"]
+    if here["arm"] >= 0:
+        text += " | %.0f ly from %s arm" % [here["arm_distance"], here["arm_name"]]
+    text += " | [Fe/H] %+.2f | %.4f stars/ly³" % [here["metallicity"], here["stellar_density"]]
+    location_label.text = text
+
 func _update_hud(delta: float):
     if camera == null:
         return
@@ -1077,8 +1098,7 @@ func _update_hud(delta: float):
     
     if in_system_view:
         # System view: positions in AU (visual coords / SYSTEM_SCALE)
-        const SYSTEM_SCALE = 1000.0  # Must match system.gd
-        var pos_au = cam_pos / SYSTEM_SCALE
+        var pos_au = cam_pos / SystemView.SYSTEM_SCALE
         x_position.text = "%.4f AU" % pos_au.x
         y_position.text = "%.4f AU" % pos_au.y
         z_position.text = "%.4f AU" % pos_au.z
@@ -1558,7 +1578,6 @@ func _update_nearest_stars_list():
     var ids: PackedInt64Array = nearby.get("ids", PackedInt64Array())
     var luminosities: PackedFloat32Array = nearby.get("luminosities", PackedFloat32Array())
     var temperatures: PackedFloat32Array = nearby.get("temperatures", PackedFloat32Array())
-    var star_types: PackedStringArray = nearby.get("star_types", PackedStringArray())
     
     if positions.is_empty():
         _update_nearby_panels_visibility()
@@ -1577,7 +1596,8 @@ func _update_nearest_stars_list():
             "pos": star_pos_ly,
             "luminosity": luminosities[i] if i < luminosities.size() else 1.0,
             "temperature": temperatures[i] if i < temperatures.size() else 5000.0,
-            "star_type": star_types[i] if i < star_types.size() else "Unknown"
+            "star_type": MeiUtils.get_star_type(nearby, i),
+            "spectral_class": MeiUtils.get_spectral_class(nearby, i)
         })
     
     # Sort by distance
@@ -1589,6 +1609,8 @@ func _update_nearest_stars_list():
         var star = nearby_stars_data[i]
         var btn = Button.new()
         btn.text = "%.1f ly - %s" % [star.dist, star.star_type]
+        if star.spectral_class != "":
+            btn.text += " (%s)" % star.spectral_class
         btn.alignment = HORIZONTAL_ALIGNMENT_LEFT
         btn.pressed.connect(_on_nearby_star_selected.bind(star))
         nearest_star_list.add_child(btn)
@@ -1644,6 +1666,8 @@ func _update_star_info_label(distance_ly: float):
         var s = stars[0]
         info += "[center][b][color=#ffdd44]★ %s ★[/color][/b][/center]\n\n" % s.get("star_type", "Unknown")
         info += "[color=#88ccff]📏 Distance:[/color] %.2f ly\n" % distance_ly
+        if s.get("spectral_class", "") != "":
+            info += "[color=#ffdd44]✦ Spectral class:[/color] %s\n" % s["spectral_class"]
         info += "[color=#ff8844]🌡 Temperature:[/color] %.0f K\n" % s.get("temperature", 0)
         info += "[color=#ffaa00]☀ Luminosity:[/color] %.2f L☉\n" % s.get("luminosity", 0)
         info += "[color=#aaaaff]⚖ Mass:[/color] %.2f M☉\n" % s.get("mass", 0)
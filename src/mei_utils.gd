@@ -4,10 +4,10 @@ extends Node
 ## Registered as autoload "MeiUtils"
 
 # =============================================================================
-# REALISTIC SCALE CONSTANTS (in AU)
+# REALISTIC SCALE (in AU)
 # =============================================================================
-# 1 AU = 149,597,870.7 km (Earth-Sun distance)
-# All sizes are DIAMETERS in AU for direct use as object scale
+# All sizes are DIAMETERS in AU for direct use as object scale.
+# Unit conversions come from MeiUnits, e.g. MeiUnits.convert(1.0, "earth_radius", "au")
 #
 # Reference objects:
 # - Sun diameter: 0.00930 AU (1,392,680 km)
@@ -17,12 +17,6 @@ extends Node
 # - Earth-Moon distance: 0.00257 AU (384,400 km)
 # =============================================================================
 
-# Conversion constants
-const KM_PER_AU: float = 149597870.7
-const SOLAR_RADIUS_AU: float = 0.00465  # Sun radius in AU
-const EARTH_RADIUS_AU: float = 0.0000426  # Earth radius in AU
-const JUPITER_RADIUS_AU: float = 0.000477  # Jupiter radius in AU
-
 # Planet type colors - based on actual planetary appearances
 const PLANET_COLORS = {
     "GasGiant": Color(0.85, 0.65, 0.45, 1.0),    # Jupiter - tan/orange bands
@@ -41,117 +35,36 @@ const PLANET_RADIUS_RANGES = {
     "Dwarf": {"min": 0.05, "max": 0.4},        # Pluto=0.19, Ceres=0.07 Earth radii
 }
 
-## Convert stellar temperature (Kelvin) to RGB color
-## Based on realistic blackbody radiation and stellar spectral classes
-## Reference: Mitchell Charity's "What color are the stars?" and CIE color matching
-func temperature_to_color(temp: float) -> Color:
-    # Clamp temperature to reasonable stellar range
-    temp = clamp(temp, 1000.0, 40000.0)
-    
-    var r: float
-    var g: float
-    var b: float
-    
-    # Using realistic color values for stellar spectral classes
-    # Based on blackbody radiation chromaticity
-    if temp < 2400:
-        # Very cool (L/T class brown dwarfs) - deep red
-        r = 1.0
-        g = 0.2 + 0.15 * (temp / 2400.0)
-        b = 0.05
-    elif temp < 3700:
-        # M class (Red dwarfs, red giants) - orange-red
-        var t = (temp - 2400.0) / 1300.0
-        r = 1.0
-        g = 0.35 + 0.35 * t
-        b = 0.08 + 0.17 * t
-    elif temp < 5200:
-        # K class (Orange stars) - orange to pale orange-white
-        var t = (temp - 3700.0) / 1500.0
-        r = 1.0
-        g = 0.75 + 0.15 * t
-        b = 0.4 + 0.4 * t
-    elif temp < 6000:
-        # G class (Yellow stars like Sun) - nearly white with very slight warmth
-        # Sun at 5778K appears white to pale yellow-white
-        var t = (temp - 5200.0) / 800.0
-        r = 1.0
-        g = 0.95 + 0.03 * t
-        b = 0.85 + 0.13 * t
-    elif temp < 7500:
-        # F class (Yellow-white stars) - white with barely perceptible warmth
-        var t = (temp - 6000.0) / 1500.0
-        r = 1.0 - 0.02 * t
-        g = 0.98 - 0.01 * t
-        b = 0.98 + 0.02 * t
-    elif temp < 10000:
-        # A class (White stars like Sirius, Vega) - pure white to blue-white
-        var t = (temp - 7500.0) / 2500.0
-        r = 0.98 - 0.15 * t
-        g = 0.97 - 0.07 * t
-        b = 1.0
-    elif temp < 30000:
-        # B class (Blue-white stars) - distinctly blue
-        var t = clamp((temp - 10000.0) / 20000.0, 0.0, 1.0)
-        r = 0.8 - 0.25 * t
-        g = 0.85 - 0.15 * t
-        b = 1.0
-    else:
-        # O class (Blue stars, hottest) - deep blue
-        var t = clamp((temp - 30000.0) / 10000.0, 0.0, 1.0)
-        r = 0.55 - 0.1 * t
-        g = 0.7 - 0.1 * t
-        b = 1.0
-    
-    return Color(r, g, b, 1.0)
-
 ## Get color for a planet type
 func get_planet_color(planet_type: String) -> Color:
     return PLANET_COLORS.get(planet_type, Color(0.5, 0.5, 0.5, 1.0))
 
-## Get random texture path for a planet type
-func get_planet_texture(planet_type: String, planet_id: int = 0) -> String:
-    # Use planet_id as seed for consistent texture selection per planet
-    var rng = RandomNumberGenerator.new()
-    rng.seed = planet_id
-    
-    var texture_folders = []
-    
-    match planet_type:
-        "GasGiant":
-            texture_folders = ["Gaseous", "Methane"]
-        "IceGiant":
-            texture_folders = ["Snowy", "Tundra"]
-        "Terrestrial":
-            texture_folders = ["Grassland", "Jungle", "Marshy", "Sandy", "Arid"]
-        "Dwarf":
-            texture_folders = ["Barren", "Dusty", "Martian"]
-        _:
-            texture_folders = ["Barren"]  # Default fallback
-    
-    # Pick random folder
-    var folder = texture_folders[rng.randi() % texture_folders.size()]
-    
-    # Get available textures in that folder
-    var dir = DirAccess.open("res://assets/PlanetTextures/" + folder)
-    if dir == null:
-        return ""  # Folder doesn't exist
-    
-    var textures = []
-    dir.list_dir_begin()
-    var file_name = dir.get_next()
-    while file_name != "":
-        if file_name.ends_with(".png") and not file_name.ends_with(".import"):
-            textures.append(file_name)
-        file_name = dir.get_next()
-    dir.list_dir_end()
-    
-    if textures.is_empty():
-        return ""  # No textures found
-    
-    # Pick random texture
-    var texture_file = textures[rng.randi() % textures.size()]
-    return "res://assets/PlanetTextures/" + folder + "/" + texture_file
+## Get the type name of star `index` in a get_structure or get_nearby_stars result
+## Star types come as byte codes into a per-result name table
+func get_star_type(stars: Dictionary, index: int) -> String:
+    var codes: PackedByteArray = stars.get("star_type_codes", PackedByteArray())
+    var names: PackedStringArray = stars.get("star_type_names", PackedStringArray())
+    if index < 0 or index >= codes.size() or codes[index] >= names.size():
+        return "Unknown"
+    return names[codes[index]]
+
+## Get the spectral class (e.g. "G2V") of star `index` in a get_structure or get_nearby_stars result
+## Empty for neutron stars and black holes, which have none
+func get_spectral_class(stars: Dictionary, index: int) -> String:
+    var codes: PackedInt32Array = stars.get("spectral_class_codes", PackedInt32Array())
+    var names: PackedStringArray = stars.get("spectral_class_names", PackedStringArray())
+    if index < 0 or index >= codes.size() or codes[index] >= names.size():
+        return ""
+    return names[codes[index]]
+
+## Texture catalog shared by all planets and moons
+var texture_catalog: MeiTextureCatalog = MeiTextureCatalog.new()
+
+## Get texture path for a planet or moon dictionary
+## Picked from the catalog by body type, temperature band and body seed,
+## so the same body always gets the same texture
+func get_body_texture(body: Dictionary) -> String:
+    return texture_catalog.select_texture(body)
 
 ## Calculate REALISTIC diameter for a planet based on type and mass (in AU)
 ## Uses mass-radius relationship: R ∝ M^0.27 for rocky, R ∝ M^0.5 for gas giants
@@ -163,7 +76,7 @@ func get_planet_size(planet_type: String, mass: float) -> float:
         "GasGiant":
             # Gas giants: R ∝ M^0.5 (roughly), mass in Jupiter masses
             # Jupiter = 318 Earth masses, radius = 11.2 Earth radii
-            var mass_jupiter = mass / 318.0
+            var mass_jupiter = MeiUnits.convert(mass, "earth_mass", "jupiter_mass")
             radius_earth = 11.2 * pow(mass_jupiter, 0.5)
         "IceGiant":
             # Ice giants: similar scaling, mass in Neptune masses
@@ -182,7 +95,7 @@ func get_planet_size(planet_type: String, mass: float) -> float:
     radius_earth = clamp(radius_earth, ranges["min"], ranges["max"])
     
     # Convert to diameter in AU (Earth radius = 0.0000426 AU)
-    return radius_earth * EARTH_RADIUS_AU * 2.0
+    return MeiUnits.convert(radius_earth * 2.0, "earth_radius", "au")
 
 ## Calculate REALISTIC diameter for a star based on luminosity (in AU)
 ## Uses different relationships for different stellar types
@@ -207,7 +120,7 @@ func get_star_size(luminosity: float, _scale_factor: float = 0.5) -> float:
     # Clamp to reasonable stellar sizes (0.08 to 2000 solar radii)
     radius_solar = clamp(radius_solar, 0.08, 2000.0)
     # Return diameter in AU
-    return radius_solar * SOLAR_RADIUS_AU * 2.0
+    return MeiUnits.convert(radius_solar * 2.0, "solar_radius", "au")
 
 ## Get moon diameter in AU based on mass (in Earth masses)
 func get_moon_size(mass: float) -> float:
@@ -215,4 +128,4 @@ func get_moon_size(mass: float) -> float:
     # Our Moon: mass = 0.0123 Earth masses, radius = 0.273 Earth radii
     var radius_earth = 0.273 * pow(mass / 0.0123, 0.27)
     radius_earth = clamp(radius_earth, 0.05, 0.5)  # 0.05 to 0.5 Earth radii
-    return radius_earth * EARTH_RADIUS_AU * 2.0
+    return MeiUnits.convert(radius_earth * 2.0, "earth_radius", "au")
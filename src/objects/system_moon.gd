@@ -27,9 +27,14 @@ func setup(data: Dictionary, index: int, p_index: int, size: float):
     sphere_shape.radius = 0.5  # Will be scaled with node
     collision_shape.shape = sphere_shape
     
-    # Set material with white albedo
+    # Set material with white albedo so the texture shows its own colors
     var mat = mesh.get_surface_override_material(0).duplicate()
     mat.albedo_color = Color.WHITE
+    var texture_path = MeiUtils.get_body_texture(data)
+    if texture_path != "" and ResourceLoader.exists(texture_path):
+        var texture = load(texture_path)
+        if texture != null:
+            mat.albedo_texture = texture
     mesh.set_surface_override_material(0, mat)
     
     # Store metadata for picking
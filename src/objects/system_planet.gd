@@ -27,12 +27,11 @@ func setup(data: Dictionary, index: int, size: float):
     
     # Set texture and color based on planet type
     var planet_type = data.get("planet_type", "Terrestrial")
-    var planet_id = data.get("id", index)  # Use planet ID for consistent texture selection
     
     var mat = mesh.get_surface_override_material(0).duplicate()
     
     # Try to load a texture first
-    var texture_path = MeiUtils.get_planet_texture(planet_type, planet_id)
+    var texture_path = MeiUtils.get_body_texture(data)
     print("Planet ", planet_index, " (", planet_type, "): trying texture ", texture_path)
     
     if texture_path != "" and ResourceLoader.exists(texture_path):
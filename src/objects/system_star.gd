@@ -10,6 +10,7 @@ var pulse_light: OmniLight3D = null
 var star_data: Dictionary = {}
 var star_index: int = 0
 var star_type: String = ""
+var star_color: Color = Color.WHITE  # sRGB blackbody color computed by MeiGalaxy
 var pulse_time: float = 0.0
 
 # Exotic star type identifiers
@@ -25,6 +26,7 @@ func setup(data: Dictionary, index: int, size: float):
     star_data = data
     star_index = index
     star_type = data.get("star_type", "YellowStar")
+    star_color = data.get("color", Color.WHITE)
     
     # Get nodes directly since setup is called before _ready
     mesh = $Mesh
@@ -79,9 +81,9 @@ func _setup_star_appearance(data: Dictionary, size: float):
     mesh.set_surface_override_material(0, mat)
     
     # Configure light based on star properties
-    _configure_star_light(temp, luminosity)
+    _configure_star_light(luminosity)
 
-func _configure_star_light(temp: float, luminosity: float):
+func _configure_star_light(luminosity: float):
     ## Configure the omni light based on star's physical properties.
     ## Uses high energy with low attenuation to simulate realistic light falloff.
     ## At SYSTEM_SCALE=1000, 1 AU = 1000 visual units.
@@ -90,8 +92,8 @@ func _configure_star_light(temp: float, luminosity: float):
     if light == null:
         return
     
-    # Set light color from temperature
-    light.light_color = MeiUtils.temperature_to_color(temp)
+    # Set light color from the star's blackbody color
+    light.light_color = star_color
     
     # Use low attenuation for gradual falloff (0 = no falloff, 1 = linear, 2 = inverse square)
     # Lower values mean light reaches further with less falloff
@@ -109,8 +111,8 @@ func _configure_star_light(temp: float, luminosity: float):
     energy = clamp(energy, 0.5, 1000.0)
     light.light_energy = energy
 
-func _setup_normal_star(mat: StandardMaterial3D, temp: float, luminosity: float):
-    var color = MeiUtils.temperature_to_color(temp)
+func _setup_normal_star(mat: StandardMaterial3D, _temp: float, luminosity: float):
+    var color = star_color
     mat.albedo_color = color
     mat.emission_enabled = true
     mat.emission = color
@@ -161,9 +163,9 @@ func _setup_magnetar(mat: StandardMaterial3D, _temp: float, _luminosity: float):
     # Add pulsing light
     _create_pulse_light(color)
 
-func _setup_white_dwarf(mat: StandardMaterial3D, temp: float, _luminosity: float):
+func _setup_white_dwarf(mat: StandardMaterial3D, _temp: float, _luminosity: float):
     # White dwarf: hot but dim, blue-white
-    var color = MeiUtils.temperature_to_color(temp)
+    var color = star_color
     mat.albedo_color = color
     mat.emission_enabled = true
     mat.emission = color
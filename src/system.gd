@@ -40,9 +40,22 @@ const DISTANT_STAR_SCALE: float = 4000.0 # SYSTEM_SCALE * 50.0  # Background sta
 # Minimum visual size to ensure objects remain clickable
 const MIN_CLICKABLE_SIZE: float = 0.1
 
+# Asteroids sampled per belt, and colors indexed by MeiAsteroidBelt type code
+# (Carbonaceous, Silicate, Metallic)
+const ASTEROIDS_PER_BELT: int = 2000
+const ASTEROID_TYPE_COLORS = [Color(0.3, 0.28, 0.26, 1.0), Color(0.55, 0.48, 0.4, 1.0), Color(0.6, 0.6, 0.62, 1.0)]
+
+# Orbit line segments, colors per stellar component (blue primary, orange
+# secondary, green tertiary) and a subtle gray/blue for moons
+const ORBIT_SEGMENTS: int = 64
+const COMPONENT_ORBIT_COLORS = [Color(0.3, 0.5, 1.0, 0.6), Color(1.0, 0.5, 0.3, 0.6), Color(0.3, 1.0, 0.5, 0.6)]
+const MOON_ORBIT_COLOR = Color(0.5, 0.6, 0.8, 0.3)
+
 var current_system: Dictionary = {}
 var system_center: Vector3 = Vector3.ZERO
 var star_galactic_position: Vector3 = Vector3.ZERO
+# Where each planet and moon sits on its orbit (MeiOrbit.get_system_layout)
+var body_layout: Dictionary = {}
 
 # Rendered object nodes
 var star_nodes: Array = []
@@ -117,6 +130,7 @@ func load_system(system_data: Dictionary, star_data: Dictionary, nearby_stars: D
     )
     
     system_center = Vector3.ZERO
+    body_layout = MeiOrbit.get_system_layout(system_data)
     
     render_system_stars(system_data.get("stars", []))
     
@@ -129,10 +143,20 @@ func load_system(system_data: Dictionary, star_data: Dictionary, nearby_stars: D
     else:
         # Legacy: single component system
         render_planets(system_data.get("inner_planets", []), system_data.get("outer_planets", []))
-        render_orbit_lines(system_data.get("inner_planets", []), system_data.get("outer_planets", []), system_data.get("asteroid_belts", []))
+    render_orbit_lines(system_data)
     
     render_asteroid_belts(system_data.get("asteroid_belts", []))
-    render_distant_stars(nearby_stars, star_galactic_position)
+    # Relative queries carry the coordinate their positions are measured from;
+    # subtract it in float (64-bit) before building the Vector3
+    var origin := PackedFloat64Array([0.0, 0.0, 0.0])
+    if nearby_stars.get("origin") is MeiCoordinate:
+        origin = nearby_stars["origin"].get_galactic()
+    var star_offset = Vector3(
+        star_pos.get("x", 0.0) - origin[0],
+        star_pos.get("y", 0.0) - origin[1],
+        star_pos.get("z", 0.0) - origin[2]
+    )
+    render_distant_stars(nearby_stars, star_offset)
     
     _print_system_info(system_data, configuration)
 
@@ -168,14 +192,7 @@ func render_planets(inner: Array, outer: Array):
     
     for i in range(all_planets.size()):
         var planet = all_planets[i]
-        var orbital_radius = planet.get("orbital_radius", planet.get("position", {}).get("x", 1.0))
-        
-        var angle = i * TAU / max(all_planets.size(), 1) + i * 0.5
-        var planet_pos = Vector3(
-            cos(angle) * orbital_radius * SYSTEM_SCALE,
-            0,
-            sin(angle) * orbital_radius * SYSTEM_SCALE
-        )
+        var planet_pos = _layout_position("planet_positions", planet)
         
         var planet_type = planet.get("planet_type", "Terrestrial")
         var mass = planet.get("mass", 1.0)
@@ -193,6 +210,10 @@ func render_planets(inner: Array, outer: Array):
             var moon = planet_moons[j]
             _render_moon(moon, j, i, planet_pos)
 
+# Visual position of a planet or moon from body_layout
+func _layout_position(kind: String, body: Dictionary) -> Vector3:
+    return body_layout.get(kind, {}).get(body.get("seed", 0), Vector3.ZERO) * SYSTEM_SCALE
+
 func _render_moon(moon: Dictionary, moon_idx: int, planet_idx: int, planet_pos: Vector3):
     # Get orbital radius in km - do NOT use position as fallback (that's galactic coordinates!)
     var orbital_radius_km = moon.get("orbital_radius", 10000.0)
@@ -203,14 +224,9 @@ func _render_moon(moon: Dictionary, moon_idx: int, planet_idx: int, planet_pos:
         print("  Moon data: ", moon)
     
     # REALISTIC: Convert km to AU (no multiplier - true scale)
-    var orbital_radius_au = orbital_radius_km / MeiUtils.KM_PER_AU
+    var orbital_radius_au = MeiUnits.convert(orbital_radius_km, "km", "au")
     
-    var angle = moon_idx * TAU / 7.0
-    var moon_pos = planet_pos + Vector3(
-        cos(angle) * orbital_radius_au * SYSTEM_SCALE,
-        0,
-        sin(angle) * orbital_radius_au * SYSTEM_SCALE
-    )
+    var moon_pos = _layout_position("moon_positions", moon)
     
     # Debug moon placement
     var distance_from_planet = planet_pos.distance_to(moon_pos)
@@ -227,26 +243,6 @@ func _render_moon(moon: Dictionary, moon_idx: int, planet_idx: int, planet_pos:
     moon_node.setup(moon, moon_idx, planet_idx, size)
     moons_container.add_child(moon_node)
     moon_nodes.append(moon_node)
-    
-    # Draw moon orbit line around planet
-    _draw_moon_orbit(planet_pos, orbital_radius_au * SYSTEM_SCALE)
-
-func _draw_moon_orbit(center: Vector3, radius: float):
-    if radius < 0.001:
-        return
-    
-    # Use fewer segments for moon orbits since they're smaller
-    var segments = 32
-    var mesh = _create_circle_mesh(radius, segments)
-    var instance = MeshInstance3D.new()
-    instance.mesh = mesh
-    
-    # Moon orbits are subtle gray/blue
-    var mat = OrbitMaterial.duplicate()
-    mat.albedo_color = Color(0.5, 0.6, 0.8, 0.3)
-    instance.material_override = mat
-    instance.position = center
-    orbit_lines_container.add_child(instance)
 
 func select_object_node(obj_node: Node3D):
     if obj_node == null:
@@ -267,50 +263,37 @@ func clear_selection():
 func get_planet_size(planet_type: String, mass: float) -> float:
     return MeiUtils.get_planet_size(planet_type, mass)
 
-func render_orbit_lines(inner: Array, outer: Array, asteroid_belts: Array = [], center: Vector3 = Vector3.ZERO, orbit_color: Color = Color.WHITE):
+func render_orbit_lines(system_data: Dictionary):
     if orbit_lines_container == null:
         return
     
-    var all_planets = inner + outer
-    
-    for i in range(all_planets.size()):
-        var planet = all_planets[i]
-        var orbital_radius = planet.get("orbital_radius", 0.0)
-        if orbital_radius == 0.0:
-            orbital_radius = planet.get("position", {}).get("x", 1.0)
-        orbital_radius *= SYSTEM_SCALE
-        if orbital_radius < 0.01:
-            continue
-        
-        var orbit_mesh = _create_circle_mesh(orbital_radius, 64)
-        var mesh_instance = MeshInstance3D.new()
-        mesh_instance.mesh = orbit_mesh
-        var mat = OrbitMaterial.duplicate()
-        if orbit_color != Color.WHITE:
-            mat.albedo_color = orbit_color
-        mesh_instance.material_override = mat
-        mesh_instance.position = center
-        orbit_lines_container.add_child(mesh_instance)
-    
-    for belt in asteroid_belts:
-        var inner_radius = belt.get("inner_radius", 0.0) * SYSTEM_SCALE
-        var outer_radius = belt.get("outer_radius", 0.0) * SYSTEM_SCALE
-        
-        if inner_radius > 0.01:
-            var inner_mesh = _create_circle_mesh(inner_radius, 64)
-            var inner_instance = MeshInstance3D.new()
-            inner_instance.mesh = inner_mesh
-            inner_instance.material_override = BeltOrbitMaterial.duplicate()
-            inner_instance.position = center
-            orbit_lines_container.add_child(inner_instance)
-        
-        if outer_radius > 0.01:
-            var outer_mesh = _create_circle_mesh(outer_radius, 64)
-            var outer_instance = MeshInstance3D.new()
-            outer_instance.mesh = outer_mesh
-            outer_instance.material_override = BeltOrbitMaterial.duplicate()
-            outer_instance.position = center
-            orbit_lines_container.add_child(outer_instance)
+    # Every planet, moon and comet orbit in one mesh, through the positions
+    # body_layout placed the planets and moons at
+    var layout = {
+        "planet_positions": body_layout.get("planet_positions", {}),
+        "planet_color": OrbitMaterial.albedo_color,
+        "component_colors": COMPONENT_ORBIT_COLORS,
+        "moon_color": MOON_ORBIT_COLOR,
+    }
+    var orbits_instance = MeshInstance3D.new()
+    orbits_instance.mesh = MeiOrbit.build_system_orbit_mesh(system_data, layout, ORBIT_SEGMENTS, SYSTEM_SCALE)
+    var mat = OrbitMaterial.duplicate()
+    mat.albedo_color = Color.WHITE
+    mat.vertex_color_use_as_albedo = true
+    orbits_instance.material_override = mat
+    orbit_lines_container.add_child(orbits_instance)
+    
+    # Asteroid belt edges, batched the same way
+    var belt_edges = []
+    for belt in system_data.get("asteroid_belts", []):
+        for radius in [belt.get("inner_radius", 0.0), belt.get("outer_radius", 0.0)]:
+            if radius * SYSTEM_SCALE > 0.01:
+                belt_edges.append({"semi_major_axis": radius})
+    if belt_edges.size() > 0:
+        var belts_instance = MeshInstance3D.new()
+        belts_instance.mesh = MeiOrbit.build_orbit_mesh_batch(belt_edges, ORBIT_SEGMENTS, SYSTEM_SCALE)
+        belts_instance.material_override = BeltOrbitMaterial.duplicate()
+        orbit_lines_container.add_child(belts_instance)
 
 func _render_multi_star_system(components: Array, _stars: Array):
     # Clear existing orbit lines
@@ -319,13 +302,6 @@ func _render_multi_star_system(components: Array, _stars: Array):
             orbit_lines_container.remove_child(child)
             child.free()
     
-    # Colors for different components' orbits
-    var component_colors = [
-        Color(0.3, 0.5, 1.0, 0.6),   # Blue for primary
-        Color(1.0, 0.5, 0.3, 0.6),   # Orange for secondary
-        Color(0.3, 1.0, 0.5, 0.6),   # Green for tertiary
-    ]
-    
     var total_planets = 0
     
     for comp_idx in range(components.size()):
@@ -345,13 +321,10 @@ func _render_multi_star_system(components: Array, _stars: Array):
         var star_indices = component.get("star_indices", [])
         var is_binary_component = star_indices.size() >= 2
         
-        # Choose orbit color based on component index
-        var orbit_color = component_colors[comp_idx % component_colors.size()]
-        
-        # Render planets for this component (positioned relative to barycenter)
+        # Render planets for this component (on their orbits around the
+        # barycenter); the orbits are drawn with the rest in render_orbit_lines
         if inner_planets.size() > 0 or outer_planets.size() > 0:
-            _render_component_planets(inner_planets, outer_planets, barycenter, comp_idx)
-            render_orbit_lines(inner_planets, outer_planets, [], barycenter, orbit_color)
+            _render_component_planets(inner_planets, outer_planets)
             total_planets += inner_planets.size() + outer_planets.size()
         
         # For binary components, draw a line showing the binary orbit
@@ -362,21 +335,13 @@ func _render_multi_star_system(components: Array, _stars: Array):
     
     print("Multi-star system: ", components.size(), " components, ", total_planets, " total planets")
 
-func _render_component_planets(inner: Array, outer: Array, barycenter: Vector3, component_idx: int):
+func _render_component_planets(inner: Array, outer: Array):
     var all_planets = inner + outer
     var base_planet_idx = planet_nodes.size()
     
     for i in range(all_planets.size()):
         var planet = all_planets[i]
-        var orbital_radius = planet.get("orbital_radius", planet.get("position", {}).get("x", 1.0))
-        
-        # Position planet on orbit around barycenter
-        var angle = i * TAU / max(all_planets.size(), 1) + i * 0.5 + component_idx * 0.7
-        var planet_pos = barycenter + Vector3(
-            cos(angle) * orbital_radius * SYSTEM_SCALE,
-            0,
-            sin(angle) * orbital_radius * SYSTEM_SCALE
-        )
+        var planet_pos = _layout_position("planet_positions", planet)
         
         var planet_type = planet.get("planet_type", "Terrestrial")
         var mass = planet.get("mass", 1.0)
@@ -503,31 +468,20 @@ func get_object_position_in_au(obj_type: String, index: int) -> Dictionary:
     return {}
 
 func _create_circle_mesh(radius: float, segments: int) -> ArrayMesh:
-    var mesh = ArrayMesh.new()
-    var vertices = PackedVector3Array()
-    
-    for i in range(segments + 1):
-        var angle = float(i) / float(segments) * TAU
-        vertices.append(Vector3(cos(angle) * radius, 0, sin(angle) * radius))
-    
-    var arrays = []
-    arrays.resize(Mesh.ARRAY_MAX)
-    arrays[Mesh.ARRAY_VERTEX] = vertices
-    
-    mesh.add_surface_from_arrays(Mesh.PRIMITIVE_LINE_STRIP, arrays)
-    return mesh
+    return MeiOrbit.build_orbit_mesh({"semi_major_axis": radius}, segments, 1.0)
 
 func render_asteroid_belts(belts: Array):
     if asteroid_belt_viz == null or asteroid_belt_viz.multimesh == null:
         return
     
-    var all_asteroids = []
+    # Sample individual asteroids per belt; seeded per belt so revisits look identical
+    var samples = []
+    var count = 0
     for belt in belts:
-        for asteroid in belt.get("largest_bodies", []):
-            asteroid["belt_name"] = belt.get("name", "Belt")
-            all_asteroids.append(asteroid)
+        var sample = MeiAsteroidBelt.from_dict(belt).sample_asteroids(ASTEROIDS_PER_BELT, 0)
+        samples.append(sample)
+        count += sample.get("count", 0)
     
-    var count = all_asteroids.size()
     if count == 0:
         asteroid_belt_viz.multimesh.instance_count = 0
         return
@@ -537,23 +491,20 @@ func render_asteroid_belts(belts: Array):
     multimesh.use_colors = true
     multimesh.instance_count = count
     
-    for i in range(count):
-        var asteroid = all_asteroids[i]
-        var pos = asteroid.get("position", {})
-        
-        var asteroid_pos = Vector3(
-            pos.get("x", 0.0) * SYSTEM_SCALE,
-            pos.get("y", 0.0) * SYSTEM_SCALE,
-            pos.get("z", 0.0) * SYSTEM_SCALE
-        )
-        
-        var size = 0.005 + asteroid.get("diameter", 100.0) / 10000.0
-        
-        var transform = Transform3D()
-        transform.basis = transform.basis.scaled(Vector3(size, size, size))
-        transform.origin = asteroid_pos
-        multimesh.set_instance_transform(i, transform)
-        multimesh.set_instance_color(i, Color(0.5, 0.45, 0.4, 1.0))
+    var i = 0
+    for sample in samples:
+        var positions: PackedVector3Array = sample["positions"]
+        var sizes: PackedFloat32Array = sample["sizes"]
+        var types: PackedByteArray = sample["types"]
+        for j in range(sample["count"]):
+            var size = 0.005 + sizes[j] / 10000.0
+            
+            var transform = Transform3D()
+            transform.basis = transform.basis.scaled(Vector3(size, size, size))
+            transform.origin = positions[j] * SYSTEM_SCALE
+            multimesh.set_instance_transform(i, transform)
+            multimesh.set_instance_color(i, ASTEROID_TYPE_COLORS[types[j]])
+            i += 1
 
 func render_distant_stars(nearby_stars: Dictionary, current_star_pos: Vector3):
     if distant_stars == null or distant_stars.multimesh == null:
@@ -561,7 +512,7 @@ func render_distant_stars(nearby_stars: Dictionary, current_star_pos: Vector3):
     
     var positions: PackedVector3Array = nearby_stars.get("positions", PackedVector3Array())
     var luminosities: PackedFloat32Array = nearby_stars.get("luminosities", PackedFloat32Array())
-    var temperatures: PackedFloat32Array = nearby_stars.get("temperatures", PackedFloat32Array())
+    var colors: PackedColorArray = nearby_stars.get("colors", PackedColorArray())
     
     print("render_distant_stars called with ", positions.size(), " nearby stars")
     
@@ -600,13 +551,9 @@ func render_distant_stars(nearby_stars: Dictionary, current_star_pos: Vector3):
         instance_transform.origin = distant_pos
         multimesh.set_instance_transform(idx, instance_transform)
         
-        var temp = temperatures[i]
-        multimesh.set_instance_color(idx, temperature_to_color(temp))
+        multimesh.set_instance_color(idx, colors[i])
         idx += 1
 
-func temperature_to_color(temp: float) -> Color:
-    return MeiUtils.temperature_to_color(temp)
-
 func get_recommended_camera_position() -> Vector3:
     # Position camera to see the star well
     # Star diameter is ~0.0093 AU, so at SYSTEM_SCALE it's 0.0093 * SYSTEM_SCALE visual units
@@ -0,0 +1,742 @@
+extends SceneTree
+
+## Headless integration tests for the MeiGalaxy GDExtension class.
+##
+## Exercises every MeiGalaxy method against the built extension, including
+## calls before ready() and bad arguments, and checks both the returned
+## values and the errors pushed to the Godot log.
+##
+## Run from the project root (see scripts/run-godot-tests.sh):
+##     godot --headless --path . --import
+##     godot --headless --path . --script res://tests/godot/test_mei_galaxy.gd
+## The exit code is the number of failed checks.
+
+const SEED := 42
+const CACHE_DIR := "user://mei_test_cache"
+const NEARBY_RADIUS := 10.0
+
+const STAR_KEYS := ["positions", "ids", "luminosities", "temperatures", "masses", "radii", "absolute_magnitudes",
+    "colors", "star_type_codes", "spectral_class_codes", "count"]
+const PROPERTY_KEYS := ["positions", "luminosities", "temperatures", "masses", "radii", "absolute_magnitudes",
+    "colors"]
+const SYSTEM_KEYS := ["star_id", "name", "designation", "position", "stars", "configuration",
+    "stellar_components", "inner_planets", "outer_planets", "asteroid_belts", "frost_line",
+    "habitable_zone_inner", "habitable_zone_outer"]
+
+
+## Collects errors pushed to the Godot log so tests can assert on them.
+class ErrorLog extends Logger:
+    var _mutex := Mutex.new()
+    var _errors: PackedStringArray = []
+
+    func _log_error(_function: String, _file: String, _line: int, code: String, rationale: String,
+            _editor_notify: bool, _error_type: int, _script_backtraces: Array[ScriptBacktrace]) -> void:
+        _mutex.lock()
+        _errors.append((code + " " + rationale).strip_edges())
+        _mutex.unlock()
+
+    func _log_message(_message: String, _error: bool) -> void:
+        pass
+
+    ## Returns and forgets the errors logged so far.
+    func take() -> PackedStringArray:
+        _mutex.lock()
+        var errors := _errors
+        _errors = []
+        _mutex.unlock()
+        return errors
+
+
+var _log := ErrorLog.new()
+var _checks := 0
+var _failures := 0
+var _current := ""
+
+
+func _initialize() -> void:
+    OS.add_logger(_log)
+    _run.call_deferred()
+
+
+func _run() -> void:
+    if not ClassDB.class_exists("MeiGalaxy"):
+        printerr("MeiGalaxy class not found - build the extension and run `godot --headless --import` first")
+        quit(1)
+        return
+
+    for test in [
+        test_uninitialized,
+        test_structure,
+        test_nearby_stars,
+        test_star_properties_agree,
+        test_star_system,
+        test_evolve_star,
+        test_star_positions_at_time,
+        test_coordinates,
+        test_units,
+        test_describe_location,
+        test_morphology,
+        test_invalid_star_ids,
+        test_designations,
+        test_resolve_designation,
+        test_search_systems,
+        test_batches,
+        test_config,
+        test_caches,
+    ]:
+        _current = test.get_method()
+        var galaxy: MeiGalaxy = null
+        if _current != "test_uninitialized":
+            galaxy = _make_galaxy()
+        await test.call(galaxy)
+        if galaxy:
+            galaxy.queue_free()
+        var unexpected := _log.take()
+        _check(unexpected.is_empty(), "unexpected errors: %s" % [unexpected])
+
+    OS.remove_logger(_log)
+    print("%d checks, %d failed" % [_checks, _failures])
+    quit(mini(_failures, 255))
+
+
+func _make_galaxy() -> MeiGalaxy:
+    var galaxy := MeiGalaxy.new()
+    galaxy.structure_cache_dir = CACHE_DIR
+    root.add_child(galaxy)
+    galaxy.set_galaxy_seed(SEED)
+    return galaxy
+
+
+func _check(condition: bool, message: String) -> void:
+    _checks += 1
+    if not condition:
+        _failures += 1
+        printerr("FAIL %s: %s" % [_current, message])
+
+
+func _check_eq(actual: Variant, expected: Variant, message: String) -> void:
+    _check(actual == expected, "%s: expected %s, got %s" % [message, expected, actual])
+
+
+## Checks that exactly the errors logged since the last check contain `needle`.
+func _check_error(needle: String, message: String) -> void:
+    var errors := _log.take()
+    var found := false
+    for error in errors:
+        found = found or error.contains(needle)
+    _check(found, "%s: expected an error containing \"%s\", got %s" % [message, needle, errors])
+
+
+func _check_keys(dict: Dictionary, keys: Array, message: String) -> void:
+    for key in keys:
+        _check(dict.has(key), "%s: missing key \"%s\"" % [message, key])
+
+
+func _check_star_arrays(stars: Dictionary, message: String) -> void:
+    _check_keys(stars, STAR_KEYS, message)
+    for key in STAR_KEYS:
+        if key != "count":
+            _check_eq(stars[key].size(), stars["count"], "%s: %s size" % [message, key])
+    for code in stars.get("star_type_codes", PackedByteArray()):
+        _check(code < stars["star_type_names"].size(), "%s: star type code %d has a name" % [message, code])
+    _check(stars.get("spectral_class_codes", PackedInt32Array()) is PackedInt32Array,
+        "%s: spectral class codes hold more than 256 classes" % message)
+    for code in stars.get("spectral_class_codes", PackedInt32Array()):
+        _check(code < stars["spectral_class_names"].size(), "%s: spectral class code %d has a name" % [message, code])
+
+
+## A populated spot in the galaxy: the first star of a small structure sample.
+func _star_center(galaxy: MeiGalaxy) -> Vector3:
+    return galaxy.get_structure(1)["positions"][0]
+
+
+func _nearby_ids(galaxy: MeiGalaxy, count: int) -> PackedInt64Array:
+    var center := _star_center(galaxy)
+    var ids: PackedInt64Array = galaxy.get_nearby_stars(center.x, center.y, center.z, NEARBY_RADIUS)["ids"]
+    return ids.slice(0, count)
+
+
+func test_uninitialized(_galaxy: MeiGalaxy) -> void:
+    # Not added to the tree, so ready() never runs
+    var galaxy := MeiGalaxy.new()
+    const NOT_INITIALIZED := "MeiGalaxy not initialized"
+
+    _check_eq(galaxy.get_structure(10), {}, "get_structure")
+    _check_error(NOT_INITIALIZED, "get_structure")
+    _check_eq(galaxy.get_nearby_stars(0, 0, 0, 10), {}, "get_nearby_stars")
+    _check_error(NOT_INITIALIZED, "get_nearby_stars")
+    _check_eq(galaxy.get_nearby_stars_limited(0, 0, 0, 10, 5), {}, "get_nearby_stars_limited")
+    _check_error(NOT_INITIALIZED, "get_nearby_stars_limited")
+    _check_eq(galaxy.get_nearby_stars_relative(MeiCoordinate.new(), Vector3.ZERO, 10), {}, "get_nearby_stars_relative")
+    _check_error(NOT_INITIALIZED, "get_nearby_stars_relative")
+    _check_eq(galaxy.get_coordinate(0, 0, 0), null, "get_coordinate")
+    _check_error(NOT_INITIALIZED, "get_coordinate")
+    _check_eq(galaxy.describe_location(Vector3.ZERO, Vector3.ZERO), {}, "describe_location")
+    _check_error(NOT_INITIALIZED, "describe_location")
+    _check_eq(galaxy.get_morphology(), {}, "get_morphology")
+    _check_error(NOT_INITIALIZED, "get_morphology")
+    _check_eq(galaxy.get_arm_spline(0, 8), PackedVector3Array(), "get_arm_spline")
+    _check_error(NOT_INITIALIZED, "get_arm_spline")
+    _check_eq(galaxy.get_star_system("1"), {}, "get_star_system")
+    _check_error(NOT_INITIALIZED, "get_star_system")
+    _check_eq(galaxy.get_star_designation("1"), {}, "get_star_designation")
+    _check_error(NOT_INITIALIZED, "get_star_designation")
+    _check_eq(galaxy.evolve_star("1", 0.0), {}, "evolve_star")
+    _check_error(NOT_INITIALIZED, "evolve_star")
+    _check_eq(galaxy.get_star_positions_at_time(PackedInt64Array([1]), 1.0), PackedVector3Array(),
+        "get_star_positions_at_time")
+    _check_error(NOT_INITIALIZED, "get_star_positions_at_time")
+    _check_eq(galaxy.get_body_designation("1", 0, 0), "", "get_body_designation")
+    _check_error(NOT_INITIALIZED, "get_body_designation")
+    _check_eq(galaxy.resolve_designation("Velonis", Vector3.ZERO, 10.0), {}, "resolve_designation")
+    _check_error(NOT_INITIALIZED, "resolve_designation")
+    _check_eq(galaxy.search_systems(Vector3.ZERO, 10.0, {}, 5), {}, "search_systems")
+    _check_error(NOT_INITIALIZED, "search_systems")
+    _check_eq(galaxy.search_systems_relative(MeiCoordinate.new(), Vector3.ZERO, 10.0, {}, 5), {},
+        "search_systems_relative")
+    _check_error(NOT_INITIALIZED, "search_systems_relative")
+    _check_eq(galaxy.get_star_systems_batch(PackedInt64Array([1]), MeiGalaxy.DETAIL_FULL).size(), 0,
+        "get_star_systems_batch")
+    _check_error(NOT_INITIALIZED, "get_star_systems_batch")
+    _check_eq(galaxy.get_system_summaries(PackedInt64Array([1])), {}, "get_system_summaries")
+    _check_error(NOT_INITIALIZED, "get_system_summaries")
+    _check_eq(galaxy.get_config_fingerprint(), "", "get_config_fingerprint")
+    _check_error(NOT_INITIALIZED, "get_config_fingerprint")
+    _check_eq(galaxy.get_config_fields().size(), 0, "get_config_fields")
+    _check_error(NOT_INITIALIZED, "get_config_fields")
+
+    # Accessors with documented fallbacks, and setters that are no-ops until ready
+    _check_eq(galaxy.get_galaxy_radius(), 0.0, "get_galaxy_radius")
+    _check_eq(galaxy.get_nearby_max_radius(), 16.0, "get_nearby_max_radius")
+    galaxy.set_nearby_max_radius(5.0)
+    galaxy.set_structure_block_size(50.0)
+    galaxy.set_structure_samples_per_block(2)
+    _check_eq(galaxy.get_nearby_max_radius(), 16.0, "set_nearby_max_radius before ready")
+
+    # Names only need the seed
+    _check(not galaxy.get_star_name("1").is_empty(), "get_star_name works without the generator")
+    _check_eq(galaxy.cache_stats()["entries"], 0, "cache_stats entries")
+    galaxy.clear_system_cache()
+
+    # ready() initializes the generator
+    galaxy.structure_cache_dir = ""
+    root.add_child(galaxy)
+    _check(galaxy.get_galaxy_radius() > 0.0, "get_galaxy_radius after ready")
+    _check(galaxy.get_structure(10)["count"] > 0, "get_structure after ready")
+    galaxy.free()
+
+
+func test_structure(galaxy: MeiGalaxy) -> void:
+    galaxy.clear_structure_cache()
+
+    var stars := galaxy.get_structure(500)
+    _check_star_arrays(stars, "get_structure(500)")
+    _check(stars["count"] > 0 and stars["count"] <= 500, "count within max_stars, got %d" % stars["count"])
+    _check(stars["estimated_total_stars"] > stars["count"], "estimated_total_stars exceeds sample")
+    _check_eq(stars["from_cache"], false, "first call generates")
+
+    var again := galaxy.get_structure(500)
+    _check_eq(again["from_cache"], true, "second call loads the disk cache")
+    _check_eq(again["ids"], stars["ids"], "cached ids")
+    _check_eq(again["positions"], stars["positions"], "cached positions")
+
+    var none := galaxy.get_structure(0)
+    _check_star_arrays(none, "get_structure(0)")
+    _check_eq(none["count"], 0, "get_structure(0) count")
+
+    galaxy.star_layout = MeiGalaxy.STAR_LAYOUT_INTERLEAVED
+    var interleaved := galaxy.get_structure(500)
+    galaxy.star_layout = MeiGalaxy.STAR_LAYOUT_SEPARATE
+    _check_keys(interleaved, ["stars", "stride", "ids", "colors", "star_type_names", "count"], "interleaved layout")
+    _check_eq(interleaved["colors"], stars["colors"], "interleaved colors")
+    _check(not interleaved.has("positions"), "interleaved layout has no separate arrays")
+    var stride: int = interleaved["stride"]
+    _check_eq(interleaved["stars"].size(), stars["count"] * stride, "interleaved size")
+    for i in [0, stars["count"] - 1]:
+        var row: PackedFloat32Array = interleaved["stars"].slice(i * stride, (i + 1) * stride)
+        _check_eq(Vector3(row[0], row[1], row[2]), stars["positions"][i], "interleaved position %d" % i)
+        _check_eq(row[3], stars["luminosities"][i], "interleaved luminosity %d" % i)
+        _check_eq(row[5], stars["masses"][i], "interleaved mass %d" % i)
+        _check_eq(int(row[6]), stars["star_type_codes"][i], "interleaved star type %d" % i)
+        _check_eq(row[7], stars["radii"][i], "interleaved radius %d" % i)
+
+    var negative := galaxy.get_structure(-5)
+    _check_error("max_stars must not be negative", "get_structure(-5)")
+    _check_star_arrays(negative, "get_structure(-5)")
+    _check_eq(negative["count"], 0, "get_structure(-5) count")
+
+
+func test_nearby_stars(galaxy: MeiGalaxy) -> void:
+    var center := _star_center(galaxy)
+    var max_radius := galaxy.get_nearby_max_radius()
+
+    var stars := galaxy.get_nearby_stars(center.x, center.y, center.z, NEARBY_RADIUS)
+    _check_star_arrays(stars, "get_nearby_stars")
+    _check(stars["count"] > 0, "stars around a known star")
+    for position in stars["positions"]:
+        _check(position.distance_to(center) <= NEARBY_RADIUS + 0.01, "star %s outside radius" % position)
+
+    var limited := galaxy.get_nearby_stars_limited(center.x, center.y, center.z, NEARBY_RADIUS, 3)
+    _check_star_arrays(limited, "get_nearby_stars_limited")
+    _check(limited["count"] <= 3, "max_stars respected, got %d" % limited["count"])
+
+    var zero := galaxy.get_nearby_stars(center.x, center.y, center.z, 0.0)
+    _check_star_arrays(zero, "zero radius")
+    _check(zero["count"] <= 1, "zero radius finds at most the star itself")
+
+    var negative_radius := galaxy.get_nearby_stars(center.x, center.y, center.z, -10.0)
+    _check_star_arrays(negative_radius, "negative radius")
+    _check(negative_radius["count"] <= 1, "negative radius is treated as zero")
+
+    var huge := galaxy.get_nearby_stars(center.x, center.y, center.z, 1.0e12)
+    _check_star_arrays(huge, "huge radius")
+    for position in huge["positions"]:
+        _check(position.distance_to(center) <= max_radius + 0.01, "huge radius clamped to nearby_max_radius")
+
+    var negative_max := galaxy.get_nearby_stars_limited(center.x, center.y, center.z, NEARBY_RADIUS, -1)
+    _check_error("max_stars must not be negative", "negative max_stars")
+    _check_eq(negative_max["count"], 0, "negative max_stars count")
+
+    # Far outside the galaxy
+    var empty := galaxy.get_nearby_stars(1.0e9, 1.0e9, 1.0e9, NEARBY_RADIUS)
+    _check_star_arrays(empty, "outside the galaxy")
+    _check_eq(empty["count"], 0, "no stars outside the galaxy")
+
+
+## A star fetched by get_structure and by get_nearby_stars must carry the same values.
+func test_star_properties_agree(galaxy: MeiGalaxy) -> void:
+    var structure := galaxy.get_structure(500)
+    var center := _star_center(galaxy)
+    var nearby := galaxy.get_nearby_stars(center.x, center.y, center.z, NEARBY_RADIUS)
+
+    var nearby_index := {}
+    for i in nearby["count"]:
+        nearby_index[nearby["ids"][i]] = i
+    var shared := 0
+    for i in structure["count"]:
+        var j: int = nearby_index.get(structure["ids"][i], -1)
+        if j < 0:
+            continue
+        shared += 1
+        for key in PROPERTY_KEYS:
+            _check_eq(nearby[key][j], structure[key][i], "star %d: %s" % [structure["ids"][i], key])
+        _check_eq(MeiUtils.get_star_type(nearby, j), MeiUtils.get_star_type(structure, i),
+            "star %d: star type" % structure["ids"][i])
+        _check_eq(nearby["spectral_class_names"][nearby["spectral_class_codes"][j]],
+            structure["spectral_class_names"][structure["spectral_class_codes"][i]],
+            "star %d: spectral class" % structure["ids"][i])
+    _check(shared > 0, "structure and nearby queries share a star")
+
+
+func test_star_system(galaxy: MeiGalaxy) -> void:
+    var id := str(_nearby_ids(galaxy, 1)[0])
+
+    var system := galaxy.get_star_system(id)
+    _check_keys(system, SYSTEM_KEYS, "get_star_system")
+    _check_eq(system["star_id"], id, "star_id")
+    _check(system["stars"].size() > 0, "system has stars")
+    _check_keys(system["stars"][0], ["star_type", "spectral_class", "spectral_type", "spectral_subclass",
+        "luminosity_class", "luminosity", "temperature", "radius", "absolute_magnitude", "color"], "system star")
+    for star in system["stars"]:
+        _check(star["color"] is Color, "star color is a Color")
+        if star["spectral_class"] != "":
+            _check(star["spectral_class"].begins_with(star["spectral_type"]), "spectral class %s starts with its type" % star["spectral_class"])
+            _check(star["spectral_class"].ends_with(star["luminosity_class"]), "spectral class %s ends with its luminosity class" % star["spectral_class"])
+    _check(system["configuration"].has("type"), "configuration type")
+    _check_eq(system["name"], galaxy.get_star_name(id), "name matches get_star_name")
+    for comet in system.get("oort_cloud", {}).get("notable_comets", []):
+        var at_epoch: Vector3 = MeiComet.get_comet_state(comet, 0.0)["position"]
+        var position := Vector3(comet["position"]["x"], comet["position"]["y"], comet["position"]["z"])
+        _check(at_epoch.is_equal_approx(position), "comet position %s matches its state at the epoch %s" % [position, at_epoch])
+
+    # Planets are placed on the orbits MeiOrbit draws, around their component
+    var layout := MeiOrbit.get_system_layout(system)
+    var groups: Array = system["stellar_components"] if system["stellar_components"].size() > 0 else [system]
+    for group in groups:
+        var barycenter: Dictionary = group.get("barycenter", {"x": 0.0, "y": 0.0, "z": 0.0})
+        var center := Vector3(barycenter["x"], barycenter["y"], barycenter["z"])
+        for planet in group["inner_planets"] + group["outer_planets"]:
+            var placed: Vector3 = layout["planet_positions"][planet["seed"]]
+            _check(is_equal_approx(placed.distance_to(center), planet["orbital_radius"]),
+                "planet %s placed on its orbit" % planet["designation"])
+
+    # Returned dictionaries are copies of the cached one
+    system["name"] = "Changed"
+    _check_eq(galaxy.get_star_system(id)["name"], galaxy.get_star_name(id), "cache unaffected by edits")
+    _check(galaxy.cache_stats()["hits"] >= 1, "second lookup hits the cache")
+
+
+func test_evolve_star(galaxy: MeiGalaxy) -> void:
+    var id := str(_nearby_ids(galaxy, 1)[0])
+    var now := galaxy.evolve_star(id, 0.0)
+    var star: Dictionary = galaxy.get_star_system(id)["stars"][0]
+    for candidate in galaxy.get_star_system(id)["stars"]:
+        if candidate["id"] == now.get("id"):
+            star = candidate
+    _check_keys(now, ["region", "initial_mass", "current_age", "age", "phase", "exists", "star_type",
+        "spectral_class", "luminosity", "temperature", "radius", "color"], "evolve_star")
+    _check_eq(now["age"], now["current_age"], "age today")
+    _check_eq(now["star_type"], star["star_type"], "star type today")
+    _check_eq(now["luminosity"], star["luminosity"], "luminosity today")
+    _check_eq(now["temperature"], star["temperature"], "temperature today")
+    _check(now["current_age"] > 0.0 and now["current_age"] < 2.0e10, "age %f in range" % now["current_age"])
+    _check_eq(galaxy.evolve_star(id, 0.0), now, "deterministic")
+
+    var before := galaxy.evolve_star(id, -now["current_age"] - 1.0e6)
+    _check_eq(before["exists"], false, "not formed before its age")
+    _check_eq(before["phase"], "Unformed", "unformed phase")
+
+    var far_future := galaxy.evolve_star(id, 1.0e13)
+    _check(far_future["phase"] in ["WhiteDwarf", "NeutronStar", "BlackHole", "BrownDwarf", "MainSequence"],
+        "long-term phase %s" % far_future["phase"])
+
+    _check_eq(galaxy.evolve_star("abc", 0.0), {}, "evolve_star(\"abc\")")
+    _check_error("Invalid star ID", "evolve_star(\"abc\")")
+
+
+func test_star_positions_at_time(galaxy: MeiGalaxy) -> void:
+    var center := _star_center(galaxy)
+    var nearby := galaxy.get_nearby_stars(center.x, center.y, center.z, NEARBY_RADIUS)
+    var ids: PackedInt64Array = nearby["ids"]
+    var today := galaxy.get_star_positions_at_time(ids, 0.0)
+    _check_eq(today, nearby["positions"], "zero time keeps today's positions")
+
+    var later := galaxy.get_star_positions_at_time(ids, 10.0)
+    _check_eq(later.size(), ids.size(), "one position per ID")
+    _check_eq(galaxy.get_star_positions_at_time(ids, 10.0), later, "deterministic")
+    for i in ids.size():
+        var moved := later[i].distance_to(today[i])
+        # 220 km/s for 10 Myr is about 7,300 ly, plus peculiar motion
+        _check(moved > 0.0 and moved < 10000.0, "star %d moved %f ly" % [ids[i], moved])
+
+    # A viewer that never queried these stars places them in the same spots
+    var viewer := _make_galaxy()
+    var elsewhere := viewer.get_star_positions_at_time(ids, 10.0)
+    viewer.queue_free()
+    for i in ids.size():
+        _check(elsewhere[i].is_equal_approx(later[i]), "star %d agrees across viewers" % ids[i])
+
+
+func test_coordinates(galaxy: MeiGalaxy) -> void:
+    var here := galaxy.get_coordinate(41250.3, 12.5, -8000.126)
+    var galactic := here.get_galactic()
+    _check(absf(galactic[0] - 41250.3) < 1e-9 and absf(galactic[2] + 8000.126) < 1e-9, "round trip %s" % galactic)
+    _check_eq(here.get_sector(), Vector3i(165, 0, -33), "sector")
+    _check_eq(here.get_sector_code(), "TJLT+0", "sector code")
+    var moved := here.translated(Vector3(0.001, 0.0, 0.0))
+    _check(absf(moved.relative_to(here).x - 0.001) < 1e-6, "small offset far from the core")
+    _check(here.get_local().x >= 0.0 and here.get_local().x < here.get_cell_size(), "local offset inside the cell")
+
+    # Relative queries match absolute ones, shifted by the origin
+    var center := _star_center(galaxy)
+    var absolute := galaxy.get_nearby_stars(center.x, center.y, center.z, NEARBY_RADIUS)
+    var origin := galaxy.get_coordinate(center.x, center.y, center.z)
+    var relative := galaxy.get_nearby_stars_relative(origin, Vector3.ZERO, NEARBY_RADIUS)
+    _check_eq(relative["origin"], origin, "origin echoed")
+    _check_eq(relative["ids"], absolute["ids"], "same stars")
+    for i in relative["count"]:
+        var expected: Vector3 = absolute["positions"][i] - center
+        _check(relative["positions"][i].distance_to(expected) < 0.01, "star %d relative position" % i)
+
+    var found := galaxy.search_systems(center, NEARBY_RADIUS, {}, 5)
+    var found_relative := galaxy.search_systems_relative(origin, Vector3.ZERO, NEARBY_RADIUS, {}, 5)
+    _check_eq(found_relative["count"], found["count"], "relative search finds the same systems")
+    for i in found["count"]:
+        var match: Dictionary = found["matches"][i]
+        var relative_match: Dictionary = found_relative["matches"][i]
+        _check_eq(relative_match["star_id"], match["star_id"], "relative search order")
+        _check(relative_match["position"].distance_to(match["position"] - center) < 0.01,
+            "relative match %d position" % i)
+
+
+func test_units(_galaxy: MeiGalaxy) -> void:
+    _check_eq(MeiUnits.convert(1.0, "au", "km"), 149597870.7, "au to km")
+    _check(absf(MeiUnits.convert(1.0, "pc", "ly") - 3.26156) < 1e-5, "pc to ly")
+    _check(absf(MeiUnits.convert(1.0, "jupiter_mass", "earth_mass") - 317.83) < 0.01, "jupiter to earth mass")
+    _check(is_nan(MeiUnits.convert(1.0, "furlong", "km")), "unknown unit")
+    _check_error("Unknown unit", "unknown unit")
+    _check(is_nan(MeiUnits.convert(1.0, "kg", "km")), "mismatched quantities")
+    _check_error("Cannot convert", "mismatched quantities")
+
+    var observer := Vector3(26000.0, 20.0, 0.0)
+    var core := MeiUnits.galactic_to_spherical(Vector3(0.0, 20.0, 0.0), observer)
+    _check(absf(core["longitude"]) < 1e-6 and absf(core["latitude"]) < 1e-6, "core at l = 0, b = 0")
+    _check(absf(core["distance"] - 26000.0) < 1e-6, "core distance")
+    var there := MeiUnits.spherical_to_galactic(45.0, -12.0, 300.0, observer)
+    var back := MeiUnits.galactic_to_spherical(there, observer)
+    _check(absf(back["longitude"] - 45.0) < 0.01 and absf(back["latitude"] + 12.0) < 0.01, "spherical round trip")
+
+    var ecliptic := MeiUnits.galactic_to_ecliptic(Vector3(1.0, 2.0, -3.0) / 63241.077)
+    _check(ecliptic.is_equal_approx(Vector3(1.0, 3.0, 2.0)), "ecliptic is Z up: %s" % ecliptic)
+
+
+func test_describe_location(galaxy: MeiGalaxy) -> void:
+    var center := _star_center(galaxy)
+    var reference := center + Vector3(100.0, 0.0, 0.0)
+    var here := galaxy.describe_location(center, reference)
+    _check_keys(here, ["radius", "height", "longitude", "latitude", "distance", "region", "arm", "arm_name",
+        "arm_distance", "sector", "metallicity", "stellar_density"], "describe_location")
+    _check(absf(here["radius"] - Vector2(center.x, center.z).length()) < 0.01, "radius")
+    _check(absf(here["distance"] - 100.0) < 0.01, "distance from the reference")
+    _check(here["region"] in ["Bulge", "Bar", "Arm", "Disk", "ThickDisk", "Halo"], "region %s" % here["region"])
+    _check(here["stellar_density"] > 0.0, "a populated spot has stars")
+    _check_eq(here["sector"], galaxy.get_coordinate(center.x, center.y, center.z).get_sector_code(), "sector")
+    _check_eq(here["arm_name"] == "", here["arm"] < 0, "arm name given with the arm")
+
+    var core := galaxy.describe_location(Vector3.ZERO, reference)
+    _check_eq(core["region"], "Bulge", "core region")
+    _check_eq(core["arm"], -1, "no arm in the bulge")
+    var halo := galaxy.describe_location(Vector3(0.0, 0.9 * galaxy.get_galaxy_radius(), 0.0), reference)
+    _check_eq(halo["region"], "Halo", "far above the disk")
+    _check(halo["metallicity"] < core["metallicity"], "halo is metal-poor")
+
+
+func test_morphology(galaxy: MeiGalaxy) -> void:
+    var morphology := galaxy.get_morphology()
+    _check_keys(morphology, ["radius", "arm_count", "arm_names", "arm_pitch", "arm_start_radius", "arm_half_width",
+        "bar_length", "bar_width", "bar_angle", "bulge_radius", "thin_disk_height", "thick_disk_height"], "get_morphology")
+    _check_eq(morphology["radius"], galaxy.get_galaxy_radius(), "radius")
+    _check_eq(morphology["arm_names"].size(), morphology["arm_count"], "one name per arm")
+    _check_eq(morphology["arm_start_radius"], morphology["bar_length"] / 2.0, "arms start at the ends of the bar")
+
+    for arm in morphology["arm_count"]:
+        var spline := galaxy.get_arm_spline(arm, 32)
+        _check_eq(spline.size(), 32, "arm %d samples" % arm)
+        var start := Vector2(spline[0].x, spline[0].z).length()
+        var end := Vector2(spline[31].x, spline[31].z).length()
+        _check(absf(start - morphology["arm_start_radius"]) < 1.0, "arm %d starts at the bar" % arm)
+        _check(absf(end - morphology["radius"]) < 1.0, "arm %d ends at the edge" % arm)
+        # The arm's own center line, as describe_location sees it
+        var middle := galaxy.describe_location(spline[16], Vector3.ZERO)
+        _check_eq(middle["arm"], arm, "arm %d nearest to its spline" % arm)
+        _check_eq(middle["arm_name"], morphology["arm_names"][arm], "arm %d name" % arm)
+
+    _check_eq(galaxy.get_arm_spline(morphology["arm_count"], 8), PackedVector3Array(), "arm out of range")
+    _check_error("arm_index", "arm out of range")
+    _check_eq(galaxy.get_arm_spline(0, 1), PackedVector3Array(), "too few samples")
+    _check_error("samples", "too few samples")
+
+
+func test_invalid_star_ids(galaxy: MeiGalaxy) -> void:
+    for id in ["", "abc", "12abc", " 1", "1.5", "99999999999999999999999"]:
+        _check_eq(galaxy.get_star_system(id), {}, "get_star_system(\"%s\")" % id)
+        _check_error("Invalid star ID", "get_star_system(\"%s\")" % id)
+        _check_eq(galaxy.get_star_designation(id), {}, "get_star_designation(\"%s\")" % id)
+        _check_error("Invalid star ID", "get_star_designation(\"%s\")" % id)
+        _check_eq(galaxy.get_body_designation(id, 0, -1), "", "get_body_designation(\"%s\")" % id)
+        _check_error("Invalid star ID", "get_body_designation(\"%s\")" % id)
+    _check_eq(galaxy.cache_stats()["entries"], 0, "invalid IDs are not cached")
+
+
+func test_designations(galaxy: MeiGalaxy) -> void:
+    var id := str(_nearby_ids(galaxy, 1)[0])
+    var system := galaxy.get_star_system(id)
+
+    var names := galaxy.get_star_designation(id)
+    _check_keys(names, ["name", "designation", "sector", "constellation", "bayer"], "get_star_designation")
+    _check_eq(names["designation"], system["designation"], "designation matches the system")
+    _check(names["designation"].contains(names["sector"]), "designation contains the sector code")
+
+    var designation: String = system["designation"]
+    _check_eq(galaxy.get_body_designation(id, -1, -1), designation, "star designation")
+    var lettered := {}
+    for planet in system["inner_planets"] + system["outer_planets"]:
+        lettered[planet["letter"]] = planet
+    for component in system["stellar_components"]:
+        for planet in component["inner_planets"] + component["outer_planets"]:
+            lettered[planet["letter"]] = planet
+    if lettered.has("b"):
+        _check_eq(galaxy.get_body_designation(id, 0, -1), designation + " b", "first planet")
+        var moons: int = lettered["b"]["moon_count"]
+        if moons > 0:
+            _check_eq(galaxy.get_body_designation(id, 0, 0), designation + " b I", "first moon of the first planet")
+        _check_eq(galaxy.get_body_designation(id, 0, moons), "", "moon past the last")
+        _check_error("moons", "moon past the last")
+    _check_eq(galaxy.get_body_designation(id, lettered.size(), -1), "", "planet past the last")
+    _check_error("planets", "planet past the last")
+
+    # Planets that only orbit a stellar component are lettered too, and no
+    # two distinct planets share a letter
+    for candidate in _nearby_ids(galaxy, 50):
+        var candidate_system := galaxy.get_star_system(str(candidate))
+        var planets: Array = candidate_system["inner_planets"] + candidate_system["outer_planets"]
+        for component in candidate_system["stellar_components"]:
+            planets += component["inner_planets"] + component["outer_planets"]
+        var planet_by_letter := {}
+        for planet in planets:
+            _check(planet["letter"] != "", "planet %s has a letter" % planet["designation"])
+            var key := [planet["orbital_radius"], planet["mass"]]
+            _check_eq(planet_by_letter.get(planet["letter"], key), key, "letter %s names one planet" % planet["designation"])
+            planet_by_letter[planet["letter"]] = key
+
+
+func test_resolve_designation(galaxy: MeiGalaxy) -> void:
+    var center := _star_center(galaxy)
+    var id := str(_nearby_ids(galaxy, 1)[0])
+    var system := galaxy.get_star_system(id)
+
+    var found := galaxy.resolve_designation(system["designation"], Vector3.ZERO, 0.0)
+    _check_eq(found["ok"], true, "catalog designation resolves anywhere")
+    _check_eq(found["kind"], "catalog", "kind")
+    _check_eq(found["star_id"], id, "catalog star_id")
+    _check_eq(found["planet_index"], -1, "no planet")
+
+    var by_name := galaxy.resolve_designation(system["name"], center, NEARBY_RADIUS)
+    _check_eq(by_name["ok"], true, "proper name resolves near the region center")
+    _check_eq(by_name["kind"], "name", "name kind")
+
+    for bad in [["", "empty"], ["Sector", "missing_sector_code"], ["Sector ???", "bad_sector_code"]]:
+        var result := galaxy.resolve_designation(bad[0], center, NEARBY_RADIUS)
+        _check_eq(result["ok"], false, "resolve(\"%s\")" % bad[0])
+        _check_eq(result["error"]["code"], bad[1], "resolve(\"%s\") error code" % bad[0])
+
+    var unknown := galaxy.resolve_designation("Zzyzxqwv", center, NEARBY_RADIUS)
+    _check_eq(unknown["ok"], false, "unknown name")
+    _check_eq(unknown["error"]["code"], "not_found", "unknown name error code")
+    _check(unknown["suggestions"] is Array, "suggestions array")
+
+    var nowhere := galaxy.resolve_designation(system["name"], Vector3(1.0e9, 1.0e9, 1.0e9), -5.0)
+    _check_eq(nowhere["ok"], false, "names are not found outside the region")
+
+    galaxy.resolve_designation(system["name"], center, 500.0)
+    _check_error("clamped to nearby_max_radius", "wide name searches report the clamp")
+
+    # Bright-star labels resolve back to the star they were given to
+    for candidate in _nearby_ids(galaxy, 50):
+        var bayer: String = galaxy.get_star_designation(str(candidate))["bayer"]
+        if bayer != "":
+            var resolved := galaxy.resolve_designation(bayer, center, NEARBY_RADIUS)
+            _check_eq(resolved["ok"], true, "resolve(\"%s\")" % bayer)
+            if resolved["ok"]:
+                # Another star may share the label; it must at least carry it too
+                _check_eq(galaxy.get_star_designation(resolved["star_id"])["bayer"], bayer, "resolved star's label")
+
+
+func test_search_systems(galaxy: MeiGalaxy) -> void:
+    var center := _star_center(galaxy)
+
+    var found := galaxy.search_systems(center, NEARBY_RADIUS, {}, 5)
+    _check_keys(found, ["matches", "count", "scanned", "failed"], "search_systems")
+    _check(found["count"] > 0 and found["count"] <= 5, "limit respected, got %d" % found["count"])
+    var last := 0.0
+    for entry in found["matches"]:
+        _check(entry["distance"] >= last, "matches sorted nearest first")
+        last = entry["distance"]
+
+    var typo := galaxy.search_systems(center, NEARBY_RADIUS, {"planet_typse": PackedStringArray(["Ocean"])}, 5)
+    _check_error("unknown filter keys", "filter typo")
+    _check_keys(typo, ["matches", "count", "scanned"], "filter typo still searches")
+
+    _check_eq(galaxy.search_systems(center, NEARBY_RADIUS, {}, -1)["count"], 0, "negative limit")
+    _check(galaxy.search_systems(center, 0.0, {"oort_cloud": true}, 5)["count"] <= 1, "zero radius")
+    _check_eq(galaxy.search_systems(center, 1.0e12, {}, 1)["count"], 1, "huge radius")
+
+    # Plain arrays and whole floats (e.g. from JSON) filter like their packed/int forms
+    var packed := galaxy.search_systems(center, NEARBY_RADIUS, {"planet_types": PackedStringArray(["GasGiant"]), "min_planets": 2}, 5)
+    var plain := galaxy.search_systems(center, NEARBY_RADIUS, {"planet_types": ["GasGiant"], "min_planets": 2.0}, 5)
+    _check_eq(plain["matches"], packed["matches"], "Array and float filter values")
+    galaxy.search_systems(center, NEARBY_RADIUS, {"min_planets": "two", "oort_cloud": 1}, 5)
+    _check_error("wrong type", "wrongly typed filter values are reported")
+
+
+func test_batches(galaxy: MeiGalaxy) -> void:
+    var ids := _nearby_ids(galaxy, 3)
+
+    var summaries := galaxy.get_star_systems_batch(ids, MeiGalaxy.DETAIL_SUMMARY)
+    _check_eq(summaries.size(), ids.size(), "one summary per id")
+    for i in summaries.size():
+        _check_eq(summaries[i]["ok"], true, "summary ok")
+        _check_eq(summaries[i]["star_id"], str(ids[i]), "summaries keep id order")
+        _check_keys(summaries[i], ["name", "designation", "planet_count", "configuration_type"], "summary")
+        _check(not summaries[i].has("planets"), "summary has no planets")
+
+    for entry in galaxy.get_star_systems_batch(ids, MeiGalaxy.DETAIL_PLANETS):
+        _check(entry.has("planets"), "planet detail has planets")
+        for planet in entry["planets"]:
+            _check(not planet.has("moons"), "planet detail has no moons")
+
+    for entry in galaxy.get_star_systems_batch(ids, MeiGalaxy.DETAIL_FULL):
+        _check_keys(entry, SYSTEM_KEYS, "full detail")
+        _check_eq(entry, galaxy.get_star_system(entry["star_id"]).merged({"ok": true}), "full detail matches get_star_system")
+
+    _check_eq(galaxy.get_star_systems_batch(PackedInt64Array(), MeiGalaxy.DETAIL_FULL).size(), 0, "empty batch")
+
+    var packed := galaxy.get_system_summaries(ids)
+    _check_eq(packed["count"], ids.size(), "packed summary count")
+    for key in ["ids", "valid", "configurations", "planet_counts", "moon_counts", "belt_counts",
+            "habitable", "oort_clouds", "dominant_planet_types"]:
+        _check_eq(packed[key].size(), ids.size(), "packed %s size" % key)
+    _check_eq(packed["configuration_names"].size(), 5, "configuration names")
+    for i in ids.size():
+        _check_eq(packed["valid"][i], 1, "packed summary valid")
+        _check_eq(packed["planet_counts"][i], summaries[i]["planet_count"], "packed planet count")
+    _check_eq(galaxy.get_system_summaries(PackedInt64Array())["count"], 0, "empty summaries")
+
+
+func test_config(galaxy: MeiGalaxy) -> void:
+    var fingerprint := galaxy.get_config_fingerprint()
+    _check(fingerprint.begins_with("mei1-"), "fingerprint format, got %s" % fingerprint)
+
+    var fields := galaxy.get_config_fields()
+    _check_eq(fields.size(), 6, "config field count")
+    for field in fields:
+        _check_keys(field, ["name", "value", "role", "affects_generation"], "config field")
+
+    galaxy.set_nearby_max_radius(5.0)
+    _check_eq(galaxy.get_nearby_max_radius(), 5.0, "set_nearby_max_radius")
+    _check_eq(galaxy.get_config_fingerprint(), fingerprint, "query fields don't change the fingerprint")
+
+    galaxy.set_structure_block_size(50.0)
+    galaxy.set_structure_samples_per_block(2)
+    _check_eq(galaxy.get_config_fingerprint(), fingerprint, "sampling fields don't change the fingerprint")
+    _check(galaxy.get_structure(100)["count"] > 0, "structure after sampling changes")
+
+    galaxy.set_galaxy_seed(SEED + 1)
+    _check(galaxy.get_config_fingerprint() != fingerprint, "seed changes the fingerprint")
+    galaxy.set_galaxy_seed(SEED)
+
+    galaxy.load_config(ProjectSettings.globalize_path("res://generator_config.toml"))
+    _check_eq(galaxy.get_nearby_max_radius(), 32.0, "load_config reads nearby_max_radius")
+    _check(galaxy.get_galaxy_radius() > 0.0, "galaxy radius after load_config")
+
+
+func test_caches(galaxy: MeiGalaxy) -> void:
+    var ids := _nearby_ids(galaxy, 3)
+    galaxy.clear_system_cache()
+    var before: Dictionary = galaxy.cache_stats()
+    _check_keys(before, ["hits", "misses", "evictions", "entries", "capacity", "memory_bytes"], "cache_stats")
+    _check_eq(before["entries"], 0, "cleared cache is empty")
+
+    galaxy.system_cache_size = 2
+    for id in ids:
+        galaxy.get_star_system(str(id))
+    var after := galaxy.cache_stats()
+    _check_eq(after["capacity"], 2, "capacity follows system_cache_size")
+    _check(after["entries"] <= 2, "cache stays within capacity")
+    _check(after["memory_bytes"] > 0, "memory estimate")
+
+    var cached_id: int = ids[ids.size() - 1]
+    var batch := galaxy.get_star_systems_batch(PackedInt64Array([cached_id, ids[0]]), MeiGalaxy.DETAIL_FULL)
+    galaxy.get_system_summaries(PackedInt64Array([cached_id, ids[0]]))
+    galaxy.search_systems(_star_center(galaxy), NEARBY_RADIUS, {}, 5)
+    var probed := galaxy.cache_stats()
+    _check_eq([probed["hits"], probed["misses"]], [after["hits"], after["misses"]],
+        "batches, summaries and searches only peek at the system cache")
+    _check_eq(batch[0], galaxy.get_star_system(str(cached_id)).merged({"ok": true}), "cached batch entry")
+    galaxy.get_star_system("0%d" % cached_id)
+    _check_eq(galaxy.cache_stats()["hits"], probed["hits"] + 2, "leading zeros name the same cached system")
+
+    var style := MeiNameStyle.new()
+    galaxy.name_style = style
+    var styled_name: String = galaxy.get_star_system(str(cached_id))["name"]
+    style.prefixes = PackedStringArray(["Qx"])
+    _check(galaxy.get_star_system(str(cached_id))["name"] != styled_name, "editing the name style refreshes cached names")
+    galaxy.name_style = null
+
+    galaxy.system_cache_size = 0
+    galaxy.get_star_system(str(ids[0]))
+    _check_eq(galaxy.cache_stats()["entries"], 0, "zero capacity disables the cache")
+
+    galaxy.get_structure(50)
+    _check(galaxy.clear_structure_cache() >= 1, "structure cache files removed")
+    _check_eq(galaxy.clear_structure_cache(), 0, "nothing left to remove")